@@ -1,13 +1,14 @@
 //! Procedural macros for sql-check.
 //!
-//! Provides the `query!` macro for compile-time SQL validation.
+//! Provides the `query!` macro for compile-time SQL validation, and the
+//! `pg_enum!` macro for generating Rust enums from Postgres enum types.
 
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use sql_check::{validate_query, Schema};
-use std::path::PathBuf;
-use syn::{parse::Parse, parse::ParseStream, parse_macro_input, Expr, LitStr, Token};
+use std::path::{Path, PathBuf};
+use syn::{parse::Parse, parse::ParseStream, parse_macro_input, Expr, Ident, LitStr, Token};
 
 /// Input for the query! macro: SQL string followed by optional parameters.
 struct QueryInput {
@@ -35,29 +36,238 @@ impl Parse for QueryInput {
     }
 }
 
-/// Get the schema file path from environment or default.
-fn get_schema_path() -> PathBuf {
-    if let Ok(path) = std::env::var("SQL_CHECK_SCHEMA") {
-        PathBuf::from(path)
+/// Input for the pg_enum!/pg_composite!/pg_domain! macros: the Postgres type name, either as an
+/// identifier (`mood`) or a string for schema-qualified names (`"public.mood"`).
+struct PgTypeInput {
+    name: String,
+    span: proc_macro2::Span,
+}
+
+impl Parse for PgTypeInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(LitStr) {
+            let lit: LitStr = input.parse()?;
+            Ok(PgTypeInput {
+                name: lit.value(),
+                span: lit.span(),
+            })
+        } else {
+            let ident: Ident = input.parse()?;
+            Ok(PgTypeInput {
+                name: ident.to_string(),
+                span: ident.span(),
+            })
+        }
+    }
+}
+
+/// Get the schema file paths from environment or default.
+///
+/// `SQL_CHECK_SCHEMA` is a comma-separated list of files and globs
+/// (`schema/*.sql`), applied in order.
+fn get_schema_paths() -> Result<Vec<PathBuf>, String> {
+    if let Ok(paths) = std::env::var("SQL_CHECK_SCHEMA") {
+        let mut expanded = Vec::new();
+        for path in paths.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            if path.contains(['*', '?', '[']) {
+                expanded.extend(sql_check::schema::glob_paths(path).map_err(|e| e.to_string())?);
+            } else {
+                expanded.push(PathBuf::from(path));
+            }
+        }
+        Ok(expanded)
     } else if let Ok(manifest_dir) = std::env::var("CARGO_MANIFEST_DIR") {
-        PathBuf::from(manifest_dir).join("schema.sql")
+        Ok(vec![PathBuf::from(manifest_dir).join("schema.sql")])
     } else {
-        PathBuf::from("schema.sql")
+        Ok(vec![PathBuf::from("schema.sql")])
+    }
+}
+
+/// Get the migrations directory from environment, if configured.
+///
+/// When `SQL_CHECK_MIGRATIONS` is set, the schema is built by replaying the
+/// migrations in that directory instead of reading a schema file.
+fn get_migrations_path() -> Option<PathBuf> {
+    std::env::var("SQL_CHECK_MIGRATIONS")
+        .ok()
+        .map(PathBuf::from)
+}
+
+/// Get the search path queries are checked with from `SQL_CHECK_SEARCH_PATH`.
+///
+/// The value is a comma-separated list of schemas, like Postgres'
+/// `search_path` (for example `app, public`). Defaults to `public`.
+fn get_search_path() -> Option<Vec<String>> {
+    std::env::var("SQL_CHECK_SEARCH_PATH").ok().map(|path| {
+        path.split(',')
+            .map(|namespace| namespace.trim().trim_matches('"').to_string())
+            .filter(|namespace| !namespace.is_empty())
+            .collect()
+    })
+}
+
+/// Whether schema files are loaded tolerantly, from `SQL_CHECK_SCHEMA_TOLERANT`
+/// (`1` or `true`; off by default).
+///
+/// Tolerant loading skips statements that don't affect tables (see
+/// `Schema::from_files_tolerant`), which a full pg_dump needs, but also hides
+/// mistakes like a duplicate view, so it must be asked for.
+fn load_tolerantly() -> Result<bool, String> {
+    match std::env::var("SQL_CHECK_SCHEMA_TOLERANT") {
+        Err(_) => Ok(false),
+        Ok(value) => match value.trim() {
+            "" | "0" | "false" => Ok(false),
+            "1" | "true" => Ok(true),
+            other => Err(format!(
+                "Invalid SQL_CHECK_SCHEMA_TOLERANT `{}`, expected `1` or `true`",
+                other
+            )),
+        },
+    }
+}
+
+/// The type overrides file, `sql-check-types.txt` in the crate root.
+fn get_type_overrides_file() -> Option<PathBuf> {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").ok()?;
+    Some(PathBuf::from(manifest_dir).join("sql-check-types.txt"))
+}
+
+/// Get the type overrides from the type overrides file, then from
+/// `SQL_CHECK_TYPE_OVERRIDES`, so the variable wins.
+///
+/// Each is a list of `target = rust::Type` pairs, where the target is a
+/// column (`users.id`) or a Postgres type (`money_cents`, `uuid[]`), for
+/// example `users.id = crate::ids::UserId, bigint = Cents`. The file has one
+/// pair per line and `#` comments; the variable separates them with commas.
+fn get_type_overrides() -> Result<Vec<(String, String)>, String> {
+    let mut overrides = Vec::new();
+    if let Some(path) = get_type_overrides_file().filter(|path| path.is_file()) {
+        overrides.extend(read_type_overrides_file(&path)?);
+    }
+    if let Ok(value) = std::env::var("SQL_CHECK_TYPE_OVERRIDES") {
+        overrides.extend(parse_type_overrides(&value, "SQL_CHECK_TYPE_OVERRIDES")?);
     }
+    Ok(overrides)
 }
 
-/// Load and parse the schema file.
+/// Read a type overrides file: one `target = rust::Type` pair per line, with
+/// `#` comments.
+fn read_type_overrides_file(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let entries = content
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(entry, _)| entry))
+        .collect::<Vec<_>>()
+        .join(",");
+    parse_type_overrides(&entries, &path.display().to_string())
+}
+
+/// Parse comma-separated `target = rust::Type` pairs read from `source`.
+fn parse_type_overrides(value: &str, source: &str) -> Result<Vec<(String, String)>, String> {
+    // Commas inside generics (`HashMap<String, i64>`) don't end an override
+    let mut entries = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in value.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                entries.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(&value[start..]);
+
+    let mut overrides = Vec::new();
+    for entry in entries.into_iter().map(str::trim).filter(|e| !e.is_empty()) {
+        let (target, rust_type) = entry.split_once('=').ok_or_else(|| {
+            format!(
+                "Invalid type override `{}` in {}, expected `target = rust::Type`",
+                entry, source
+            )
+        })?;
+        let rust_type = rust_type.trim();
+        syn::parse_str::<syn::Type>(rust_type)
+            .map_err(|e| format!("Invalid Rust type `{}` in {}: {}", rust_type, source, e))?;
+        overrides.push((target.trim().to_string(), rust_type.to_string()));
+    }
+    Ok(overrides)
+}
+
+/// Items making Cargo rebuild the crate when the type overrides or tolerant
+/// schema loading change.
+///
+/// Proc macros can't tell Cargo what they read, but `option_env!` and
+/// `include_bytes!` in the generated code can.
+fn track_config() -> TokenStream2 {
+    let file = get_type_overrides_file()
+        .filter(|path| path.is_file())
+        .map(|path| path.display().to_string());
+    let file = file.iter();
+    quote! {
+        const _: ::core::option::Option<&str> = ::core::option_env!("SQL_CHECK_TYPE_OVERRIDES");
+        const _: ::core::option::Option<&str> = ::core::option_env!("SQL_CHECK_SCHEMA_TOLERANT");
+        #(const _: &[u8] = ::core::include_bytes!(#file);)*
+    }
+}
+
+/// Load the schema and apply the configured search path and type overrides.
 fn load_schema() -> Result<Schema, String> {
-    let path = get_schema_path();
+    let mut schema = read_schema()?;
+    if let Some(path) = get_search_path() {
+        schema.set_search_path(path);
+    }
+    for (target, rust_type) in get_type_overrides()? {
+        schema
+            .add_type_override(&target, &rust_type)
+            .map_err(|e| format!("Invalid type override for {}: {}", target, e))?;
+    }
+    Ok(schema)
+}
+
+/// Load and parse the schema file (or replay the migrations directory).
+fn read_schema() -> Result<Schema, String> {
+    if let Some(dir) = get_migrations_path() {
+        if !dir.is_dir() {
+            return Err(format!(
+                "Migrations directory not found: {}. Check the SQL_CHECK_MIGRATIONS env var.",
+                dir.display()
+            ));
+        }
+
+        return Schema::from_migrations(&dir)
+            .map_err(|e| format!("Failed to build schema from migrations: {}", e));
+    }
+
+    let paths = get_schema_paths()?;
 
-    if !path.exists() {
+    if let Some(path) = paths.iter().find(|path| !path.exists()) {
         return Err(format!(
             "Schema file not found: {}. Set SQL_CHECK_SCHEMA env var or create schema.sql in your crate root.",
             path.display()
         ));
     }
 
-    Schema::from_file(&path).map_err(|e| format!("Failed to parse schema: {}", e))
+    match paths.as_slice() {
+        // A JSON snapshot (see `Schema::to_json`) loads without re-parsing the DDL
+        [path] if path.extension().is_some_and(|ext| ext == "json") => {
+            std::fs::read_to_string(path)
+                .map_err(sql_check::Error::from)
+                .and_then(|json| Schema::from_json(&json))
+                .map_err(|e| format!("Failed to load schema snapshot: {}", e))
+        }
+        // A full pg_dump has statements (policies, OWNER TO, ...) that don't
+        // matter to queries; tolerant loading skips them, with warnings that a
+        // proc macro has nowhere to report
+        paths if load_tolerantly()? => Schema::from_files_tolerant(paths)
+            .map(|(schema, _)| schema)
+            .map_err(|e| format!("Failed to parse schema: {}", e)),
+        paths => Schema::from_files(paths).map_err(|e| format!("Failed to parse schema: {}", e)),
+    }
 }
 
 /// The `query!` macro validates SQL at compile time and generates typed code.
@@ -131,8 +341,141 @@ pub fn query(input: TokenStream) -> TokenStream {
 
     // Generate the output
     let generated = generate_query_code(&sql, &result, &params);
+    let tracking = track_config();
+
+    quote! {
+        {
+            #tracking
+            #generated
+        }
+    }
+    .into()
+}
+
+/// The `pg_enum!` macro generates a Rust enum for a Postgres enum type in the schema.
+///
+/// # Example
+///
+/// ```ignore
+/// // CREATE TYPE ticket_status AS ENUM ('open', 'in_progress', 'closed');
+/// sql_check_macros::pg_enum!(ticket_status);
+///
+/// // Generates `pub enum TicketStatus { Open, InProgress, Closed }` with
+/// // tokio_postgres `ToSql`/`FromSql` implementations.
+/// let q = query!("SELECT id FROM tickets WHERE status = $1", TicketStatus::Open);
+/// ```
+///
+/// Variants mirror the enum's labels in order. Columns of the enum type are
+/// typed as the generated enum by `query!`, so it must be in scope there.
+#[proc_macro]
+pub fn pg_enum(input: TokenStream) -> TokenStream {
+    let enum_input = parse_macro_input!(input as PgTypeInput);
+
+    let schema = match load_schema() {
+        Ok(s) => s,
+        Err(e) => {
+            return syn::Error::new(enum_input.span, e)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let Some(enum_type) = schema.get_enum(&enum_input.name) else {
+        return syn::Error::new(
+            enum_input.span,
+            format!("Unknown enum type: {}", enum_input.name),
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    match generate_enum_code(enum_type) {
+        Ok(generated) => generated.into(),
+        Err(e) => syn::Error::new(enum_input.span, e)
+            .to_compile_error()
+            .into(),
+    }
+}
+
+/// The `pg_composite!` macro generates a Rust struct for a Postgres composite type in the schema.
+///
+/// # Example
+///
+/// ```ignore
+/// // CREATE TYPE postal_address AS (street TEXT, city TEXT, postal_code TEXT);
+/// sql_check_macros::pg_composite!(postal_address);
+///
+/// // Generates `pub struct PostalAddress { pub street: Option<String>, ... }`
+/// // with tokio_postgres `ToSql`/`FromSql` implementations.
+/// let q = query!("SELECT address FROM warehouses");
+/// ```
+///
+/// Fields mirror the type's attributes in order and are always `Option`, since
+/// Postgres doesn't allow NOT NULL on composite attributes. Enum and composite
+/// fields need their own `pg_enum!`/`pg_composite!` in scope.
+#[proc_macro]
+pub fn pg_composite(input: TokenStream) -> TokenStream {
+    let type_input = parse_macro_input!(input as PgTypeInput);
+
+    let schema = match load_schema() {
+        Ok(s) => s,
+        Err(e) => {
+            return syn::Error::new(type_input.span, e)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let Some(composite) = schema.get_composite(&type_input.name) else {
+        return syn::Error::new(
+            type_input.span,
+            format!("Unknown composite type: {}", type_input.name),
+        )
+        .to_compile_error()
+        .into();
+    };
+
+    generate_composite_code(&schema, composite).into()
+}
+
+/// The `pg_domain!` macro generates a Rust newtype for a Postgres domain in the schema.
+///
+/// # Example
+///
+/// ```ignore
+/// // CREATE DOMAIN email_address AS text NOT NULL CHECK (VALUE LIKE '%@%');
+/// sql_check_macros::pg_domain!(email_address);
+///
+/// // Generates `pub struct EmailAddress(pub String)` with tokio_postgres
+/// // `ToSql`/`FromSql` implementations and `From` conversions.
+/// let q = query!("INSERT INTO contacts (id, email) VALUES ($1, $2)", id, EmailAddress::from(email));
+/// ```
+///
+/// `query!` types domain columns as the domain's base type, so the newtype is
+/// opt-in: use it for parameters, or convert results with `From`.
+#[proc_macro]
+pub fn pg_domain(input: TokenStream) -> TokenStream {
+    let type_input = parse_macro_input!(input as PgTypeInput);
+
+    let schema = match load_schema() {
+        Ok(s) => s,
+        Err(e) => {
+            return syn::Error::new(type_input.span, e)
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let Some(domain) = schema.get_domain(&type_input.name) else {
+        return syn::Error::new(
+            type_input.span,
+            format!("Unknown domain: {}", type_input.name),
+        )
+        .to_compile_error()
+        .into();
+    };
 
-    generated.into()
+    generate_domain_code(domain).into()
 }
 
 /// Count the number of $N placeholders in SQL.
@@ -173,7 +516,12 @@ fn generate_query_code(
         .map(|col| {
             let name = format_ident!("{}", sanitize_field_name(&col.name));
             let ty = rust_type_to_tokens(&col.rust_type);
-            quote! { pub #name: #ty }
+            // Surface the column's COMMENT ON text as the field's docs
+            let doc = col.comment.iter().map(|comment| format!(" {comment}"));
+            quote! {
+                #(#[doc = #doc])*
+                pub #name: #ty
+            }
         })
         .collect();
 
@@ -233,6 +581,304 @@ fn generate_query_code(
     }
 }
 
+/// Generate a Rust enum with `ToSql`/`FromSql` implementations for a Postgres enum.
+fn generate_enum_code(enum_type: &sql_check::EnumType) -> Result<TokenStream2, String> {
+    let enum_name = format_ident!("{}", enum_type.rust_name());
+    let pg_name = sql_check::types::unqualified_name(&enum_type.name);
+
+    let mut variants = Vec::new();
+    for label in &enum_type.labels {
+        let variant = format_ident!("{}", sql_check::types::to_pascal_case(label));
+        if variants.contains(&variant) {
+            return Err(format!(
+                "Enum labels of {} map to the same Rust variant {}",
+                enum_type.name, variant
+            ));
+        }
+        variants.push(variant);
+    }
+    let labels = &enum_type.labels;
+    let doc = format!("Rust mirror of the Postgres enum `{}`.", enum_type.name);
+    let unknown_label = format!("unknown label '{{}}' for enum {}", enum_type.name);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum #enum_name {
+            #(#variants),*
+        }
+
+        impl #enum_name {
+            /// The Postgres label for this variant.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    #(#enum_name::#variants => #labels),*
+                }
+            }
+        }
+
+        impl ::tokio_postgres::types::ToSql for #enum_name {
+            fn to_sql(
+                &self,
+                _ty: &::tokio_postgres::types::Type,
+                out: &mut ::tokio_postgres::types::private::BytesMut,
+            ) -> ::std::result::Result<
+                ::tokio_postgres::types::IsNull,
+                Box<dyn ::std::error::Error + Sync + Send>,
+            > {
+                out.extend_from_slice(self.as_str().as_bytes());
+                Ok(::tokio_postgres::types::IsNull::No)
+            }
+
+            fn accepts(ty: &::tokio_postgres::types::Type) -> bool {
+                ty.name() == #pg_name
+                    && matches!(ty.kind(), ::tokio_postgres::types::Kind::Enum(_))
+            }
+
+            ::tokio_postgres::types::to_sql_checked!();
+        }
+
+        impl<'a> ::tokio_postgres::types::FromSql<'a> for #enum_name {
+            fn from_sql(
+                _ty: &::tokio_postgres::types::Type,
+                raw: &'a [u8],
+            ) -> ::std::result::Result<Self, Box<dyn ::std::error::Error + Sync + Send>> {
+                match ::std::str::from_utf8(raw)? {
+                    #(#labels => Ok(#enum_name::#variants),)*
+                    other => Err(format!(#unknown_label, other).into()),
+                }
+            }
+
+            fn accepts(ty: &::tokio_postgres::types::Type) -> bool {
+                ty.name() == #pg_name
+                    && matches!(ty.kind(), ::tokio_postgres::types::Kind::Enum(_))
+            }
+        }
+    })
+}
+
+/// Generate a Rust struct with `ToSql`/`FromSql` implementations for a Postgres composite type.
+///
+/// Uses the binary record format: a field count, then for each field its type
+/// oid and a length-prefixed value (length -1 for NULL).
+fn generate_composite_code(schema: &Schema, composite: &sql_check::CompositeType) -> TokenStream2 {
+    let struct_name = format_ident!("{}", composite.rust_name());
+    let pg_name = sql_check::types::unqualified_name(&composite.name);
+    let field_count = composite.fields.len();
+
+    let field_idents: Vec<Ident> = composite
+        .fields
+        .iter()
+        .map(|f| format_ident!("{}", sanitize_field_name(&f.name)))
+        .collect();
+    let field_types: Vec<TokenStream2> = composite
+        .fields
+        .iter()
+        .map(|f| rust_type_to_tokens(&schema.rust_type(&f.data_type).nullable()))
+        .collect();
+    let field_names: Vec<&str> = composite.fields.iter().map(|f| f.name.as_str()).collect();
+    let indices: Vec<usize> = (0..field_count).collect();
+
+    let doc = format!(
+        "Rust mirror of the Postgres composite type `{}`.",
+        composite.name
+    );
+    let not_composite = format!("{} is not a composite type", composite.name);
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct #struct_name {
+            #(pub #field_idents: #field_types),*
+        }
+
+        impl ::tokio_postgres::types::ToSql for #struct_name {
+            fn to_sql(
+                &self,
+                ty: &::tokio_postgres::types::Type,
+                out: &mut ::tokio_postgres::types::private::BytesMut,
+            ) -> ::std::result::Result<
+                ::tokio_postgres::types::IsNull,
+                Box<dyn ::std::error::Error + Sync + Send>,
+            > {
+                let ::tokio_postgres::types::Kind::Composite(fields) = ty.kind() else {
+                    return Err(#not_composite.into());
+                };
+                out.extend_from_slice(&(#field_count as i32).to_be_bytes());
+                #(
+                    out.extend_from_slice(&fields[#indices].type_().oid().to_be_bytes());
+                    let base = out.len();
+                    out.extend_from_slice(&[0; 4]);
+                    let len = match ::tokio_postgres::types::ToSql::to_sql(
+                        &self.#field_idents,
+                        fields[#indices].type_(),
+                        out,
+                    )? {
+                        ::tokio_postgres::types::IsNull::Yes => -1,
+                        ::tokio_postgres::types::IsNull::No => {
+                            i32::try_from(out.len() - base - 4)?
+                        }
+                    };
+                    out[base..base + 4].copy_from_slice(&len.to_be_bytes());
+                )*
+                Ok(::tokio_postgres::types::IsNull::No)
+            }
+
+            fn accepts(ty: &::tokio_postgres::types::Type) -> bool {
+                if ty.name() != #pg_name {
+                    return false;
+                }
+                match ty.kind() {
+                    ::tokio_postgres::types::Kind::Composite(fields) => {
+                        fields.len() == #field_count
+                            #(&& fields[#indices].name() == #field_names
+                                && <#field_types as ::tokio_postgres::types::ToSql>::accepts(
+                                    fields[#indices].type_(),
+                                ))*
+                    }
+                    _ => false,
+                }
+            }
+
+            ::tokio_postgres::types::to_sql_checked!();
+        }
+
+        impl<'a> ::tokio_postgres::types::FromSql<'a> for #struct_name {
+            fn from_sql(
+                ty: &::tokio_postgres::types::Type,
+                raw: &'a [u8],
+            ) -> ::std::result::Result<Self, Box<dyn ::std::error::Error + Sync + Send>> {
+                let ::tokio_postgres::types::Kind::Composite(fields) = ty.kind() else {
+                    return Err(#not_composite.into());
+                };
+                let mut buf = raw;
+                let count = ::tokio_postgres::types::private::read_be_i32(&mut buf)?;
+                if count as usize != fields.len() {
+                    return Err(format!(
+                        "expected {} composite fields, got {}",
+                        fields.len(),
+                        count
+                    )
+                    .into());
+                }
+                #(
+                    let oid = ::tokio_postgres::types::private::read_be_i32(&mut buf)? as u32;
+                    if oid != fields[#indices].type_().oid() {
+                        return Err(format!(
+                            "unexpected oid {} for composite field {}",
+                            oid, #field_names
+                        )
+                        .into());
+                    }
+                    let #field_idents = ::tokio_postgres::types::private::read_value(
+                        fields[#indices].type_(),
+                        &mut buf,
+                    )?;
+                )*
+                Ok(#struct_name {
+                    #(#field_idents),*
+                })
+            }
+
+            fn accepts(ty: &::tokio_postgres::types::Type) -> bool {
+                if ty.name() != #pg_name {
+                    return false;
+                }
+                match ty.kind() {
+                    ::tokio_postgres::types::Kind::Composite(fields) => {
+                        fields.len() == #field_count
+                            #(&& fields[#indices].name() == #field_names
+                                && <#field_types as ::tokio_postgres::types::FromSql>::accepts(
+                                    fields[#indices].type_(),
+                                ))*
+                    }
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+/// Generate a Rust newtype with `ToSql`/`FromSql` implementations for a Postgres domain.
+///
+/// Values are encoded as the base type; the domain's constraints are enforced by Postgres.
+fn generate_domain_code(domain: &sql_check::DomainType) -> TokenStream2 {
+    let struct_name = format_ident!("{}", domain.rust_name());
+    let pg_name = sql_check::types::unqualified_name(&domain.name);
+    let inner = rust_type_to_tokens(&domain.base_type.to_rust_type());
+    let doc = format!("Rust newtype for the Postgres domain `{}`.", domain.name);
+
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct #struct_name(pub #inner);
+
+        impl ::std::convert::From<#inner> for #struct_name {
+            fn from(value: #inner) -> Self {
+                #struct_name(value)
+            }
+        }
+
+        impl ::std::convert::From<#struct_name> for #inner {
+            fn from(value: #struct_name) -> Self {
+                value.0
+            }
+        }
+
+        impl ::tokio_postgres::types::ToSql for #struct_name {
+            fn to_sql(
+                &self,
+                ty: &::tokio_postgres::types::Type,
+                out: &mut ::tokio_postgres::types::private::BytesMut,
+            ) -> ::std::result::Result<
+                ::tokio_postgres::types::IsNull,
+                Box<dyn ::std::error::Error + Sync + Send>,
+            > {
+                let ty = match ty.kind() {
+                    ::tokio_postgres::types::Kind::Domain(base) => base,
+                    _ => ty,
+                };
+                ::tokio_postgres::types::ToSql::to_sql(&self.0, ty, out)
+            }
+
+            fn accepts(ty: &::tokio_postgres::types::Type) -> bool {
+                match ty.kind() {
+                    ::tokio_postgres::types::Kind::Domain(base) => {
+                        ty.name() == #pg_name
+                            && <#inner as ::tokio_postgres::types::ToSql>::accepts(base)
+                    }
+                    _ => <#inner as ::tokio_postgres::types::ToSql>::accepts(ty),
+                }
+            }
+
+            ::tokio_postgres::types::to_sql_checked!();
+        }
+
+        impl<'a> ::tokio_postgres::types::FromSql<'a> for #struct_name {
+            fn from_sql(
+                ty: &::tokio_postgres::types::Type,
+                raw: &'a [u8],
+            ) -> ::std::result::Result<Self, Box<dyn ::std::error::Error + Sync + Send>> {
+                let ty = match ty.kind() {
+                    ::tokio_postgres::types::Kind::Domain(base) => base,
+                    _ => ty,
+                };
+                <#inner as ::tokio_postgres::types::FromSql>::from_sql(ty, raw).map(#struct_name)
+            }
+
+            fn accepts(ty: &::tokio_postgres::types::Type) -> bool {
+                match ty.kind() {
+                    ::tokio_postgres::types::Kind::Domain(base) => {
+                        ty.name() == #pg_name
+                            && <#inner as ::tokio_postgres::types::FromSql>::accepts(base)
+                    }
+                    _ => <#inner as ::tokio_postgres::types::FromSql>::accepts(ty),
+                }
+            }
+        }
+    }
+}
+
 /// Sanitize a column name to be a valid Rust identifier.
 fn sanitize_field_name(name: &str) -> String {
     let name = name.replace(|c: char| !c.is_alphanumeric() && c != '_', "_");
@@ -301,13 +947,20 @@ fn rust_type_to_tokens(ty: &sql_check::RustType) -> TokenStream2 {
         RustType::String => quote! { String },
         RustType::VecU8 => quote! { Vec<u8> },
         RustType::Bool => quote! { bool },
-        RustType::DateTime => quote! { chrono::DateTime<chrono::Utc> },
-        RustType::Date => quote! { chrono::NaiveDate },
-        RustType::Time => quote! { chrono::NaiveTime },
-        RustType::Duration => quote! { chrono::Duration },
+        // chrono or time, depending on sql-check's `time` feature
+        RustType::DateTime
+        | RustType::LocalDateTime
+        | RustType::Date
+        | RustType::Time
+        | RustType::Duration => {
+            let ty: syn::Type = syn::parse_str(&ty.type_path()).expect("date/time type path");
+            quote! { #ty }
+        }
         RustType::Uuid => quote! { uuid::Uuid },
         RustType::JsonValue => quote! { serde_json::Value },
         RustType::IpAddr => quote! { std::net::IpAddr },
+        RustType::Hstore => quote! { std::collections::HashMap<String, Option<String>> },
+        RustType::Geometry => quote! { postgis::ewkb::Geometry },
         RustType::Vec(inner) => {
             let inner_tokens = rust_type_to_tokens(inner);
             quote! { Vec<#inner_tokens> }
@@ -316,9 +969,69 @@ fn rust_type_to_tokens(ty: &sql_check::RustType) -> TokenStream2 {
             let inner_tokens = rust_type_to_tokens(inner);
             quote! { Option<#inner_tokens> }
         }
+        RustType::Enum(_) | RustType::Composite(_) => {
+            let ident = format_ident!("{}", ty.type_path());
+            quote! { #ident }
+        }
+        // Checked when the overrides are loaded
+        RustType::Path(path) => syn::parse_str::<syn::Type>(path)
+            .map(|ty| quote! { #ty })
+            .unwrap_or_else(|e| e.to_compile_error()),
         RustType::Custom(name) => {
             let ident = format_ident!("{}", name);
             quote! { #ident }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_comments_become_field_docs() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE TABLE users (id integer PRIMARY KEY, email text NOT NULL);
+            COMMENT ON COLUMN users.email IS 'Login address';
+            "#,
+        )
+        .unwrap();
+        let sql = "SELECT id, email FROM users";
+        let result = validate_query(&schema, sql).unwrap();
+
+        let code = generate_query_code(sql, &result, &[]).to_string();
+        let code: String = code.split_whitespace().collect();
+        assert!(code.contains(r#"pubid:i32,#[doc="Loginaddress"]pubemail:String"#));
+    }
+
+    #[test]
+    fn test_type_overrides_file() {
+        let path = std::env::temp_dir().join(format!(
+            "sql-check-types-{}-{}.txt",
+            std::process::id(),
+            "test_type_overrides_file"
+        ));
+        std::fs::write(
+            &path,
+            "# Ids\nusers.id = crate::ids::UserId\n\nbigint = std::collections::HashMap<String, i64> # odd\n",
+        )
+        .unwrap();
+        let overrides = read_type_overrides_file(&path).unwrap();
+        assert_eq!(
+            overrides,
+            vec![
+                ("users.id".to_string(), "crate::ids::UserId".to_string()),
+                (
+                    "bigint".to_string(),
+                    "std::collections::HashMap<String, i64>".to_string()
+                ),
+            ]
+        );
+
+        std::fs::write(&path, "users.id crate::ids::UserId\n").unwrap();
+        let err = read_type_overrides_file(&path).unwrap_err();
+        assert!(err.contains("sql-check-types-"), "{err}");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
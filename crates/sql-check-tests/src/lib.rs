@@ -654,6 +654,275 @@ fn test_delete_returning_all() {
     assert!(q.sql().contains("RETURNING *"));
 }
 
+// ============================================================================
+// Enum type tests
+// ============================================================================
+
+sql_check_macros::pg_enum!(ticket_status);
+
+#[test]
+fn test_pg_enum_variants() {
+    assert_eq!(TicketStatus::Open.as_str(), "open");
+    assert_eq!(TicketStatus::InProgress.as_str(), "in_progress");
+    assert_eq!(TicketStatus::Closed.as_str(), "closed");
+}
+
+sql_check_macros::pg_enum!(visibility);
+
+#[test]
+fn test_pg_enum_reserved_label() {
+    // `Self` can't be a variant name
+    assert_eq!(Visibility::Self_.as_str(), "self");
+    assert_eq!(Visibility::Public.as_str(), "public");
+}
+
+#[test]
+fn test_select_enum_column() {
+    let q = query!("SELECT id, status, previous_status FROM tickets");
+    assert_eq!(q.sql(), "SELECT id, status, previous_status FROM tickets");
+}
+
+#[test]
+fn test_enum_param() {
+    let q = query!(
+        "SELECT id FROM tickets WHERE status = $1",
+        TicketStatus::InProgress
+    );
+    assert!(q.sql().contains("status = $1"));
+}
+
+// ============================================================================
+// Composite type tests
+// ============================================================================
+
+sql_check_macros::pg_composite!(postal_address);
+
+#[test]
+fn test_pg_composite_fields() {
+    let address = PostalAddress {
+        street: Some("1 Main St".to_string()),
+        city: Some("Springfield".to_string()),
+        postal_code: None,
+    };
+    assert_eq!(address.clone(), address);
+}
+
+#[test]
+fn test_select_composite_column() {
+    let q = query!("SELECT id, address FROM warehouses");
+    assert_eq!(q.sql(), "SELECT id, address FROM warehouses");
+}
+
+#[test]
+fn test_composite_param() {
+    let address = PostalAddress {
+        street: None,
+        city: Some("Springfield".to_string()),
+        postal_code: None,
+    };
+    let q = query!("SELECT id FROM warehouses WHERE address = $1", address);
+    assert!(q.sql().contains("address = $1"));
+}
+
+// ============================================================================
+// Domain tests
+// ============================================================================
+
+sql_check_macros::pg_domain!(email_address);
+
+#[test]
+fn test_pg_domain_newtype() {
+    let email = EmailAddress::from("ada@example.com".to_string());
+    assert_eq!(email.0, "ada@example.com");
+    assert_eq!(String::from(email), "ada@example.com");
+}
+
+#[test]
+fn test_domain_param() {
+    let email = EmailAddress("ada@example.com".to_string());
+    let q = query!("SELECT id FROM contacts WHERE email = $1", email);
+    assert!(q.sql().contains("email = $1"));
+}
+
+// ============================================================================
+// View tests
+// ============================================================================
+
+#[test]
+fn test_select_from_view() {
+    let q = query!("SELECT id, name, bio FROM user_profiles");
+    assert_eq!(q.sql(), "SELECT id, name, bio FROM user_profiles");
+}
+
+#[test]
+fn test_select_from_materialized_view() {
+    let q = query!("SELECT total FROM user_counts");
+    assert_eq!(q.sql(), "SELECT total FROM user_counts");
+}
+
+// ============================================================================
+// Schema-qualified name tests
+// ============================================================================
+
+#[test]
+fn test_select_schema_qualified_table() {
+    let q = query!("SELECT id, amount_cents, paid_at FROM billing.invoices");
+    assert!(q.sql().contains("billing.invoices"));
+}
+
+#[test]
+fn test_same_table_name_in_two_schemas() {
+    // `users` resolves through the search path to public.users
+    let q =
+        query!("SELECT u.email, b.account_code FROM users u JOIN billing.users b ON b.id = u.id");
+    assert!(q.sql().contains("billing.users"));
+}
+
+#[test]
+fn test_schema_qualified_column_reference() {
+    let q = query!("SELECT public.users.name FROM public.users");
+    assert!(q.sql().contains("public.users.name"));
+}
+
+// ============================================================================
+// CHECK constraint tests
+// ============================================================================
+
+#[test]
+fn test_check_constraint_literals() {
+    // deliveries.status and deliveries.priority are limited by CHECK (... = ANY (ARRAY[...]))
+    let q = query!(
+        "SELECT id FROM deliveries WHERE status = 'delivered' OR status IN ('pending', 'in_transit')"
+    );
+    assert!(q.sql().contains("'delivered'"));
+
+    let id = uuid::Uuid::new_v4();
+    let q = query!(
+        "UPDATE deliveries SET status = 'returned', priority = 1.0 WHERE id = $1",
+        id
+    );
+    assert!(q.sql().contains("'returned'"));
+
+    // Negated comparisons are always true for other values
+    let q = query!("SELECT id FROM deliveries WHERE status <> 'lost' AND status NOT IN ('lost')");
+    assert!(q.sql().contains("NOT IN"));
+}
+
+// ============================================================================
+// Generated and identity column tests
+// ============================================================================
+
+#[test]
+fn test_insert_omitting_generated_columns() {
+    let label = "kitchen".to_string();
+    let q = query!(
+        "INSERT INTO measurements (label, celsius) VALUES ($1, $2) RETURNING id, fahrenheit",
+        label,
+        21i32
+    );
+    assert!(q.sql().contains("RETURNING id, fahrenheit"));
+}
+
+#[test]
+fn test_insert_overriding_system_value() {
+    let label = "attic".to_string();
+    let q = query!(
+        "INSERT INTO measurements (id, label, celsius) OVERRIDING SYSTEM VALUE VALUES ($1, $2, $3)",
+        1000i64,
+        label,
+        30i32
+    );
+    assert!(q.sql().contains("OVERRIDING SYSTEM VALUE"));
+}
+
+// ============================================================================
+// User-defined function tests
+// ============================================================================
+
+#[test]
+fn test_scalar_user_function() {
+    let q = query!("SELECT id, display_label(label) AS shown FROM measurements");
+    assert!(q.sql().contains("display_label"));
+}
+
+#[test]
+fn test_set_returning_user_functions() {
+    let q = query!(
+        "SELECT id, label, fahrenheit FROM measurements_above($1)",
+        20i32
+    );
+    assert!(q.sql().contains("measurements_above"));
+
+    let q = query!("SELECT label, readings FROM measurement_labels()");
+    assert!(q.sql().contains("measurement_labels"));
+}
+
+// ============================================================================
+// Sequence tests
+// ============================================================================
+
+#[test]
+fn test_sequence_functions() {
+    let q = query!("SELECT nextval('billing.invoice_numbers') AS number");
+    assert!(q.sql().contains("nextval"));
+
+    let q = query!("SELECT currval('measurements_id_seq'::regclass) AS id");
+    assert!(q.sql().contains("currval"));
+}
+
+// ============================================================================
+// Column comment tests
+// ============================================================================
+
+#[test]
+fn test_commented_columns() {
+    // `email` and `metadata` carry COMMENT ON docs; the macros crate checks the generated docs
+    let q = query!("SELECT id, email, metadata FROM users");
+    assert!(q.sql().contains("metadata"));
+}
+
+// ============================================================================
+// Partition and inheritance tests
+// ============================================================================
+
+#[test]
+fn test_partitioned_tables() {
+    let q = query!(
+        "SELECT id, kind, occurred_at FROM events WHERE kind = $1",
+        "signup"
+    );
+    assert!(q.sql().contains("FROM events"));
+
+    // Partitions have their parent's columns
+    let q = query!("SELECT id, kind, payload FROM events_2024");
+    assert!(q.sql().contains("events_2024"));
+    let q = query!("SELECT id, occurred_at FROM events_default");
+    assert!(q.sql().contains("events_default"));
+}
+
+#[test]
+fn test_inherited_tables() {
+    let q = query!("SELECT id, message, logged_at, archived_at FROM audit_log_archive");
+    assert!(q.sql().contains("audit_log_archive"));
+}
+
+// ============================================================================
+// Extension type tests
+// ============================================================================
+
+#[test]
+fn test_extension_types() {
+    // citext and ltree map to String, hstore to a HashMap
+    let q = query!(
+        "SELECT slug, path, attributes FROM pages WHERE path <@ $1",
+        "docs"
+    );
+    assert!(q.sql().contains("<@"));
+
+    let q = query!("SELECT subpath(path, 0, 1) AS section, akeys(attributes) AS keys FROM pages");
+    assert!(q.sql().contains("akeys"));
+}
+
 // --- Window functions ---
 // Window functions are now supported!
 
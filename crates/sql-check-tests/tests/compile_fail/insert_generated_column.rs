@@ -0,0 +1,7 @@
+//! Test that writing a generated column fails with a clear error.
+
+use sql_check_macros::query;
+
+fn main() {
+    let _q = query!("INSERT INTO measurements (label, celsius, fahrenheit) VALUES ('attic', 30, 86)");
+}
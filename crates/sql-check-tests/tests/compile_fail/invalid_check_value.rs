@@ -0,0 +1,7 @@
+//! Test that a literal outside a column's CHECK (... IN (...)) list fails.
+
+use sql_check_macros::query;
+
+fn main() {
+    let _q = query!("SELECT id FROM deliveries WHERE status = 'lost'");
+}
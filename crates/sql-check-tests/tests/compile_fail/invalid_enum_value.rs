@@ -0,0 +1,7 @@
+//! Test that a literal that isn't one of an enum's labels fails.
+
+use sql_check_macros::query;
+
+fn main() {
+    let _q = query!("SELECT id FROM tickets WHERE status = 'bogus'");
+}
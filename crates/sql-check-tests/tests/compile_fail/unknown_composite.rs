@@ -0,0 +1,7 @@
+//! Test that generating a Rust struct for a non-existent composite type fails.
+
+use sql_check_macros::pg_composite;
+
+pg_composite!(nonexistent_type);
+
+fn main() {}
@@ -0,0 +1,7 @@
+//! Test that generating a Rust newtype for a non-existent domain fails.
+
+use sql_check_macros::pg_domain;
+
+pg_domain!(nonexistent_domain);
+
+fn main() {}
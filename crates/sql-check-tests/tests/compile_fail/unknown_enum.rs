@@ -0,0 +1,7 @@
+//! Test that generating a Rust enum for a non-existent Postgres enum fails.
+
+use sql_check_macros::pg_enum;
+
+pg_enum!(nonexistent_enum);
+
+fn main() {}
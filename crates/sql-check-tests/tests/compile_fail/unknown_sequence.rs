@@ -0,0 +1,7 @@
+//! Test that nextval() on a sequence that doesn't exist fails with a clear error.
+
+use sql_check_macros::query;
+
+fn main() {
+    let _q = query!("SELECT nextval('invoice_numbers')");
+}
@@ -0,0 +1,7 @@
+//! Test that calling a schema function with the wrong number of arguments fails.
+
+use sql_check_macros::query;
+
+fn main() {
+    let _q = query!("SELECT display_label(label, '!', '?') FROM measurements");
+}
@@ -0,0 +1,20 @@
+//! Test that date/time columns map to chrono types by default, and to the
+//! time crate with the `time` feature.
+//!
+//! Uses trybuild so each case is checked against the exact types. Run with
+//! `--features time` for the time crate case.
+
+use std::path::PathBuf;
+
+#[test]
+fn date_time_tests() {
+    let schema_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("schema.sql");
+    std::env::set_var("SQL_CHECK_SCHEMA", &schema_path);
+
+    let t = trybuild::TestCases::new();
+    if cfg!(feature = "time") {
+        t.pass("tests/date_time/time_crate.rs");
+    } else {
+        t.pass("tests/date_time/chrono_crate.rs");
+    }
+}
@@ -0,0 +1,26 @@
+//! Columns and date/time functions typed with chrono, the default.
+
+use sql_check_macros::query;
+
+#[allow(dead_code)]
+async fn check(client: &tokio_postgres::Client) {
+    let row = query!(
+        "SELECT created_at, localtimestamp AS local, current_date AS today, localtime AS now_time
+         FROM users"
+    )
+    .fetch_one(client)
+    .await
+    .unwrap();
+    let _: chrono::DateTime<chrono::Utc> = row.created_at;
+    let _: chrono::NaiveDateTime = row.local;
+    let _: chrono::NaiveDate = row.today;
+    let _: chrono::NaiveTime = row.now_time;
+
+    let invoice = query!("SELECT paid_at FROM billing.invoices")
+        .fetch_one(client)
+        .await
+        .unwrap();
+    let _: Option<chrono::DateTime<chrono::Utc>> = invoice.paid_at;
+}
+
+fn main() {}
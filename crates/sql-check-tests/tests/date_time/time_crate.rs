@@ -0,0 +1,26 @@
+//! Columns and date/time functions typed with the time crate.
+
+use sql_check_macros::query;
+
+#[allow(dead_code)]
+async fn check(client: &tokio_postgres::Client) {
+    let row = query!(
+        "SELECT created_at, localtimestamp AS local, current_date AS today, localtime AS now_time
+         FROM users"
+    )
+    .fetch_one(client)
+    .await
+    .unwrap();
+    let _: time::OffsetDateTime = row.created_at;
+    let _: time::PrimitiveDateTime = row.local;
+    let _: time::Date = row.today;
+    let _: time::Time = row.now_time;
+
+    let invoice = query!("SELECT paid_at FROM billing.invoices")
+        .fetch_one(client)
+        .await
+        .unwrap();
+    let _: Option<time::OffsetDateTime> = invoice.paid_at;
+}
+
+fn main() {}
@@ -1200,3 +1200,444 @@ async fn test_aggregate_as_window() {
         assert_eq!(r.total_count, 3i64);
     }
 }
+
+// ============================================================================
+// Enum type tests
+// ============================================================================
+
+sql_check_macros::pg_enum!(ticket_status);
+
+#[tokio::test]
+#[serial]
+async fn test_enum_round_trip() {
+    let client = connect().await;
+
+    client.execute("DELETE FROM tickets", &[]).await.unwrap();
+
+    let ticket_id = uuid::Uuid::new_v4();
+    let inserted = query!(
+        "INSERT INTO tickets (id, title, status) VALUES ($1, $2, $3) RETURNING status, previous_status",
+        ticket_id,
+        "Broken login".to_string(),
+        TicketStatus::InProgress
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(inserted.status, TicketStatus::InProgress);
+    assert_eq!(inserted.previous_status, None);
+
+    let row = query!(
+        "SELECT id, status FROM tickets WHERE status = $1",
+        TicketStatus::InProgress
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(row.id, ticket_id);
+    assert_eq!(row.status, TicketStatus::InProgress);
+}
+
+sql_check_macros::pg_composite!(postal_address);
+
+#[tokio::test]
+#[serial]
+async fn test_composite_round_trip() {
+    let client = connect().await;
+
+    client.execute("DELETE FROM warehouses", &[]).await.unwrap();
+
+    let address = PostalAddress {
+        street: Some("1 Main St".to_string()),
+        city: Some("Springfield".to_string()),
+        postal_code: None,
+    };
+    let warehouse_id = uuid::Uuid::new_v4();
+    let inserted = query!(
+        "INSERT INTO warehouses (id, name, address) VALUES ($1, $2, $3) RETURNING address",
+        warehouse_id,
+        "North".to_string(),
+        address.clone()
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(inserted.address, address);
+
+    let row = query!(
+        "SELECT id, address FROM warehouses WHERE (address).city = $1",
+        "Springfield".to_string()
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(row.id, warehouse_id);
+    assert_eq!(row.address.street.as_deref(), Some("1 Main St"));
+    assert_eq!(row.address.postal_code, None);
+}
+
+sql_check_macros::pg_domain!(email_address);
+
+#[tokio::test]
+#[serial]
+async fn test_domain_round_trip() {
+    let client = connect().await;
+
+    client.execute("DELETE FROM contacts", &[]).await.unwrap();
+
+    let contact_id = uuid::Uuid::new_v4();
+    let inserted = query!(
+        "INSERT INTO contacts (id, email) VALUES ($1, $2) RETURNING email",
+        contact_id,
+        EmailAddress::from("ada@example.com".to_string())
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    // Domain columns are typed as the base type, and NOT NULL carries over
+    let email: String = inserted.email;
+    assert_eq!(email, "ada@example.com");
+
+    let row = query!(
+        "SELECT id, email FROM contacts WHERE email = $1",
+        "ada@example.com".to_string()
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(row.id, contact_id);
+    assert_eq!(EmailAddress::from(row.email).0, "ada@example.com");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_generated_and_identity_columns() {
+    let client = connect().await;
+
+    client
+        .execute("DELETE FROM measurements", &[])
+        .await
+        .unwrap();
+
+    let inserted = query!(
+        "INSERT INTO measurements (label, celsius) VALUES ($1, $2) RETURNING id, fahrenheit",
+        "kitchen".to_string(),
+        100i32
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    // Stored generated columns may still be NULL (when their inputs are)
+    let fahrenheit: Option<i32> = inserted.fahrenheit;
+    assert_eq!(fahrenheit, Some(212));
+    assert!(inserted.id > 0);
+
+    let overridden = query!(
+        "INSERT INTO measurements (id, label, celsius) OVERRIDING SYSTEM VALUE VALUES ($1, $2, $3) RETURNING id",
+        -1i64,
+        "attic".to_string(),
+        0i32
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+    assert_eq!(overridden.id, -1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_user_defined_functions() {
+    let client = connect().await;
+
+    client
+        .execute("DELETE FROM measurements", &[])
+        .await
+        .unwrap();
+    client
+        .execute(
+            "INSERT INTO measurements (label, celsius) VALUES ('kitchen', 21), ('kitchen', 23), ('cellar', 12)",
+            &[],
+        )
+        .await
+        .unwrap();
+
+    // STRICT over non-null arguments, so the result isn't an Option
+    let rows = query!(
+        "SELECT display_label(label, '!') AS shown FROM measurements WHERE celsius > $1",
+        20i32
+    )
+    .fetch_all(&client)
+    .await
+    .unwrap();
+    let shown: Vec<String> = rows.into_iter().map(|row| row.shown).collect();
+    assert_eq!(shown, ["KITCHEN!", "KITCHEN!"]);
+
+    let warm = query!(
+        "SELECT label, celsius, fahrenheit FROM measurements_above($1)",
+        15i32
+    )
+    .fetch_all(&client)
+    .await
+    .unwrap();
+    assert_eq!(warm.len(), 2);
+    assert_eq!(warm[0].label, "kitchen");
+    let fahrenheit: Option<i32> = warm[0].fahrenheit;
+    assert!(fahrenheit.is_some());
+
+    let labels = query!("SELECT label, readings FROM measurement_labels() ORDER BY label")
+        .fetch_all(&client)
+        .await
+        .unwrap();
+    // RETURNS TABLE columns can't be declared NOT NULL
+    let counts: Vec<(Option<String>, Option<i64>)> = labels
+        .into_iter()
+        .map(|row| (row.label, row.readings))
+        .collect();
+    assert_eq!(
+        counts,
+        [
+            (Some("cellar".to_string()), Some(1)),
+            (Some("kitchen".to_string()), Some(2))
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn test_sequence_functions() {
+    let client = connect().await;
+
+    let first = query!("SELECT nextval('billing.invoice_numbers') AS number")
+        .fetch_one(&client)
+        .await
+        .unwrap();
+    let number: i64 = first.number;
+    assert!(number >= 1000);
+
+    let current = query!("SELECT currval('billing.invoice_numbers') AS number")
+        .fetch_one(&client)
+        .await
+        .unwrap();
+    assert_eq!(current.number, number);
+
+    // The sequence behind an identity column is known too
+    let inserted = query!(
+        "INSERT INTO measurements (label, celsius) VALUES ($1, $2) RETURNING id",
+        "garage".to_string(),
+        5i32
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+    let last = query!("SELECT currval('measurements_id_seq'::regclass) AS id")
+        .fetch_one(&client)
+        .await
+        .unwrap();
+    assert_eq!(last.id, inserted.id);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_select_from_view() {
+    let client = connect().await;
+
+    client.execute("DELETE FROM profiles", &[]).await.unwrap();
+    client.execute("DELETE FROM users", &[]).await.unwrap();
+
+    let user_id = uuid::Uuid::new_v4();
+    client
+        .execute(
+            "INSERT INTO users (id, name, email) VALUES ($1, 'Ada', 'ada@example.com')",
+            &[&user_id],
+        )
+        .await
+        .unwrap();
+
+    let row = query!(
+        "SELECT id, name, bio FROM user_profiles WHERE id = $1",
+        user_id
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(row.id, user_id);
+    assert_eq!(row.name, "Ada");
+    // bio comes from the view's LEFT JOIN
+    assert_eq!(row.bio, None);
+
+    client
+        .execute("REFRESH MATERIALIZED VIEW user_counts", &[])
+        .await
+        .unwrap();
+    let counts = query!("SELECT total FROM user_counts")
+        .fetch_one(&client)
+        .await
+        .unwrap();
+    assert_eq!(counts.total, 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schema_qualified_tables() {
+    let client = connect().await;
+
+    client
+        .execute("DELETE FROM billing.invoices", &[])
+        .await
+        .unwrap();
+    client
+        .execute("DELETE FROM billing.users", &[])
+        .await
+        .unwrap();
+
+    let billing_user_id = uuid::Uuid::new_v4();
+    client
+        .execute(
+            "INSERT INTO billing.users (id, account_code) VALUES ($1, 'ACME-1')",
+            &[&billing_user_id],
+        )
+        .await
+        .unwrap();
+
+    let invoice = query!(
+        "INSERT INTO billing.invoices (id, billing_user_id, amount_cents) VALUES ($1, $2, $3) RETURNING id, amount_cents, paid_at",
+        uuid::Uuid::new_v4(),
+        billing_user_id,
+        1250i64
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(invoice.amount_cents, 1250);
+    assert_eq!(invoice.paid_at, None);
+
+    let row = query!(
+        "SELECT b.account_code, i.amount_cents FROM billing.users b JOIN billing.invoices i ON i.billing_user_id = b.id WHERE b.id = $1",
+        billing_user_id
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(row.account_code, "ACME-1");
+    assert_eq!(row.amount_cents, 1250);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_extension_types_round_trip() {
+    let client = connect().await;
+
+    client.execute("DELETE FROM pages", &[]).await.unwrap();
+
+    let attributes = std::collections::HashMap::from([
+        ("color".to_string(), Some("blue".to_string())),
+        ("draft".to_string(), None),
+    ]);
+    let page = query!(
+        "INSERT INTO pages (id, slug, path, attributes) VALUES ($1, $2, $3, $4) RETURNING slug, path, attributes",
+        1i64,
+        "Getting-Started".to_string(),
+        "docs.guides.start".to_string(),
+        attributes.clone()
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(page.slug, "Getting-Started");
+    assert_eq!(page.path, "docs.guides.start");
+    assert_eq!(page.attributes, Some(attributes));
+
+    // citext compares case-insensitively
+    let row = query!(
+        "SELECT id, nlevel(path) AS depth, attributes -> 'color' AS color FROM pages WHERE slug = $1",
+        "getting-started".to_string()
+    )
+    .fetch_one(&client)
+    .await
+    .unwrap();
+
+    assert_eq!(row.id, 1);
+    assert_eq!(row.depth, 3);
+    assert_eq!(row.color.as_deref(), Some("blue"));
+}
+
+// ============================================================================
+// Introspection tests
+// ============================================================================
+
+#[tokio::test]
+#[serial]
+async fn test_introspect_matches_schema_file() {
+    let mut client = connect().await;
+
+    let dumped = sql_check::introspect::introspect(&mut client, &["public", "billing"])
+        .await
+        .unwrap();
+    let schema_file = sql_check::Schema::from_file(std::path::Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/schema.sql"
+    )))
+    .unwrap();
+
+    assert_eq!(dumped.to_json().unwrap(), schema_file.to_json().unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_introspect_dump_is_deterministic() {
+    let mut client = connect().await;
+    client
+        .batch_execute(
+            "DROP SCHEMA IF EXISTS introspect_scratch CASCADE;
+             CREATE SCHEMA introspect_scratch;
+             CREATE DOMAIN introspect_scratch.score AS integer DEFAULT 0;
+             CREATE TABLE introspect_scratch.events (
+                 id serial PRIMARY KEY,
+                 score introspect_scratch.score,
+                 note text
+             );
+             CREATE VIEW introspect_scratch.recent AS SELECT id, score FROM introspect_scratch.events;
+             CREATE VIEW introspect_scratch.top AS SELECT id FROM introspect_scratch.recent WHERE score > 10;
+             COMMENT ON COLUMN introspect_scratch.events.note IS 'It''s free text';",
+        )
+        .await
+        .unwrap();
+
+    let sql = sql_check::introspect::dump_sql(&mut client, &["introspect_scratch"]).await;
+    let again = sql_check::introspect::dump_sql(&mut client, &["introspect_scratch"]).await;
+    client
+        .batch_execute("DROP SCHEMA introspect_scratch CASCADE")
+        .await
+        .unwrap();
+    let sql = sql.unwrap();
+    assert_eq!(sql, again.unwrap());
+    assert!(!sql.contains("public."), "{sql}");
+
+    let schema = sql_check::Schema::from_sql(&sql).unwrap();
+    let events = schema.get_table("introspect_scratch.events").unwrap();
+    let id = events.get_column("id").unwrap();
+    assert!(id.is_primary_key && id.has_default);
+    assert_eq!(
+        id.sequence.as_deref(),
+        Some("introspect_scratch.events_id_seq")
+    );
+    let score = events.get_column("score").unwrap();
+    assert_eq!(score.data_type, sql_check::PostgresType::Integer);
+    assert!(score.has_default);
+    assert_eq!(
+        events.get_column("note").unwrap().comment.as_deref(),
+        Some("It's free text")
+    );
+    assert!(schema.get_view("introspect_scratch.top").is_some());
+}
@@ -0,0 +1,29 @@
+//! Test that PostGIS columns map to `postgis::ewkb::Geometry` with the
+//! `postgis` feature, and fail with a hint to enable it without.
+//!
+//! Uses trybuild with PostGIS tables added to the schema, since the test
+//! database doesn't have PostGIS. Run with `--features postgis` for the
+//! mapped case.
+
+use std::path::PathBuf;
+
+#[test]
+fn postgis_tests() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let postgis_schema = dir.join("tests").join("postgis").join("schema.sql");
+    std::env::set_var(
+        "SQL_CHECK_SCHEMA",
+        format!(
+            "{},{}",
+            dir.join("schema.sql").display(),
+            postgis_schema.display()
+        ),
+    );
+
+    let t = trybuild::TestCases::new();
+    if cfg!(feature = "postgis") {
+        t.pass("tests/postgis/select_geometry.rs");
+    } else {
+        t.compile_fail("tests/postgis/without_feature.rs");
+    }
+}
@@ -0,0 +1,9 @@
+-- Added to the test schema by tests/postgis.rs
+CREATE EXTENSION IF NOT EXISTS postgis WITH SCHEMA public;
+
+CREATE TABLE public.places (
+    id bigint NOT NULL,
+    name text NOT NULL,
+    location public.geography NOT NULL,
+    outline public.geometry
+);
@@ -0,0 +1,19 @@
+//! Geometry and geography columns, and the PostGIS functions returning them.
+
+use sql_check_macros::query;
+
+#[allow(dead_code)]
+async fn check(client: &tokio_postgres::Client) {
+    let row = query!(
+        "SELECT location, outline, st_buffer(location, 10) AS area, st_area(location) AS size FROM places"
+    )
+    .fetch_one(client)
+    .await
+    .unwrap();
+    let _: postgis::ewkb::Geometry = row.location;
+    let _: Option<postgis::ewkb::Geometry> = row.outline;
+    let _: postgis::ewkb::Geometry = row.area;
+    let _: f64 = row.size;
+}
+
+fn main() {}
@@ -0,0 +1,5 @@
+use sql_check_macros::query;
+
+fn main() {
+    let _ = query!("SELECT id, location FROM places");
+}
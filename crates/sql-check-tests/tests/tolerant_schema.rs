@@ -0,0 +1,21 @@
+//! Test that `SQL_CHECK_SCHEMA_TOLERANT=1` loads a schema with statements
+//! sql-check can't parse, skipping them.
+//!
+//! Uses trybuild with a dump-like file added to the schema, so tolerant
+//! loading only applies to the cases in `tests/tolerant_schema/`.
+
+use std::path::PathBuf;
+
+#[test]
+fn tolerant_schema_tests() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let dump = dir.join("tests").join("tolerant_schema").join("dump.sql");
+    std::env::set_var(
+        "SQL_CHECK_SCHEMA",
+        format!("{},{}", dir.join("schema.sql").display(), dump.display()),
+    );
+    std::env::set_var("SQL_CHECK_SCHEMA_TOLERANT", "1");
+
+    let t = trybuild::TestCases::new();
+    t.pass("tests/tolerant_schema/*.rs");
+}
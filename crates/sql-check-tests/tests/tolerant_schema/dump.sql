@@ -0,0 +1,10 @@
+-- Statements from a full pg_dump that sql-check can't parse
+
+CREATE TABLE public.dump_notes (
+    id integer NOT NULL,
+    body text NOT NULL
+);
+
+CREATE AGGREGATE public.int_sum(integer) (SFUNC = int4pl, STYPE = integer);
+
+ALTER DEFAULT PRIVILEGES IN SCHEMA public GRANT SELECT ON TABLES TO PUBLIC;
@@ -0,0 +1,15 @@
+//! A table from a schema file that only loads tolerantly.
+
+use sql_check_macros::query;
+
+#[allow(dead_code)]
+async fn check(client: &tokio_postgres::Client) {
+    let note = query!("SELECT id, body FROM dump_notes")
+        .fetch_one(client)
+        .await
+        .unwrap();
+    let _: i32 = note.id;
+    let _: String = note.body;
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+//! Test that `SQL_CHECK_TYPE_OVERRIDES` changes the types of query results.
+//!
+//! Uses trybuild so the overrides only apply to the cases in
+//! `tests/type_overrides/`, not to the rest of the test crate. Their views
+//! are added to the schema.
+
+use std::path::PathBuf;
+
+#[test]
+fn type_override_tests() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let views = dir.join("tests").join("type_overrides").join("views.sql");
+    std::env::set_var(
+        "SQL_CHECK_SCHEMA",
+        format!("{},{}", dir.join("schema.sql").display(), views.display()),
+    );
+    std::env::set_var(
+        "SQL_CHECK_TYPE_OVERRIDES",
+        "users.id = crate::ids::UserId, email_address = crate::ids::Email",
+    );
+
+    let t = trybuild::TestCases::new();
+    t.pass("tests/type_overrides/*.rs");
+}
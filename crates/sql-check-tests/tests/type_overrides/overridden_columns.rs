@@ -0,0 +1,92 @@
+//! Overrides apply to joins, CTEs, RETURNING and views.
+
+use sql_check_macros::query;
+
+mod ids {
+    use tokio_postgres::types::{FromSql, Type};
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct UserId(pub uuid::Uuid);
+
+    impl<'a> FromSql<'a> for UserId {
+        fn from_sql(
+            ty: &Type,
+            raw: &'a [u8],
+        ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+            uuid::Uuid::from_sql(ty, raw).map(UserId)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <uuid::Uuid as FromSql>::accepts(ty)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Email(pub String);
+
+    impl<'a> FromSql<'a> for Email {
+        fn from_sql(
+            ty: &Type,
+            raw: &'a [u8],
+        ) -> Result<Self, Box<dyn std::error::Error + Sync + Send>> {
+            String::from_sql(ty, raw).map(Email)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            <String as FromSql>::accepts(ty)
+        }
+    }
+}
+
+use ids::{Email, UserId};
+
+#[allow(dead_code)]
+async fn check(client: &tokio_postgres::Client) {
+    let row = query!(
+        "SELECT u.id, o.user_id, o.id AS order_id FROM users u JOIN orders o ON o.user_id = u.id"
+    )
+    .fetch_one(client)
+    .await
+    .unwrap();
+    let _: UserId = row.id;
+    let _: uuid::Uuid = row.user_id;
+    let _: uuid::Uuid = row.order_id;
+
+    let row = query!("WITH recent AS (SELECT id, name FROM users) SELECT id FROM recent")
+        .fetch_one(client)
+        .await
+        .unwrap();
+    let _: UserId = row.id;
+
+    let id = uuid::Uuid::new_v4();
+    let row = query!(
+        "INSERT INTO users (id, name, email) VALUES ($1, 'a', 'a@b.c') RETURNING id",
+        id
+    )
+    .fetch_one(client)
+    .await
+    .unwrap();
+    let _: UserId = row.id;
+
+    // Domain overrides
+    let row = query!("SELECT email FROM contacts")
+        .fetch_one(client)
+        .await
+        .unwrap();
+    let _: Email = row.email;
+
+    // Views selecting overridden columns, except for computed columns
+    let row = query!("SELECT id, name FROM user_profiles")
+        .fetch_one(client)
+        .await
+        .unwrap();
+    let _: UserId = row.id;
+    let row = query!("SELECT * FROM contact_emails")
+        .fetch_one(client)
+        .await
+        .unwrap();
+    let _: Email = row.email;
+    let _: String = row.lower_email;
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+-- Views added to schema.sql for the type override cases
+
+CREATE VIEW contact_emails AS
+ SELECT contacts.id,
+    contacts.email,
+    lower((contacts.email)::text) AS lower_email
+   FROM public.contacts;
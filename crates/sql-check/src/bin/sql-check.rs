@@ -0,0 +1,100 @@
+//! Command line tools for sql-check.
+//!
+//! ```text
+//! sql-check dump [--json] [--schema NAME]... [--output FILE] [CONNECTION]
+//! ```
+//!
+//! `dump` writes the schema of a database as SQL (or a JSON snapshot with
+//! `--json`) that `SQL_CHECK_SCHEMA` can point at. The connection string
+//! defaults to `DATABASE_URL`.
+
+use std::process::ExitCode;
+use tokio_postgres::NoTls;
+
+const USAGE: &str =
+    "Usage: sql-check dump [--json] [--schema NAME]... [--output FILE] [CONNECTION]";
+
+/// Options of the `dump` command.
+#[derive(Default)]
+struct DumpArgs {
+    json: bool,
+    namespaces: Vec<String>,
+    output: Option<String>,
+    connection: Option<String>,
+}
+
+fn parse_dump_args(mut args: impl Iterator<Item = String>) -> Result<DumpArgs, String> {
+    let mut dump = DumpArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => dump.json = true,
+            "--schema" => dump
+                .namespaces
+                .push(args.next().ok_or("--schema needs a schema name")?),
+            "--output" | "-o" => dump.output = Some(args.next().ok_or("--output needs a file")?),
+            flag if flag.starts_with('-') => return Err(format!("Unknown option {}", flag)),
+            _ if dump.connection.is_none() => dump.connection = Some(arg),
+            _ => return Err(format!("Unexpected argument {}", arg)),
+        }
+    }
+    Ok(dump)
+}
+
+async fn dump(args: DumpArgs) -> Result<(), String> {
+    let connection_string = args
+        .connection
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+        .ok_or("No connection string given and DATABASE_URL is not set")?;
+    let (mut client, connection) = tokio_postgres::connect(&connection_string, NoTls)
+        .await
+        .map_err(|e| format!("Failed to connect: {}", e))?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            eprintln!("connection error: {}", e);
+        }
+    });
+
+    let namespaces: Vec<&str> = args.namespaces.iter().map(String::as_str).collect();
+    let sql = sql_check::introspect::dump_sql(&mut client, &namespaces)
+        .await
+        .map_err(|e| e.to_string())?;
+    // Loading the dump checks it before anything is written
+    let (schema, warnings) =
+        sql_check::Schema::from_sql_tolerant(&sql).map_err(|e| e.to_string())?;
+    for warning in warnings {
+        eprintln!("warning: {}", warning);
+    }
+    let output = if args.json {
+        schema.to_json().map_err(|e| e.to_string())? + "\n"
+    } else {
+        sql
+    };
+
+    match args.output {
+        Some(path) => std::fs::write(&path, output).map_err(|e| format!("{}: {}", path, e)),
+        None => {
+            print!("{}", output);
+            Ok(())
+        }
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("dump") => match parse_dump_args(args) {
+            Ok(dump_args) => dump(dump_args).await,
+            Err(e) => Err(format!("{}\n{}", e, USAGE)),
+        },
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
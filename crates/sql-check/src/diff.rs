@@ -0,0 +1,1103 @@
+//! Comparing two schemas.
+//!
+//! [`Schema::diff`] reports how the tables, types, views, functions, sequences
+//! and columns queries compile against changed between two schemas (typically
+//! before and after a migration), and whether each change breaks existing queries.
+
+use crate::schema::{
+    Check, Column, CompositeType, DomainType, EnumType, ForeignKey, Function, FunctionReturn,
+    Generated, IndexKey, Schema, Sequence, Table, UniqueKey, View,
+};
+use crate::types::{PostgresType, RustType};
+use crate::validate::QueryColumn;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// The differences between two schemas: tables, then enums, composite types,
+/// domains, views, functions and sequences, each in name order and with their
+/// columns in column order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaDiff {
+    pub changes: Vec<Change>,
+}
+
+impl SchemaDiff {
+    /// Whether the schemas have the same tables, types, views, functions,
+    /// sequences and columns.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Changes that break readers or writers.
+    pub fn breaking(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter().filter(|change| change.is_breaking())
+    }
+
+    /// Whether any change breaks readers or writers.
+    pub fn has_breaking_changes(&self) -> bool {
+        self.breaking().next().is_some()
+    }
+}
+
+impl fmt::Display for SchemaDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single change to a table, view, type, function, sequence or column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Qualified name of the table (`public.users`), or of the other object
+    /// for its changes. Functions are named with their argument types
+    /// (`public.add(integer, integer)`), since each overload is compared apart.
+    pub table: String,
+    /// The column, or the field of a composite type, for changes to one.
+    pub column: Option<String>,
+    pub kind: ChangeKind,
+    /// Whether queries reading the table may stop compiling: selected columns
+    /// disappear or change their Rust type.
+    pub breaks_readers: bool,
+    /// Whether queries writing the table may stop compiling or start failing:
+    /// columns disappear, values that were accepted are now rejected, or
+    /// `ON CONFLICT` loses the key it names. Views are taken to be read-only.
+    pub breaks_writers: bool,
+}
+
+impl Change {
+    /// Whether the change breaks readers or writers.
+    pub fn is_breaking(&self) -> bool {
+        self.breaks_readers || self.breaks_writers
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.column {
+            Some(column) => write!(f, "{}.{}: {}", self.table, column, self.kind)?,
+            None => write!(f, "{}: {}", self.table, self.kind)?,
+        }
+        match (self.breaks_readers, self.breaks_writers) {
+            (true, true) => write!(f, " (breaks readers and writers)"),
+            (true, false) => write!(f, " (breaks readers)"),
+            (false, true) => write!(f, " (breaks writers)"),
+            (false, false) => Ok(()),
+        }
+    }
+}
+
+/// What changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    TableAdded,
+    TableRemoved,
+    ColumnAdded,
+    ColumnRemoved,
+    TypeChanged {
+        from: PostgresType,
+        to: PostgresType,
+    },
+    NullabilityChanged {
+        nullable: bool,
+    },
+    DefaultChanged {
+        has_default: bool,
+    },
+    GeneratedChanged {
+        from: Option<Generated>,
+        to: Option<Generated>,
+    },
+    /// A primary key or unique constraint (or unique index) was added.
+    KeyAdded(UniqueKey),
+    KeyRemoved(UniqueKey),
+    ForeignKeyAdded(ForeignKey),
+    ForeignKeyRemoved(ForeignKey),
+    CheckAdded(Check),
+    CheckRemoved(Check),
+    /// A view column's Rust type changed.
+    ResultTypeChanged {
+        from: RustType,
+        to: RustType,
+    },
+    ViewAdded,
+    ViewRemoved,
+    /// The view's query changed (its columns are compared separately).
+    ViewDefinitionChanged,
+    EnumAdded,
+    EnumRemoved,
+    EnumLabelAdded(String),
+    EnumLabelRemoved(String),
+    CompositeAdded,
+    CompositeRemoved,
+    DomainAdded,
+    DomainRemoved,
+    FunctionAdded,
+    FunctionRemoved,
+    ReturnTypeChanged {
+        from: FunctionReturn,
+        to: FunctionReturn,
+    },
+    StrictChanged {
+        strict: bool,
+    },
+    /// Argument names, defaults or `VARIADIC` changed (the types are the same).
+    ArgumentsChanged,
+    SequenceAdded,
+    SequenceRemoved,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::TableAdded => f.write_str("table added"),
+            ChangeKind::TableRemoved => f.write_str("table removed"),
+            ChangeKind::ColumnAdded => f.write_str("column added"),
+            ChangeKind::ColumnRemoved => f.write_str("column removed"),
+            ChangeKind::TypeChanged { from, to } => {
+                write!(f, "type changed from {from} to {to}")
+            }
+            ChangeKind::NullabilityChanged { nullable: true } => f.write_str("now nullable"),
+            ChangeKind::NullabilityChanged { nullable: false } => f.write_str("now NOT NULL"),
+            ChangeKind::DefaultChanged { has_default: true } => f.write_str("default added"),
+            ChangeKind::DefaultChanged { has_default: false } => f.write_str("default removed"),
+            ChangeKind::GeneratedChanged { to: Some(_), .. } => f.write_str("now generated"),
+            ChangeKind::GeneratedChanged { to: None, .. } => f.write_str("no longer generated"),
+            ChangeKind::KeyAdded(key) => write!(f, "{} added", describe_key(key)),
+            ChangeKind::KeyRemoved(key) => write!(f, "{} removed", describe_key(key)),
+            ChangeKind::ForeignKeyAdded(fk) => write!(f, "foreign key {} added", fk.name),
+            ChangeKind::ForeignKeyRemoved(fk) => write!(f, "foreign key {} removed", fk.name),
+            ChangeKind::CheckAdded(check) => write!(f, "check {} added", check.name),
+            ChangeKind::CheckRemoved(check) => write!(f, "check {} removed", check.name),
+            ChangeKind::ResultTypeChanged { from, to } => write!(
+                f,
+                "type changed from {} to {}",
+                from.type_path(),
+                to.type_path()
+            ),
+            ChangeKind::ViewAdded => f.write_str("view added"),
+            ChangeKind::ViewRemoved => f.write_str("view removed"),
+            ChangeKind::ViewDefinitionChanged => f.write_str("view definition changed"),
+            ChangeKind::EnumAdded => f.write_str("enum added"),
+            ChangeKind::EnumRemoved => f.write_str("enum removed"),
+            ChangeKind::EnumLabelAdded(label) => write!(f, "label '{label}' added"),
+            ChangeKind::EnumLabelRemoved(label) => write!(f, "label '{label}' removed"),
+            ChangeKind::CompositeAdded => f.write_str("composite type added"),
+            ChangeKind::CompositeRemoved => f.write_str("composite type removed"),
+            ChangeKind::DomainAdded => f.write_str("domain added"),
+            ChangeKind::DomainRemoved => f.write_str("domain removed"),
+            ChangeKind::FunctionAdded => f.write_str("function added"),
+            ChangeKind::FunctionRemoved => f.write_str("function removed"),
+            ChangeKind::ReturnTypeChanged { from, to } => write!(
+                f,
+                "return type changed from {} to {}",
+                describe_return(from),
+                describe_return(to)
+            ),
+            ChangeKind::StrictChanged { strict: true } => f.write_str("now STRICT"),
+            ChangeKind::StrictChanged { strict: false } => f.write_str("no longer STRICT"),
+            ChangeKind::ArgumentsChanged => f.write_str("arguments changed"),
+            ChangeKind::SequenceAdded => f.write_str("sequence added"),
+            ChangeKind::SequenceRemoved => f.write_str("sequence removed"),
+        }
+    }
+}
+
+/// A key for messages: `primary key users_pkey (id)`, `unique key users_email_key (email)`.
+fn describe_key(key: &UniqueKey) -> String {
+    let kind = if key.primary { "primary" } else { "unique" };
+    format!("{} key {} ({})", kind, key.name, key.columns.join(", "))
+}
+
+/// A function's return type as SQL: `integer`, `SETOF users`, `TABLE(day date)`.
+fn describe_return(returns: &FunctionReturn) -> String {
+    match returns {
+        FunctionReturn::Scalar(data_type) => data_type.to_string(),
+        FunctionReturn::SetOf(data_type) => format!("SETOF {data_type}"),
+        FunctionReturn::Table(fields) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|field| format!("{} {}", field.name, field.data_type))
+                .collect();
+            format!("TABLE({})", fields.join(", "))
+        }
+    }
+}
+
+impl Schema {
+    /// Compare this schema with `other`, reporting the changes that turn this
+    /// schema's tables, types, views, functions and sequences into `other`'s.
+    ///
+    /// Objects are matched by qualified name (function overloads also by
+    /// argument types), and columns, fields and labels by name, so renames show
+    /// up as a removal plus an addition.
+    pub fn diff(&self, other: &Schema) -> SchemaDiff {
+        let mut changes = Vec::new();
+
+        let names: BTreeSet<&str> = self.table_names().chain(other.table_names()).collect();
+        for name in names {
+            match (self.get_table(name), other.get_table(name)) {
+                (Some(_), None) => changes.push(table_change(name, ChangeKind::TableRemoved)),
+                (None, Some(_)) => changes.push(table_change(name, ChangeKind::TableAdded)),
+                (Some(old), Some(new)) => diff_tables(old, new, &mut changes),
+                (None, None) => {}
+            }
+        }
+
+        let names: BTreeSet<&str> = self.enum_names().chain(other.enum_names()).collect();
+        for name in names {
+            match (self.get_enum(name), other.get_enum(name)) {
+                (Some(_), None) => changes.push(table_change(name, ChangeKind::EnumRemoved)),
+                (None, Some(_)) => changes.push(table_change(name, ChangeKind::EnumAdded)),
+                (Some(old), Some(new)) => diff_enums(old, new, &mut changes),
+                (None, None) => {}
+            }
+        }
+
+        let names: BTreeSet<&str> = self
+            .composite_names()
+            .chain(other.composite_names())
+            .collect();
+        for name in names {
+            match (self.get_composite(name), other.get_composite(name)) {
+                (Some(_), None) => changes.push(table_change(name, ChangeKind::CompositeRemoved)),
+                (None, Some(_)) => changes.push(table_change(name, ChangeKind::CompositeAdded)),
+                (Some(old), Some(new)) => diff_composites(old, new, &mut changes),
+                (None, None) => {}
+            }
+        }
+
+        let names: BTreeSet<&str> = self.domain_names().chain(other.domain_names()).collect();
+        for name in names {
+            match (self.get_domain(name), other.get_domain(name)) {
+                (Some(_), None) => changes.push(table_change(name, ChangeKind::DomainRemoved)),
+                (None, Some(_)) => changes.push(table_change(name, ChangeKind::DomainAdded)),
+                (Some(old), Some(new)) => diff_domains(old, new, &mut changes),
+                (None, None) => {}
+            }
+        }
+
+        let names: BTreeSet<&str> = self.view_names().chain(other.view_names()).collect();
+        for name in names {
+            match (self.get_view(name), other.get_view(name)) {
+                (Some(_), None) => changes.push(table_change(name, ChangeKind::ViewRemoved)),
+                (None, Some(_)) => changes.push(table_change(name, ChangeKind::ViewAdded)),
+                (Some(old), Some(new)) => diff_views(old, new, &mut changes),
+                (None, None) => {}
+            }
+        }
+
+        let names: BTreeSet<&str> = self
+            .function_names()
+            .chain(other.function_names())
+            .collect();
+        for name in names {
+            diff_functions(
+                self.get_functions(name),
+                other.get_functions(name),
+                &mut changes,
+            );
+        }
+
+        let names: BTreeSet<&str> = self
+            .sequence_names()
+            .chain(other.sequence_names())
+            .collect();
+        for name in names {
+            match (self.get_sequence(name), other.get_sequence(name)) {
+                (Some(_), None) => changes.push(table_change(name, ChangeKind::SequenceRemoved)),
+                (None, Some(_)) => changes.push(table_change(name, ChangeKind::SequenceAdded)),
+                (Some(old), Some(new)) => diff_sequences(old, new, &mut changes),
+                (None, None) => {}
+            }
+        }
+
+        SchemaDiff { changes }
+    }
+}
+
+/// A change to a whole table, view, type, function or sequence, classified.
+fn table_change(table: &str, kind: ChangeKind) -> Change {
+    let (breaks_readers, breaks_writers) = match &kind {
+        ChangeKind::TableRemoved
+        | ChangeKind::EnumRemoved
+        | ChangeKind::CompositeRemoved
+        | ChangeKind::DomainRemoved => (true, true),
+        ChangeKind::ViewRemoved => (true, false),
+        // Queries calling them stop compiling, whatever they do
+        ChangeKind::FunctionRemoved | ChangeKind::SequenceRemoved => (true, true),
+        // New constraints can reject rows that used to be accepted
+        ChangeKind::ForeignKeyAdded(_) | ChangeKind::CheckAdded(_) => (false, true),
+        // A new key rejects duplicates; without the old one, `ON CONFLICT` on it fails
+        ChangeKind::KeyAdded(_) | ChangeKind::KeyRemoved(_) => (false, true),
+        // Rows may hold the label, and queries may write it
+        ChangeKind::EnumLabelRemoved(_) => (true, true),
+        _ => (false, false),
+    };
+    Change {
+        table: table.to_string(),
+        column: None,
+        kind,
+        breaks_readers,
+        breaks_writers,
+    }
+}
+
+/// Append the changes between two versions of a table.
+fn diff_tables(old: &Table, new: &Table, changes: &mut Vec<Change>) {
+    let table = new.qualified_name();
+
+    for old_column in &old.columns {
+        match new.get_column(&old_column.name) {
+            Some(new_column) => diff_columns(&table, old_column, new_column, changes),
+            None => changes.push(Change {
+                table: table.clone(),
+                column: Some(old_column.name.clone()),
+                kind: ChangeKind::ColumnRemoved,
+                breaks_readers: true,
+                breaks_writers: true,
+            }),
+        }
+    }
+    for new_column in &new.columns {
+        if old.get_column(&new_column.name).is_none() {
+            // Inserts that don't list the column fail if it needs a value
+            let required = !new_column.nullable && !new_column.has_default;
+            changes.push(Change {
+                table: table.clone(),
+                column: Some(new_column.name.clone()),
+                kind: ChangeKind::ColumnAdded,
+                breaks_readers: false,
+                breaks_writers: required,
+            });
+        }
+    }
+
+    let (old_keys, new_keys) = (unique_keys(old), unique_keys(new));
+    for key in old_keys.iter().filter(|key| !has_key(&new_keys, key)) {
+        changes.push(table_change(&table, ChangeKind::KeyRemoved(key.clone())));
+    }
+    for key in new_keys.iter().filter(|key| !has_key(&old_keys, key)) {
+        changes.push(table_change(&table, ChangeKind::KeyAdded(key.clone())));
+    }
+
+    for fk in old
+        .foreign_keys
+        .iter()
+        .filter(|fk| !new.foreign_keys.contains(fk))
+    {
+        changes.push(table_change(
+            &table,
+            ChangeKind::ForeignKeyRemoved(fk.clone()),
+        ));
+    }
+    for fk in new
+        .foreign_keys
+        .iter()
+        .filter(|fk| !old.foreign_keys.contains(fk))
+    {
+        changes.push(table_change(
+            &table,
+            ChangeKind::ForeignKeyAdded(fk.clone()),
+        ));
+    }
+    for check in old
+        .checks
+        .iter()
+        .filter(|check| !new.checks.contains(check))
+    {
+        changes.push(table_change(
+            &table,
+            ChangeKind::CheckRemoved(check.clone()),
+        ));
+    }
+    for check in new
+        .checks
+        .iter()
+        .filter(|check| !old.checks.contains(check))
+    {
+        changes.push(table_change(&table, ChangeKind::CheckAdded(check.clone())));
+    }
+}
+
+/// A table's primary key and unique constraints, plus its non-partial unique
+/// indexes over columns, which `ON CONFLICT` can use the same way.
+fn unique_keys(table: &Table) -> Vec<UniqueKey> {
+    let mut keys = table.unique_keys.clone();
+    for index in table
+        .indexes
+        .iter()
+        .filter(|i| i.unique && i.predicate.is_none())
+    {
+        let columns: Option<Vec<String>> = index
+            .keys
+            .iter()
+            .map(|key| match key {
+                IndexKey::Column(column) => Some(column.clone()),
+                IndexKey::Expression(_) => None,
+            })
+            .collect();
+        if let Some(columns) = columns {
+            let key = UniqueKey {
+                name: index.name.clone(),
+                columns,
+                primary: false,
+            };
+            if !has_key(&keys, &key) {
+                keys.push(key);
+            }
+        }
+    }
+    keys
+}
+
+/// Whether `keys` has a key over the same columns as `key` (in any order,
+/// whatever its name), of the same kind.
+fn has_key(keys: &[UniqueKey], key: &UniqueKey) -> bool {
+    let columns = |key: &UniqueKey| {
+        let mut columns: Vec<String> = key.columns.iter().map(|c| c.to_lowercase()).collect();
+        columns.sort();
+        columns
+    };
+    keys.iter()
+        .any(|k| k.primary == key.primary && columns(k) == columns(key))
+}
+
+/// Append the changes between two versions of a column.
+fn diff_columns(table: &str, old: &Column, new: &Column, changes: &mut Vec<Change>) {
+    let mut push = |kind: ChangeKind, breaks_readers: bool, breaks_writers: bool| {
+        changes.push(Change {
+            table: table.to_string(),
+            column: Some(new.name.clone()),
+            kind,
+            breaks_readers,
+            breaks_writers,
+        });
+    };
+
+    if let Some(change) = type_change(&old.data_type, &new.data_type) {
+        push(change.0, change.1, change.2);
+    }
+    if old.nullable != new.nullable {
+        // The field flips between `T` and `Option<T>`; NULLs stop being accepted
+        push(
+            ChangeKind::NullabilityChanged {
+                nullable: new.nullable,
+            },
+            true,
+            !new.nullable,
+        );
+    }
+    if old.has_default != new.has_default {
+        // Inserts that leave out a NOT NULL column relied on its default
+        push(
+            ChangeKind::DefaultChanged {
+                has_default: new.has_default,
+            },
+            false,
+            !new.has_default && !new.nullable,
+        );
+    }
+    if old.generated != new.generated {
+        let newly_read_only =
+            new.write_restriction(false).is_some() && old.write_restriction(false).is_none();
+        push(
+            ChangeKind::GeneratedChanged {
+                from: old.generated.clone(),
+                to: new.generated.clone(),
+            },
+            false,
+            newly_read_only,
+        );
+    }
+}
+
+/// The change of a column's (or field's or domain's) type, if it changed,
+/// with whether it breaks readers and writers.
+///
+/// Only a different Rust type changes what queries compile against, but a
+/// narrower type (`text` to `varchar(10)`) rejects values on write.
+fn type_change(old: &PostgresType, new: &PostgresType) -> Option<(ChangeKind, bool, bool)> {
+    if old == new {
+        return None;
+    }
+    let rust_type_changed = old.to_rust_type() != new.to_rust_type();
+    Some((
+        ChangeKind::TypeChanged {
+            from: old.clone(),
+            to: new.clone(),
+        },
+        rust_type_changed,
+        rust_type_changed || !accepts_all(old, new),
+    ))
+}
+
+/// Whether a column of type `new` accepts every value one of type `old` did:
+/// the types are the same, or both are strings and `new` is at least as long.
+fn accepts_all(old: &PostgresType, new: &PostgresType) -> bool {
+    // `None` for unlimited; `character` without a length holds one character
+    fn max_length(data_type: &PostgresType) -> Option<Option<u32>> {
+        match data_type {
+            PostgresType::Text | PostgresType::Citext => Some(None),
+            PostgresType::Varchar(length) => Some(*length),
+            PostgresType::Char(length) => Some(Some(length.unwrap_or(1))),
+            _ => None,
+        }
+    }
+
+    match (max_length(old), max_length(new)) {
+        (Some(_), Some(None)) => true,
+        (Some(Some(old)), Some(Some(new))) => old <= new,
+        _ => old == new,
+    }
+}
+
+/// Append the changes between two versions of an enum.
+///
+/// Removed labels break readers and writers; added ones break neither.
+fn diff_enums(old: &EnumType, new: &EnumType, changes: &mut Vec<Change>) {
+    let name = new.qualified_name();
+    for label in old.labels.iter().filter(|l| !new.labels.contains(l)) {
+        changes.push(table_change(
+            &name,
+            ChangeKind::EnumLabelRemoved(label.clone()),
+        ));
+    }
+    for label in new.labels.iter().filter(|l| !old.labels.contains(l)) {
+        changes.push(table_change(
+            &name,
+            ChangeKind::EnumLabelAdded(label.clone()),
+        ));
+    }
+}
+
+/// Append the changes between two versions of a composite type.
+///
+/// Values are read and written as whole rows of the type's fields, so a
+/// removed field or one of another Rust type breaks both, and a new field
+/// breaks code building values of the type.
+fn diff_composites(old: &CompositeType, new: &CompositeType, changes: &mut Vec<Change>) {
+    let name = new.qualified_name();
+    let mut push = |field: &str, (kind, breaks_readers, breaks_writers)| {
+        changes.push(Change {
+            table: name.clone(),
+            column: Some(field.to_string()),
+            kind,
+            breaks_readers,
+            breaks_writers,
+        });
+    };
+    let find = |composite: &CompositeType, field: &str| {
+        composite
+            .fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(field))
+            .map(|f| f.data_type.clone())
+    };
+    for field in &old.fields {
+        match find(new, &field.name) {
+            Some(data_type) => {
+                if let Some(change) = type_change(&field.data_type, &data_type) {
+                    push(&field.name, change);
+                }
+            }
+            None => push(&field.name, (ChangeKind::ColumnRemoved, true, true)),
+        }
+    }
+    for field in &new.fields {
+        if find(old, &field.name).is_none() {
+            push(&field.name, (ChangeKind::ColumnAdded, false, true));
+        }
+    }
+}
+
+/// Append the changes between two versions of a domain, which carry over to
+/// every column of the domain.
+fn diff_domains(old: &DomainType, new: &DomainType, changes: &mut Vec<Change>) {
+    let name = new.qualified_name();
+    let mut push = |(kind, breaks_readers, breaks_writers)| {
+        changes.push(Change {
+            table: name.clone(),
+            column: None,
+            kind,
+            breaks_readers,
+            breaks_writers,
+        });
+    };
+    if let Some(change) = type_change(&old.base_type, &new.base_type) {
+        push(change);
+    }
+    if old.not_null != new.not_null {
+        push((
+            ChangeKind::NullabilityChanged {
+                nullable: !new.not_null,
+            },
+            true,
+            new.not_null,
+        ));
+    }
+    if old.has_default != new.has_default {
+        push((
+            ChangeKind::DefaultChanged {
+                has_default: new.has_default,
+            },
+            false,
+            !new.has_default && new.not_null,
+        ));
+    }
+}
+
+/// Append the changes between two versions of a function's overloads.
+///
+/// Overloads are matched by their argument types: one whose types changed
+/// shows up as a removal plus an addition.
+fn diff_functions(old: &[Function], new: &[Function], changes: &mut Vec<Change>) {
+    let find = |functions: &[Function], function: &Function| {
+        let name = signature(function);
+        functions.iter().find(|f| signature(f) == name).cloned()
+    };
+    let mut overload_changes = Vec::new();
+    for function in old {
+        match find(new, function) {
+            Some(new_function) => diff_function(function, &new_function, &mut overload_changes),
+            None => overload_changes.push(table_change(
+                &signature(function),
+                ChangeKind::FunctionRemoved,
+            )),
+        }
+    }
+    for function in new.iter().filter(|f| find(old, f).is_none()) {
+        overload_changes.push(table_change(
+            &signature(function),
+            ChangeKind::FunctionAdded,
+        ));
+    }
+    overload_changes.sort_by(|a, b| a.table.cmp(&b.table));
+    changes.extend(overload_changes);
+}
+
+/// Append the changes between two versions of a function overload.
+fn diff_function(old: &Function, new: &Function, changes: &mut Vec<Change>) {
+    let name = signature(new);
+    let mut push = |kind, breaks_readers, breaks_writers| {
+        changes.push(Change {
+            table: name.clone(),
+            column: None,
+            kind,
+            breaks_readers,
+            breaks_writers,
+        });
+    };
+    if old.returns != new.returns {
+        // A set of the same values still reads the same way
+        let same_values = match (&old.returns, &new.returns) {
+            (
+                FunctionReturn::Scalar(a) | FunctionReturn::SetOf(a),
+                FunctionReturn::Scalar(b) | FunctionReturn::SetOf(b),
+            ) => a.to_rust_type() == b.to_rust_type(),
+            _ => false,
+        };
+        push(
+            ChangeKind::ReturnTypeChanged {
+                from: old.returns.clone(),
+                to: new.returns.clone(),
+            },
+            !same_values,
+            false,
+        );
+    }
+    if old.strict != new.strict {
+        // Results of calls with non-null arguments become nullable
+        push(
+            ChangeKind::StrictChanged { strict: new.strict },
+            !new.strict,
+            false,
+        );
+    }
+    if old.args != new.args {
+        // Calls leaving out an argument that lost its default stop compiling
+        let max = old.args.len().max(new.args.len()) + 1;
+        let rejected = (0..=max).any(|count| old.accepts(count) && !new.accepts(count));
+        push(ChangeKind::ArgumentsChanged, rejected, rejected);
+    }
+}
+
+/// A function overload's name with its argument types: `public.add(integer, integer)`.
+fn signature(function: &Function) -> String {
+    let args: Vec<_> = function
+        .args
+        .iter()
+        .map(|arg| arg.data_type.to_string())
+        .collect();
+    format!("{}({})", function.qualified_name(), args.join(", "))
+}
+
+/// Append the changes between two versions of a sequence.
+///
+/// `nextval` and friends return `i64` whatever the sequence's type, but a
+/// narrower type runs out of values sooner, failing inserts.
+fn diff_sequences(old: &Sequence, new: &Sequence, changes: &mut Vec<Change>) {
+    if old.data_type == new.data_type {
+        return;
+    }
+    let width = |data_type: &PostgresType| match data_type {
+        PostgresType::SmallInt => 2,
+        PostgresType::Integer => 4,
+        _ => 8,
+    };
+    changes.push(Change {
+        table: new.qualified_name(),
+        column: None,
+        kind: ChangeKind::TypeChanged {
+            from: old.data_type.clone(),
+            to: new.data_type.clone(),
+        },
+        breaks_readers: false,
+        breaks_writers: width(&new.data_type) < width(&old.data_type),
+    });
+}
+
+/// Append the changes between two versions of a view.
+///
+/// Views are read-only here, so only readers break: when a column disappears
+/// or changes its Rust type.
+fn diff_views(old: &View, new: &View, changes: &mut Vec<Change>) {
+    let name = new.qualified_name();
+    if old.definition != new.definition {
+        changes.push(table_change(&name, ChangeKind::ViewDefinitionChanged));
+    }
+
+    let mut push = |column: &str, kind: ChangeKind, breaks_readers: bool| {
+        changes.push(Change {
+            table: name.clone(),
+            column: Some(column.to_string()),
+            kind,
+            breaks_readers,
+            breaks_writers: false,
+        });
+    };
+    let find = |columns: &[QueryColumn], name: &str| {
+        columns
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+            .map(|c| c.rust_type.clone())
+    };
+    for column in &old.columns {
+        match find(&new.columns, &column.name) {
+            Some(rust_type) if rust_type != column.rust_type => push(
+                &column.name,
+                ChangeKind::ResultTypeChanged {
+                    from: column.rust_type.clone(),
+                    to: rust_type,
+                },
+                true,
+            ),
+            Some(_) => {}
+            None => push(&column.name, ChangeKind::ColumnRemoved, true),
+        }
+    }
+    for column in &new.columns {
+        if find(&old.columns, &column.name).is_none() {
+            push(&column.name, ChangeKind::ColumnAdded, false);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &str, new: &str) -> SchemaDiff {
+        Schema::from_sql(old)
+            .unwrap()
+            .diff(&Schema::from_sql(new).unwrap())
+    }
+
+    fn find<'a>(diff: &'a SchemaDiff, column: &str) -> Vec<&'a Change> {
+        diff.changes
+            .iter()
+            .filter(|change| change.column.as_deref() == Some(column))
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_tables_and_columns() {
+        let old = r#"
+            CREATE TABLE users (
+                id integer PRIMARY KEY,
+                name text NOT NULL,
+                nickname text,
+                age integer,
+                created_at timestamptz NOT NULL DEFAULT now()
+            );
+            CREATE TABLE sessions (id integer);
+        "#;
+        let new = r#"
+            CREATE TABLE users (
+                id integer PRIMARY KEY,
+                name varchar(100),
+                age bigint,
+                created_at timestamptz NOT NULL,
+                email text NOT NULL UNIQUE,
+                bio text
+            );
+            CREATE TABLE posts (id integer);
+        "#;
+
+        let diff = diff(old, new);
+        assert!(diff.has_breaking_changes());
+
+        // Tables come in name order
+        let posts = &diff.changes[0];
+        assert_eq!(posts.table, "public.posts");
+        assert_eq!(posts.kind, ChangeKind::TableAdded);
+        assert!(!posts.is_breaking());
+        let sessions = &diff.changes[1];
+        assert_eq!(sessions.table, "public.sessions");
+        assert_eq!(sessions.kind, ChangeKind::TableRemoved);
+        assert!(sessions.breaks_readers && sessions.breaks_writers);
+
+        // text -> varchar keeps the Rust type but rejects long values;
+        // NOT NULL -> nullable changes the Rust type
+        let name = find(&diff, "name");
+        assert_eq!(name.len(), 2);
+        assert!(!name[0].breaks_readers && name[0].breaks_writers);
+        assert_eq!(
+            name[1].kind,
+            ChangeKind::NullabilityChanged { nullable: true }
+        );
+        assert!(name[1].breaks_readers && !name[1].breaks_writers);
+
+        let age = find(&diff, "age");
+        assert_eq!(
+            age[0].kind,
+            ChangeKind::TypeChanged {
+                from: PostgresType::Integer,
+                to: PostgresType::BigInt
+            }
+        );
+        assert!(age[0].breaks_readers && age[0].breaks_writers);
+
+        let nickname = find(&diff, "nickname");
+        assert_eq!(nickname[0].kind, ChangeKind::ColumnRemoved);
+
+        let created_at = find(&diff, "created_at");
+        assert_eq!(
+            created_at[0].kind,
+            ChangeKind::DefaultChanged { has_default: false }
+        );
+        assert!(!created_at[0].breaks_readers && created_at[0].breaks_writers);
+
+        // A required new column breaks inserts, an optional one doesn't
+        let email = find(&diff, "email");
+        assert_eq!(email[0].kind, ChangeKind::ColumnAdded);
+        assert!(!email[0].breaks_readers && email[0].breaks_writers);
+        assert!(!find(&diff, "bio")[0].is_breaking());
+
+        assert!(find(&diff, "id").is_empty());
+        assert_eq!(
+            diff.to_string().lines().nth(1),
+            Some("public.sessions: table removed (breaks readers and writers)")
+        );
+    }
+
+    #[test]
+    fn test_diff_constraints() {
+        let old = r#"
+            CREATE TABLE users (id integer PRIMARY KEY);
+            CREATE TABLE orders (
+                id integer,
+                user_id integer,
+                status text CHECK (status IN ('new', 'paid')),
+                total integer
+            );
+        "#;
+        let new = r#"
+            CREATE TABLE users (id integer PRIMARY KEY);
+            CREATE TABLE orders (
+                id integer PRIMARY KEY,
+                user_id integer REFERENCES users (id),
+                status text,
+                total integer GENERATED ALWAYS AS (1) STORED
+            );
+        "#;
+
+        let changes = diff(old, new);
+        let kinds: Vec<String> = changes.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "public.orders.id: now NOT NULL (breaks readers and writers)",
+                "public.orders.total: default added",
+                "public.orders.total: now generated (breaks writers)",
+                "public.orders: primary key orders_pkey (id) added (breaks writers)",
+                "public.orders: foreign key orders_user_id_fkey added (breaks writers)",
+                "public.orders: check orders_status_check removed",
+            ]
+        );
+
+        assert!(diff(old, old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_type_modifiers() {
+        let old = r#"
+            CREATE TABLE users (a text, b varchar(10), c varchar(10), d char(2), e char);
+        "#;
+        let new = r#"
+            CREATE TABLE users (a varchar(10), b varchar(20), c varchar(5), d text, e char(3));
+        "#;
+
+        let changes = diff(old, new);
+        let kinds: Vec<String> = changes.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "public.users.a: type changed from text to character varying(10) (breaks writers)",
+                "public.users.b: type changed from character varying(10) to character varying(20)",
+                "public.users.c: type changed from character varying(10) to character varying(5) (breaks writers)",
+                "public.users.d: type changed from character(2) to text",
+                "public.users.e: type changed from character to character(3)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_dropped_keys() {
+        let old = r#"
+            CREATE TABLE users (
+                id integer PRIMARY KEY,
+                email text UNIQUE,
+                org integer,
+                login text,
+                UNIQUE (org, login)
+            );
+            CREATE UNIQUE INDEX users_lower_email ON users (lower(email));
+        "#;
+        let new = r#"
+            CREATE TABLE users (
+                id integer PRIMARY KEY,
+                email text UNIQUE,
+                org integer,
+                login text,
+                UNIQUE (org, login)
+            );
+            ALTER TABLE users DROP CONSTRAINT users_pkey;
+            ALTER TABLE users DROP CONSTRAINT users_email_key;
+            ALTER TABLE users DROP CONSTRAINT users_org_login_key;
+            CREATE UNIQUE INDEX users_login_org ON users (login, org);
+        "#;
+
+        // `ON CONFLICT (id)` and `ON CONFLICT (email)` stop working; the
+        // (org, login) key is the same constraint whatever it is declared as
+        let changes = diff(old, new);
+        let kinds: Vec<String> = changes.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "public.users: primary key users_pkey (id) removed (breaks writers)",
+                "public.users: unique key users_email_key (email) removed (breaks writers)",
+            ]
+        );
+
+        let new = r#"
+            CREATE TABLE users (id integer PRIMARY KEY, email text UNIQUE, org integer, login text);
+        "#;
+        let changes = diff(old, new);
+        let kinds: Vec<String> = changes.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            kinds,
+            ["public.users: unique key users_org_login_key (org, login) removed (breaks writers)"]
+        );
+    }
+
+    #[test]
+    fn test_diff_enums_and_views() {
+        let old = r#"
+            CREATE TYPE mood AS ENUM ('happy', 'sad', 'meh');
+            CREATE TYPE color AS ENUM ('red');
+            CREATE TABLE users (id integer NOT NULL, name text, age integer);
+            CREATE VIEW adults AS SELECT id, name, age FROM users WHERE age >= 18;
+            CREATE VIEW names AS SELECT name FROM users;
+        "#;
+        let new = r#"
+            CREATE TYPE mood AS ENUM ('happy', 'meh', 'angry');
+            CREATE TYPE size AS ENUM ('small');
+            CREATE TABLE users (id integer NOT NULL, name text, age integer);
+            CREATE VIEW adults AS SELECT id, id AS age, now() AS seen FROM users WHERE age >= 21;
+            CREATE VIEW ids AS SELECT id FROM users;
+        "#;
+
+        let changes = diff(old, new);
+        let kinds: Vec<String> = changes.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "public.color: enum removed (breaks readers and writers)",
+                "public.mood: label 'sad' removed (breaks readers and writers)",
+                "public.mood: label 'angry' added",
+                "public.size: enum added",
+                "public.adults: view definition changed",
+                "public.adults.name: column removed (breaks readers)",
+                "public.adults.age: type changed from Option<i32> to i32 (breaks readers)",
+                "public.adults.seen: column added",
+                "public.ids: view added",
+                "public.names: view removed (breaks readers)",
+            ]
+        );
+        assert!(diff(old, old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_types_functions_and_sequences() {
+        let old = r#"
+            CREATE TYPE pair AS (x integer, y text);
+            CREATE TYPE point3 AS (x float8, y float8, z float8);
+            CREATE DOMAIN email AS text;
+            CREATE DOMAIN positive AS integer CHECK (VALUE > 0);
+            CREATE FUNCTION add(a integer, b integer) RETURNS integer AS 'SELECT a + b' LANGUAGE sql STRICT;
+            CREATE FUNCTION add(a text, b text) RETURNS text AS 'SELECT a || b' LANGUAGE sql;
+            CREATE FUNCTION greet(name text DEFAULT 'you') RETURNS text AS 'SELECT name' LANGUAGE sql;
+            CREATE FUNCTION gone() RETURNS integer AS 'SELECT 1' LANGUAGE sql;
+            CREATE SEQUENCE ticket_seq AS bigint;
+            CREATE SEQUENCE order_seq AS integer;
+        "#;
+        let new = r#"
+            CREATE TYPE pair AS (x bigint, z text);
+            CREATE DOMAIN email AS varchar(100) NOT NULL;
+            CREATE DOMAIN positive AS integer CHECK (VALUE > 0);
+            CREATE FUNCTION add(a integer, b integer) RETURNS bigint AS 'SELECT a + b' LANGUAGE sql;
+            CREATE FUNCTION add(a text, b text) RETURNS text AS 'SELECT a || b' LANGUAGE sql;
+            CREATE FUNCTION add(a numeric, b numeric) RETURNS numeric AS 'SELECT a + b' LANGUAGE sql;
+            CREATE FUNCTION greet(name text) RETURNS text AS 'SELECT name' LANGUAGE sql;
+            CREATE SEQUENCE ticket_seq AS integer;
+            CREATE SEQUENCE order_seq AS bigint;
+            CREATE SEQUENCE invoice_seq;
+        "#;
+
+        let changes = diff(old, new);
+        let kinds: Vec<String> = changes.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            kinds,
+            [
+                "public.pair.x: type changed from integer to bigint (breaks readers and writers)",
+                "public.pair.y: column removed (breaks readers and writers)",
+                "public.pair.z: column added (breaks writers)",
+                "public.point3: composite type removed (breaks readers and writers)",
+                "public.email: type changed from text to character varying(100) (breaks writers)",
+                "public.email: now NOT NULL (breaks readers and writers)",
+                "public.add(integer, integer): return type changed from integer to bigint (breaks readers)",
+                "public.add(integer, integer): no longer STRICT (breaks readers)",
+                "public.add(numeric, numeric): function added",
+                "public.gone(): function removed (breaks readers and writers)",
+                "public.greet(text): arguments changed (breaks readers and writers)",
+                "public.invoice_seq: sequence added",
+                "public.order_seq: type changed from integer to bigint",
+                "public.ticket_seq: type changed from bigint to integer (breaks writers)",
+            ]
+        );
+        assert!(diff(old, old).is_empty());
+    }
+}
@@ -15,18 +15,91 @@ pub enum Error {
     #[error("Unknown table: {0}")]
     UnknownTable(String),
 
+    #[error("Unknown type: {0}")]
+    UnknownType(String),
+
+    #[error("Unknown schema: {0}")]
+    UnknownSchema(String),
+
+    #[error("Unknown index: {0}")]
+    UnknownIndex(String),
+
+    #[error("Unknown sequence: {0}")]
+    UnknownSequence(String),
+
+    #[error("Unknown function: {0}")]
+    UnknownFunction(String),
+
     #[error("Unknown column '{column}' in table '{table}'")]
     UnknownColumn { table: String, column: String },
 
+    #[error("Duplicate definition of {kind} {name}")]
+    DuplicateDefinition { kind: &'static str, name: String },
+
     #[error("Ambiguous column '{0}' - exists in multiple tables")]
     AmbiguousColumn(String),
 
     #[error("Type mismatch: expected {expected}, got {actual}")]
     TypeMismatch { expected: String, actual: String },
 
+    #[error("Function '{function}' does not accept {count} argument(s)")]
+    WrongArgumentCount { function: String, count: usize },
+
+    #[error("No overload of function '{0}' accepts the argument types")]
+    NoMatchingFunction(String),
+
+    #[error("Call to function '{0}' is ambiguous - several overloads match the argument types")]
+    AmbiguousFunction(String),
+
     #[error("Invalid query: {0}")]
     InvalidQuery(String),
 
+    #[error("Cannot write to column '{column}' in table '{table}': {reason}")]
+    ReadOnlyColumn {
+        table: String,
+        column: String,
+        reason: String,
+    },
+
+    #[error("Value '{value}' is not allowed for column '{column}' in table '{table}' (expected one of: {allowed})")]
+    InvalidValue {
+        table: String,
+        column: String,
+        value: String,
+        allowed: String,
+    },
+
+    #[error("Value '{value}' is not a label of enum '{type_name}' (expected one of: {allowed})")]
+    InvalidEnumValue {
+        type_name: String,
+        value: String,
+        allowed: String,
+    },
+
+    #[error("Failed to apply migration {path}: {source}")]
+    Migration {
+        path: String,
+        #[source]
+        source: Box<Error>,
+    },
+
+    #[error("Failed to load schema file {path}: {source}")]
+    SchemaFile {
+        path: String,
+        #[source]
+        source: Box<Error>,
+    },
+
+    #[error("Failed to infer columns of view {name}: {reason}")]
+    View { name: String, reason: String },
+
+    #[error("Invalid schema snapshot: {0}")]
+    Snapshot(String),
+
+    #[cfg(feature = "runtime")]
+    #[error("Database error: {0}")]
+    Database(#[from] tokio_postgres::Error),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 }
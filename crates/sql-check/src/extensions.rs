@@ -0,0 +1,144 @@
+//! Types and functions that come with Postgres extensions.
+//!
+//! These are only recognized once the schema creates the extension
+//! (`CREATE EXTENSION hstore`), in the schema the extension was created in.
+
+use crate::types::PostgresType;
+
+/// The extensions whose types and functions sql-check knows.
+pub const KNOWN_EXTENSIONS: &[&str] = &["citext", "hstore", "ltree", "postgis"];
+
+/// The extension defining the type `name`, and the type.
+pub fn extension_type(name: &str) -> Option<(&'static str, PostgresType)> {
+    Some(match name.to_lowercase().as_str() {
+        "citext" => ("citext", PostgresType::Citext),
+        "hstore" => ("hstore", PostgresType::Hstore),
+        "ltree" => ("ltree", PostgresType::Ltree),
+        "geometry" => ("postgis", PostgresType::Geometry),
+        "geography" => ("postgis", PostgresType::Geography),
+        _ => return None,
+    })
+}
+
+/// The extension defining the function `name` called with a first argument
+/// of type `first_arg` (if known), and what the function returns.
+///
+/// Names common enough to be user functions too (`index`, `delete`, ...) are
+/// only recognized when the first argument has the extension's type, and
+/// PostGIS functions with a geography overload return geography for one.
+/// Extension functions are STRICT: they return NULL only for NULL arguments.
+pub fn extension_function(
+    name: &str,
+    first_arg: Option<&PostgresType>,
+) -> Option<(&'static str, PostgresType)> {
+    let text_array = PostgresType::Array(Box::new(PostgresType::Text));
+    Some(match (name.to_lowercase().as_str(), first_arg) {
+        // hstore
+        ("hstore", _) => ("hstore", PostgresType::Hstore),
+        ("slice" | "delete", Some(PostgresType::Hstore)) => ("hstore", PostgresType::Hstore),
+        ("akeys", _) => ("hstore", text_array),
+        ("exist" | "defined", Some(PostgresType::Hstore)) => ("hstore", PostgresType::Boolean),
+        ("hstore_to_json" | "hstore_to_json_loose", _) => ("hstore", PostgresType::Json),
+        ("hstore_to_jsonb" | "hstore_to_jsonb_loose", _) => ("hstore", PostgresType::Jsonb),
+
+        // ltree
+        ("subltree" | "subpath" | "text2ltree" | "lca", _) => ("ltree", PostgresType::Ltree),
+        ("nlevel", _) => ("ltree", PostgresType::Integer),
+        ("index", Some(PostgresType::Ltree)) => ("ltree", PostgresType::Integer),
+        ("ltree2text", _) => ("ltree", PostgresType::Text),
+
+        // PostGIS
+        ("st_astext" | "st_asewkt" | "st_asgeojson" | "st_geometrytype", _) => {
+            ("postgis", PostgresType::Text)
+        }
+        ("st_asbinary" | "st_asewkb", _) => ("postgis", PostgresType::Bytea),
+        (
+            "st_x" | "st_y" | "st_z" | "st_distance" | "st_area" | "st_length" | "st_perimeter"
+            | "st_azimuth",
+            _,
+        ) => ("postgis", PostgresType::DoublePrecision),
+        ("st_srid" | "st_npoints" | "st_numgeometries", _) => ("postgis", PostgresType::Integer),
+        (
+            "st_intersects" | "st_contains" | "st_within" | "st_dwithin" | "st_covers"
+            | "st_coveredby" | "st_touches" | "st_crosses" | "st_overlaps" | "st_disjoint"
+            | "st_equals" | "st_isvalid" | "st_isempty",
+            _,
+        ) => ("postgis", PostgresType::Boolean),
+        (
+            "st_buffer" | "st_centroid" | "st_intersection" | "st_closestpoint" | "st_setsrid"
+            | "st_segmentize",
+            Some(PostgresType::Geography),
+        ) => ("postgis", PostgresType::Geography),
+        (
+            "st_point" | "st_makepoint" | "st_makeenvelope" | "st_setsrid" | "st_transform"
+            | "st_geomfromtext" | "st_geomfromewkt" | "st_geomfromgeojson" | "st_buffer"
+            | "st_centroid" | "st_envelope" | "st_pointonsurface" | "st_closestpoint"
+            | "st_intersection" | "st_difference" | "st_union" | "st_collect" | "st_simplify"
+            | "st_segmentize",
+            _,
+        ) => ("postgis", PostgresType::Geometry),
+        ("st_geogfromtext", _) => ("postgis", PostgresType::Geography),
+
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extension_lookups() {
+        assert_eq!(
+            extension_type("HSTORE"),
+            Some(("hstore", PostgresType::Hstore))
+        );
+        assert_eq!(extension_type("text"), None);
+        assert_eq!(
+            extension_function("nlevel", None),
+            Some(("ltree", PostgresType::Integer))
+        );
+        assert_eq!(
+            extension_function("ST_AsText", Some(&PostgresType::Geometry)),
+            Some(("postgis", PostgresType::Text))
+        );
+
+        // Common names need an argument of the extension's type
+        assert_eq!(extension_function("index", None), None);
+        assert_eq!(
+            extension_function("delete", Some(&PostgresType::Text)),
+            None
+        );
+        assert_eq!(
+            extension_function("index", Some(&PostgresType::Ltree)),
+            Some(("ltree", PostgresType::Integer))
+        );
+        assert_eq!(
+            extension_function("delete", Some(&PostgresType::Hstore)),
+            Some(("hstore", PostgresType::Hstore))
+        );
+
+        // Geography in, geography out
+        assert_eq!(
+            extension_function("st_buffer", Some(&PostgresType::Geography)),
+            Some(("postgis", PostgresType::Geography))
+        );
+        assert_eq!(
+            extension_function("st_buffer", Some(&PostgresType::Geometry)),
+            Some(("postgis", PostgresType::Geometry))
+        );
+
+        // Every type and function belongs to a known extension
+        for (extension, _) in ["citext", "geography"]
+            .into_iter()
+            .filter_map(extension_type)
+            .chain(
+                ["akeys", "lca", "st_buffer"]
+                    .into_iter()
+                    .filter_map(|name| extension_function(name, None)),
+            )
+        {
+            assert!(KNOWN_EXTENSIONS.contains(&extension), "{extension}");
+        }
+    }
+}
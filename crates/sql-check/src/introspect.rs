@@ -0,0 +1,279 @@
+//! Schema introspection of a live database.
+//!
+//! pg_dump output varies between server versions and carries statements
+//! sql-check has no use for (ownership, grants, psql meta-commands). This
+//! module reads `pg_catalog` instead and writes only the DDL [`Schema`]
+//! models, with every name qualified and every object in a fixed order, so the
+//! same database always produces the same file.
+
+use crate::error::Result;
+use crate::schema::Schema;
+use tokio_postgres::Client;
+
+/// Namespaces to read, as a condition on `n.nspname`. `$1` is the list of
+/// requested namespaces; an empty list means all non-system namespaces.
+const NAMESPACE_FILTER: &str = "n.nspname !~ '^pg_' AND n.nspname <> 'information_schema' \
+    AND (cardinality($1::text[]) = 0 OR n.nspname = ANY($1::text[]))";
+
+/// Each section of the dump: a heading and a query returning one statement
+/// per row. Sections are ordered so every object exists before it is used.
+///
+/// Domains, composite types and views can be built on others of their kind,
+/// so they are ordered by creation (OID); everything else by name.
+/// `{ns}` is replaced with [`NAMESPACE_FILTER`], and `{local:catalog:oid}`
+/// with a condition excluding objects that belong to an extension.
+const SECTIONS: &[(&str, &str)] = &[
+    (
+        "Schemas",
+        "SELECT format('CREATE SCHEMA %I;', n.nspname)
+         FROM pg_namespace n
+         WHERE {ns} AND n.nspname <> 'public'
+         ORDER BY n.nspname",
+    ),
+    (
+        "Extensions",
+        "SELECT format('CREATE EXTENSION IF NOT EXISTS %I WITH SCHEMA %I;', e.extname, n.nspname)
+         FROM pg_extension e
+         JOIN pg_namespace n ON n.oid = e.extnamespace
+         WHERE {ns} AND e.extname <> 'plpgsql'
+         ORDER BY e.extname",
+    ),
+    (
+        "Enums",
+        "SELECT format('CREATE TYPE %I.%I AS ENUM (%s);', n.nspname, t.typname,
+             (SELECT string_agg(quote_literal(e.enumlabel), ', ' ORDER BY e.enumsortorder)
+              FROM pg_enum e WHERE e.enumtypid = t.oid))
+         FROM pg_type t
+         JOIN pg_namespace n ON n.oid = t.typnamespace
+         WHERE {ns} AND t.typtype = 'e' AND {local:pg_type:t.oid}
+         ORDER BY n.nspname, t.typname",
+    ),
+    (
+        "Domains",
+        "SELECT format('CREATE DOMAIN %I.%I AS %s%s%s%s;', n.nspname, t.typname,
+             format_type(t.typbasetype, t.typtypmod),
+             ' DEFAULT ' || t.typdefault,
+             CASE WHEN t.typnotnull THEN ' NOT NULL' END,
+             (SELECT string_agg(format(' CONSTRAINT %I %s', c.conname, pg_get_constraintdef(c.oid)), ''
+                  ORDER BY c.conname)
+              FROM pg_constraint c WHERE c.contypid = t.oid AND c.contype = 'c'))
+         FROM pg_type t
+         JOIN pg_namespace n ON n.oid = t.typnamespace
+         WHERE {ns} AND t.typtype = 'd' AND {local:pg_type:t.oid}
+         ORDER BY t.oid",
+    ),
+    (
+        "Composite types",
+        "SELECT format('CREATE TYPE %I.%I AS (%s);', n.nspname, t.typname,
+             (SELECT string_agg(format('%I %s', a.attname, format_type(a.atttypid, a.atttypmod)), ', '
+                  ORDER BY a.attnum)
+              FROM pg_attribute a
+              WHERE a.attrelid = t.typrelid AND a.attnum > 0 AND NOT a.attisdropped))
+         FROM pg_type t
+         JOIN pg_class c ON c.oid = t.typrelid
+         JOIN pg_namespace n ON n.oid = t.typnamespace
+         WHERE {ns} AND t.typtype = 'c' AND c.relkind = 'c' AND {local:pg_type:t.oid}
+         ORDER BY t.oid",
+    ),
+    (
+        // Identity sequences come with their column
+        "Sequences",
+        "SELECT format('CREATE SEQUENCE %I.%I AS %s;', n.nspname, c.relname, format_type(s.seqtypid, NULL))
+         FROM pg_class c
+         JOIN pg_sequence s ON s.seqrelid = c.oid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE {ns} AND c.relkind = 'S' AND {local:pg_class:c.oid}
+           AND NOT EXISTS (SELECT 1 FROM pg_depend d
+                           WHERE d.classid = 'pg_class'::regclass AND d.objid = c.oid AND d.deptype = 'i')
+         ORDER BY n.nspname, c.relname",
+    ),
+    (
+        "Tables",
+        "SELECT format(E'CREATE %sTABLE %I.%I (\\n%s\\n)%s%s;',
+             CASE WHEN c.relpersistence = 'u' THEN 'UNLOGGED ' ELSE '' END, n.nspname, c.relname,
+             (SELECT string_agg(def, E',\\n' ORDER BY kind, pos) FROM (
+                  SELECT 0 AS kind, a.attnum AS pos,
+                      format('    %I %s%s%s%s', a.attname, format_type(a.atttypid, a.atttypmod),
+                          CASE WHEN a.attgenerated = 's'
+                              THEN format(' GENERATED ALWAYS AS (%s) STORED', pg_get_expr(ad.adbin, ad.adrelid))
+                              ELSE ' DEFAULT ' || pg_get_expr(ad.adbin, ad.adrelid) END,
+                          CASE a.attidentity
+                              WHEN 'a' THEN ' GENERATED ALWAYS AS IDENTITY'
+                              WHEN 'd' THEN ' GENERATED BY DEFAULT AS IDENTITY' END,
+                          CASE WHEN a.attnotnull THEN ' NOT NULL' END) AS def
+                  FROM pg_attribute a
+                  LEFT JOIN pg_attrdef ad ON ad.adrelid = a.attrelid AND ad.adnum = a.attnum
+                  WHERE a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+                  UNION ALL
+                  SELECT 1, row_number() OVER (ORDER BY position(co.contype IN 'puc'), co.conname),
+                      format('    CONSTRAINT %I %s', co.conname, pg_get_constraintdef(co.oid))
+                  FROM pg_constraint co
+                  WHERE co.conrelid = c.oid AND co.contype IN ('p', 'u', 'c') AND co.conislocal
+              ) defs),
+             (SELECT E'\\nINHERITS (' || string_agg(format('%I.%I', pn.nspname, p.relname), ', '
+                  ORDER BY i.inhseqno) || ')'
+              FROM pg_inherits i
+              JOIN pg_class p ON p.oid = i.inhparent
+              JOIN pg_namespace pn ON pn.oid = p.relnamespace
+              WHERE i.inhrelid = c.oid AND NOT c.relispartition),
+             CASE WHEN c.relkind = 'p' THEN E'\\nPARTITION BY ' || pg_get_partkeydef(c.oid) END)
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE {ns} AND c.relkind IN ('r', 'p') AND c.relpersistence <> 't' AND {local:pg_class:c.oid}
+         -- Tables come after the ones they inherit from
+         ORDER BY (WITH RECURSIVE ancestors(oid) AS (
+                       SELECT i.inhparent FROM pg_inherits i
+                       WHERE i.inhrelid = c.oid AND NOT c.relispartition
+                       UNION
+                       SELECT i.inhparent FROM pg_inherits i JOIN ancestors a ON i.inhrelid = a.oid)
+                   SELECT count(*) FROM ancestors),
+             n.nspname, c.relname",
+    ),
+    (
+        // Partitions are created as tables, then attached to their parent
+        "Partitions",
+        "SELECT format('ALTER TABLE ONLY %I.%I ATTACH PARTITION %I.%I %s;',
+             pn.nspname, p.relname, n.nspname, c.relname, pg_get_expr(c.relpartbound, c.oid))
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         JOIN pg_inherits i ON i.inhrelid = c.oid
+         JOIN pg_class p ON p.oid = i.inhparent
+         JOIN pg_namespace pn ON pn.oid = p.relnamespace
+         WHERE {ns} AND c.relkind IN ('r', 'p') AND c.relispartition AND {local:pg_class:c.oid}
+         ORDER BY n.nspname, c.relname",
+    ),
+    (
+        // Added once all tables exist, since they can reference each other.
+        // Those of partitioned tables carry over to their partitions.
+        "Foreign keys",
+        "SELECT format('ALTER TABLE %I.%I ADD CONSTRAINT %I %s;',
+             n.nspname, c.relname, co.conname, pg_get_constraintdef(co.oid))
+         FROM pg_constraint co
+         JOIN pg_class c ON c.oid = co.conrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE {ns} AND co.contype = 'f' AND co.conparentid = 0 AND c.relkind IN ('r', 'p')
+           AND {local:pg_class:c.oid}
+         ORDER BY n.nspname, c.relname, co.conname",
+    ),
+    (
+        "Sequence ownership",
+        "SELECT format('ALTER SEQUENCE %I.%I OWNED BY %I.%I.%I;',
+             n.nspname, s.relname, tn.nspname, t.relname, a.attname)
+         FROM pg_depend d
+         JOIN pg_class s ON s.oid = d.objid
+         JOIN pg_namespace n ON n.oid = s.relnamespace
+         JOIN pg_class t ON t.oid = d.refobjid
+         JOIN pg_namespace tn ON tn.oid = t.relnamespace
+         JOIN pg_attribute a ON a.attrelid = t.oid AND a.attnum = d.refobjsubid
+         WHERE {ns} AND d.classid = 'pg_class'::regclass AND d.refclassid = 'pg_class'::regclass
+           AND d.deptype = 'a' AND s.relkind = 'S' AND {local:pg_class:s.oid}
+         ORDER BY n.nspname, s.relname",
+    ),
+    (
+        "Functions",
+        "SELECT rtrim(pg_get_functiondef(p.oid), E'\\n') || ';'
+         FROM pg_proc p
+         JOIN pg_namespace n ON n.oid = p.pronamespace
+         WHERE {ns} AND p.prokind = 'f' AND {local:pg_proc:p.oid}
+         ORDER BY n.nspname, p.proname, pg_get_function_identity_arguments(p.oid)",
+    ),
+    (
+        "Views",
+        "SELECT format(E'CREATE %sVIEW %I.%I AS\\n%s%s',
+             CASE WHEN c.relkind = 'm' THEN 'MATERIALIZED ' ELSE '' END, n.nspname, c.relname,
+             rtrim(pg_get_viewdef(c.oid), ';'),
+             CASE WHEN c.relkind = 'm' THEN E'\\n  WITH NO DATA;' ELSE ';' END)
+         FROM pg_class c
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE {ns} AND c.relkind IN ('v', 'm') AND {local:pg_class:c.oid}
+         ORDER BY c.oid",
+    ),
+    (
+        // Indexes backing a constraint are created by it
+        "Indexes",
+        "SELECT pg_get_indexdef(i.indexrelid) || ';'
+         FROM pg_index i
+         JOIN pg_class ic ON ic.oid = i.indexrelid
+         JOIN pg_class c ON c.oid = i.indrelid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         WHERE {ns} AND c.relkind IN ('r', 'p', 'm') AND {local:pg_class:c.oid}
+           AND NOT EXISTS (SELECT 1 FROM pg_constraint co
+                           WHERE co.conindid = i.indexrelid AND co.conrelid = i.indrelid
+                             AND co.contype IN ('p', 'u', 'x'))
+         ORDER BY n.nspname, c.relname, ic.relname",
+    ),
+    (
+        "Comments",
+        "SELECT CASE WHEN d.objsubid = 0
+                 THEN format('COMMENT ON TABLE %I.%I IS %L;', n.nspname, c.relname, d.description)
+                 ELSE format('COMMENT ON COLUMN %I.%I.%I IS %L;', n.nspname, c.relname, a.attname, d.description)
+             END
+         FROM pg_description d
+         JOIN pg_class c ON c.oid = d.objoid
+         JOIN pg_namespace n ON n.oid = c.relnamespace
+         LEFT JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = d.objsubid
+         WHERE {ns} AND d.classoid = 'pg_class'::regclass AND {local:pg_class:c.oid}
+           AND (c.relkind IN ('r', 'p') OR (d.objsubid > 0 AND c.relkind IN ('v', 'm')))
+         ORDER BY n.nspname, c.relname, d.objsubid",
+    ),
+];
+
+/// Write the schema of a database as SQL that [`Schema::from_sql`] loads.
+///
+/// Only the given namespaces are read, or every non-system namespace if none
+/// are given. Objects that belong to an extension are left to its
+/// `CREATE EXTENSION`. The catalog is read in a single read-only
+/// transaction, so the dump is a consistent snapshot.
+pub async fn dump_sql(client: &mut Client, namespaces: &[&str]) -> Result<String> {
+    let transaction = client
+        .build_transaction()
+        .read_only(true)
+        .isolation_level(tokio_postgres::IsolationLevel::RepeatableRead)
+        .start()
+        .await?;
+    // With an empty search path, the catalog functions qualify every name
+    transaction
+        .batch_execute("SET LOCAL search_path = ''")
+        .await?;
+
+    let mut sql = String::from("--\n-- Schema dumped by sql-check\n--\n\n");
+    for (heading, query) in SECTIONS {
+        let rows = transaction
+            .query(&section_query(query), &[&namespaces])
+            .await?;
+        if rows.is_empty() {
+            continue;
+        }
+        sql.push_str(&format!("-- {}\n\n", heading));
+        for row in rows {
+            let statement: String = row.get(0);
+            sql.push_str(statement.trim_end());
+            sql.push_str("\n\n");
+        }
+    }
+    transaction.commit().await?;
+
+    Ok(sql.trim_end().to_string() + "\n")
+}
+
+/// Read the schema of a database. See [`dump_sql`].
+pub async fn introspect(client: &mut Client, namespaces: &[&str]) -> Result<Schema> {
+    Schema::from_sql(&dump_sql(client, namespaces).await?)
+}
+
+/// Fill in the `{ns}` and `{local:catalog:oid}` placeholders of a section query.
+fn section_query(query: &str) -> String {
+    let mut result = query.replace("{ns}", NAMESPACE_FILTER);
+    while let Some(start) = result.find("{local:") {
+        let end = start + result[start..].find('}').expect("unterminated placeholder");
+        let (catalog, oid) = result[start + "{local:".len()..end]
+            .split_once(':')
+            .expect("placeholder has a catalog and OID");
+        let condition = format!(
+            "NOT EXISTS (SELECT 1 FROM pg_depend e \
+             WHERE e.classid = '{catalog}'::regclass AND e.objid = {oid} AND e.deptype = 'e')"
+        );
+        result.replace_range(start..=end, &condition);
+    }
+    result
+}
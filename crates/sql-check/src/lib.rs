@@ -3,16 +3,25 @@
 //! Unlike SQLx (which requires a running Postgres instance at compile time),
 //! sql-check validates queries against a schema file dumped from the database.
 
+pub mod diff;
 pub mod error;
+pub mod extensions;
 pub mod schema;
 pub mod types;
 pub mod validate;
 
+#[cfg(feature = "runtime")]
+pub mod introspect;
 #[cfg(feature = "runtime")]
 pub mod runtime;
 
+pub use diff::{Change, ChangeKind, SchemaDiff};
 pub use error::{Error, Result};
-pub use schema::{Column, Schema, Table};
+pub use schema::{
+    AllowedValues, Check, Column, CompositeField, CompositeType, DomainType, EnumType, Extension,
+    ForeignKey, Function, FunctionArgument, FunctionReturn, Generated, Index, IndexKey,
+    ReferentialAction, Schema, SchemaWarning, Sequence, Table, UniqueKey, View, SNAPSHOT_VERSION,
+};
 pub use types::{PostgresType, RustType};
 pub use validate::validate_query;
 
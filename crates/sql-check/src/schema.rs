@@ -3,19 +3,102 @@
 //! Parses pg_dump --schema-only output into Rust data structures.
 
 use crate::error::{Error, Result};
-use crate::types::PostgresType;
+use crate::extensions::{extension_function, extension_type};
+use crate::types::{
+    quote_name_part, split_qualified_name, to_pascal_case, unqualified_name, unquote, PostgresType,
+    RustType,
+};
+use crate::validate::{validate_select, QueryColumn};
+use serde::{Deserialize, Serialize};
+use sqlparser::ast::helpers::stmt_create_table::CreateTableBuilder;
 use sqlparser::ast::{
-    CharacterLength, ColumnDef, ColumnOption, DataType, Expr, ObjectName, Statement,
-    TableConstraint, TimezoneInfo,
+    AlterColumnOperation, AlterIndexOperation, AlterTableOperation, AlterTypeAddValuePosition,
+    AlterTypeOperation, ArgMode, BinaryOperator, CharacterLength, CheckConstraint, ColumnDef,
+    ColumnOption, ColumnOptionDef, CommentObject, CreateDomain, CreateTable, DataType, Expr,
+    ForeignKeyConstraint, FunctionArg, FunctionArgExpr, FunctionArguments, GeneratedAs, Ident,
+    IndexOption, ObjectName, ObjectType, Query, RenameTableNameKind, SchemaName, SelectItem, Set,
+    SetExpr, Statement, TableConstraint, TimezoneInfo, UserDefinedTypeRepresentation, Value,
 };
 use sqlparser::dialect::PostgreSqlDialect;
-use sqlparser::parser::Parser;
-use std::collections::HashMap;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::{Parser, ParserError};
+use sqlparser::tokenizer::{Token, TokenWithSpan, Tokenizer};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The namespace Postgres creates objects in by default.
+const DEFAULT_NAMESPACE: &str = "public";
+
+/// Version of the JSON snapshot format written by [`Schema::to_json`].
+///
+/// Bumped whenever the format changes incompatibly; snapshots of other
+/// versions are rejected rather than misread, except for the older ones
+/// [`Schema::from_json`] knows how to upgrade.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+/// The oldest snapshot version [`Schema::from_json`] can still load.
+///
+/// Version 1 predates `Table::unique_keys`, which are rebuilt from the
+/// columns' flags, and the fields since added with defaults.
+const OLDEST_SNAPSHOT_VERSION: u32 = 1;
 
 /// A database schema containing tables and their definitions.
-#[derive(Debug, Default)]
+///
+/// Objects are keyed by their qualified name (`public.users`). Unqualified
+/// names are resolved against the search path, like Postgres does.
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Schema {
+    #[serde(serialize_with = "serialize_sorted")]
     tables: HashMap<String, Table>,
+    #[serde(serialize_with = "serialize_sorted")]
+    enums: HashMap<String, EnumType>,
+    #[serde(serialize_with = "serialize_sorted")]
+    composites: HashMap<String, CompositeType>,
+    #[serde(serialize_with = "serialize_sorted")]
+    domains: HashMap<String, DomainType>,
+    #[serde(serialize_with = "serialize_sorted")]
+    views: HashMap<String, View>,
+    /// Overloads of each function, in declaration order.
+    #[serde(serialize_with = "serialize_sorted")]
+    functions: HashMap<String, Vec<Function>>,
+    #[serde(serialize_with = "serialize_sorted")]
+    sequences: HashMap<String, Sequence>,
+    /// Installed extensions, by name.
+    #[serde(default, serialize_with = "serialize_sorted")]
+    extensions: HashMap<String, Extension>,
+    namespaces: Vec<String>,
+    search_path: Vec<String>,
+    /// Rust type paths overriding the types of columns (by `namespace.table.column`)
+    /// and Postgres types (by type name); see [`Schema::add_type_override`].
+    #[serde(skip)]
+    column_overrides: HashMap<String, String>,
+    #[serde(skip)]
+    type_overrides: HashMap<String, String>,
+    /// The search path of each foreign key referencing a table that didn't
+    /// exist yet, by table and constraint name, to look the table up with.
+    #[serde(skip)]
+    pending_search_paths: HashMap<(String, String), Vec<String>>,
+}
+
+impl Default for Schema {
+    fn default() -> Self {
+        Self {
+            tables: HashMap::new(),
+            enums: HashMap::new(),
+            composites: HashMap::new(),
+            domains: HashMap::new(),
+            views: HashMap::new(),
+            functions: HashMap::new(),
+            sequences: HashMap::new(),
+            extensions: HashMap::new(),
+            namespaces: vec![DEFAULT_NAMESPACE.to_string()],
+            search_path: vec![DEFAULT_NAMESPACE.to_string()],
+            column_overrides: HashMap::new(),
+            type_overrides: HashMap::new(),
+            pending_search_paths: HashMap::new(),
+        }
+    }
 }
 
 impl Schema {
@@ -26,336 +109,6888 @@ impl Schema {
 
     /// Parse schema from SQL DDL statements (like pg_dump output).
     pub fn from_sql(sql: &str) -> Result<Self> {
-        let dialect = PostgreSqlDialect {};
-        let statements =
-            Parser::parse_sql(&dialect, sql).map_err(|e| Error::SchemaParse(e.to_string()))?;
+        let mut schema = Schema::new();
+        schema.apply_sql(sql)?;
+        schema.resolve_pending_foreign_keys()?;
+        Ok(schema)
+    }
+
+    /// Parse schema from SQL DDL, skipping statements that don't matter.
+    ///
+    /// Full pg_dump files contain statements sql-check can't parse or doesn't
+    /// model (psql meta-commands, policies, triggers, ...). Instead of failing,
+    /// each one that can't be parsed or applied is skipped with a warning
+    /// naming its line. Statements that create, alter or drop tables, types or
+    /// domains still fail, since queries depend on them, unless they only
+    /// change ownership or storage (`OWNER TO`, `SET STATISTICS`, ...).
+    ///
+    /// psql's `\i` and `\ir` includes can't be followed without a file to
+    /// resolve them against, so they are skipped with a warning; use
+    /// [`Schema::from_file_tolerant`] for dumps that include other files.
+    pub fn from_sql_tolerant(sql: &str) -> Result<(Self, Vec<SchemaWarning>)> {
+        let mut schema = Schema::new();
+        let warnings = schema.apply_sql_tolerant(sql)?;
+        schema.resolve_pending_foreign_keys()?;
+        Ok((schema, warnings))
+    }
+
+    /// Load schema from a file.
+    ///
+    /// psql's `\i` and `\ir` include directives are followed; errors in an
+    /// included file name that file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let mut schema = Schema::new();
+        schema.apply_file(path, None)?;
+        schema.resolve_pending_foreign_keys()?;
+        Ok(schema)
+    }
+
+    /// Load schema from a file, skipping statements that don't matter.
+    ///
+    /// Like [`Schema::from_sql_tolerant`], but includes are followed as in
+    /// [`Schema::from_file`], and each warning names the file it is about.
+    pub fn from_file_tolerant(path: &Path) -> Result<(Self, Vec<SchemaWarning>)> {
+        Self::from_files_tolerant(&[path])
+    }
+
+    /// Load a schema split across several files, applied in order.
+    ///
+    /// Later files can refer to objects from earlier ones (and foreign keys to
+    /// tables from any file), and an object defined in two files is an error
+    /// naming the second file.
+    pub fn from_files<P: AsRef<Path>>(paths: &[P]) -> Result<Self> {
+        let mut schema = Schema::new();
+        for path in paths {
+            let path = path.as_ref();
+            schema
+                .apply_file(path, None)
+                .map_err(|e| schema_file_error(path, e))?;
+        }
+        schema.resolve_pending_foreign_keys()?;
+        Ok(schema)
+    }
 
+    /// Load a schema split across several files, skipping statements that
+    /// don't matter (see [`Schema::from_file_tolerant`]).
+    pub fn from_files_tolerant<P: AsRef<Path>>(paths: &[P]) -> Result<(Self, Vec<SchemaWarning>)> {
         let mut schema = Schema::new();
+        let mut warnings = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            schema
+                .apply_file(path, Some(&mut warnings))
+                .map_err(|e| schema_file_error(path, e))?;
+        }
+        schema.resolve_pending_foreign_keys()?;
+        Ok((schema, warnings))
+    }
+
+    /// Load a schema from the files matching a glob (`schema/*.sql`), applied
+    /// in path order.
+    pub fn from_glob(pattern: &str) -> Result<Self> {
+        Self::from_files(&glob_paths(pattern)?)
+    }
+
+    /// Serialize the schema to a JSON snapshot.
+    ///
+    /// The snapshot is compact and deterministic (objects are written sorted
+    /// by name), so it can be checked in and diffed. Load it back with
+    /// [`Schema::from_json`].
+    pub fn to_json(&self) -> Result<String> {
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            schema: self,
+        };
+        serde_json::to_string(&snapshot).map_err(|e| Error::Snapshot(e.to_string()))
+    }
 
-        for statement in statements {
-            // We can add support for CREATE INDEX, CREATE TYPE, etc. later
-            if let Statement::CreateTable(create) = statement {
-                let table = Table::from_create_table(&create)?;
-                schema.tables.insert(table.name.clone(), table);
+    /// Load a schema from a JSON snapshot written by [`Schema::to_json`].
+    ///
+    /// Snapshots of an older format version are upgraded where possible;
+    /// others written by a different version are rejected.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let header: Snapshot<serde::de::IgnoredAny> =
+            serde_json::from_str(json).map_err(|e| Error::Snapshot(e.to_string()))?;
+        if !(OLDEST_SNAPSHOT_VERSION..=SNAPSHOT_VERSION).contains(&header.version) {
+            return Err(Error::Snapshot(format!(
+                "unsupported version {} (expected {})",
+                header.version, SNAPSHOT_VERSION
+            )));
+        }
+
+        let snapshot: Snapshot<Schema> =
+            serde_json::from_str(json).map_err(|e| Error::Snapshot(e.to_string()))?;
+        let mut schema = snapshot.schema;
+        for table in schema.tables.values_mut() {
+            table.rebuild_column_map();
+            if header.version < 2 {
+                table.rebuild_unique_keys();
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Build a schema by replaying a directory of migrations in order.
+    ///
+    /// Migrations are applied in the order of their numeric prefix
+    /// (`2_add_bio.sql` before `10_add_email.sql`), then by name. Both flat layouts
+    /// (`migrations/0001_create_users.sql`, with `.down.sql` files skipped)
+    /// and directory-per-migration layouts (`migrations/0001_create_users/up.sql`)
+    /// are supported. The resulting schema is the end state of all migrations.
+    pub fn from_migrations(dir: &Path) -> Result<Self> {
+        let mut migrations = Vec::new();
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                let up = path.join("up.sql");
+                if up.is_file() {
+                    migrations.push((migration_order(&path), up));
+                }
+            } else if is_up_migration(&path) {
+                migrations.push((migration_order(&path), path));
             }
         }
 
+        migrations.sort();
+
+        let mut schema = Schema::new();
+        for (_, path) in migrations {
+            let sql = std::fs::read_to_string(&path)?;
+            schema.apply_sql(&sql).map_err(|e| Error::Migration {
+                path: path.display().to_string(),
+                source: Box::new(e),
+            })?;
+        }
+        schema.resolve_pending_foreign_keys()?;
+
         Ok(schema)
     }
 
-    /// Load schema from a file.
-    pub fn from_file(path: &std::path::Path) -> Result<Self> {
-        let sql = std::fs::read_to_string(path)?;
-        Self::from_sql(&sql)
+    /// Apply a schema file on top of the current schema, following includes.
+    ///
+    /// Like psql, `\i` paths are relative to the working directory and `\ir`
+    /// paths to the including file. Other psql meta-commands are skipped.
+    /// With `warnings`, statements are applied as by
+    /// [`Schema::from_sql_tolerant`] and the skipped ones collected there.
+    ///
+    /// The file and the files it includes run as one psql session: a `SET
+    /// search_path` lasts across includes until the end of the file.
+    fn apply_file(&mut self, path: &Path, warnings: Option<&mut Vec<SchemaWarning>>) -> Result<()> {
+        self.in_session(|schema| schema.apply_file_in_session(path, &mut Vec::new(), warnings))
     }
 
-    /// Get a table by name.
-    pub fn get_table(&self, name: &str) -> Option<&Table> {
-        // Try exact match first
-        if let Some(table) = self.tables.get(name) {
-            return Some(table);
+    /// Apply a schema file within the current session (see [`Schema::apply_file`]).
+    ///
+    /// `including` holds the files being applied, to reject include cycles.
+    fn apply_file_in_session(
+        &mut self,
+        path: &Path,
+        including: &mut Vec<PathBuf>,
+        mut warnings: Option<&mut Vec<SchemaWarning>>,
+    ) -> Result<()> {
+        let canonical = path.canonicalize()?;
+        if including.contains(&canonical) {
+            return Err(Error::SchemaParse(format!(
+                "{} includes itself",
+                path.display()
+            )));
         }
+        let sql = std::fs::read_to_string(path)?;
+        including.push(canonical);
 
-        // Try case-insensitive match
-        let name_lower = name.to_lowercase();
-        self.tables
-            .values()
-            .find(|t| t.name.to_lowercase() == name_lower)
+        let mut pending = String::new();
+        for (idx, line) in sql.lines().enumerate() {
+            let Some((command, argument)) = meta_command(line) else {
+                pending.push_str(line);
+                pending.push('\n');
+                continue;
+            };
+            let include = match command {
+                "i" | "include" => PathBuf::from(argument),
+                "ir" | "include_relative" => path.parent().unwrap_or(Path::new("")).join(argument),
+                _ => {
+                    // Kept when tolerant, to be skipped with a warning
+                    if warnings.is_some() {
+                        pending.push_str(line);
+                    }
+                    pending.push('\n');
+                    continue;
+                }
+            };
+            self.apply_file_sql(path, &pending, warnings.as_deref_mut())?;
+            self.apply_file_in_session(&include, including, warnings.as_deref_mut())
+                .map_err(|e| schema_file_error(&include, e))?;
+            // Keep line numbers in parse errors matching the file
+            pending = "\n".repeat(idx + 1);
+        }
+
+        including.pop();
+        self.apply_file_sql(path, &pending, warnings)
     }
 
-    /// Get all table names.
-    pub fn table_names(&self) -> impl Iterator<Item = &str> {
-        self.tables.keys().map(|s| s.as_str())
+    /// Apply SQL from the file at `path` within the current session,
+    /// tolerantly if there are `warnings`.
+    fn apply_file_sql(
+        &mut self,
+        path: &Path,
+        sql: &str,
+        warnings: Option<&mut Vec<SchemaWarning>>,
+    ) -> Result<()> {
+        let Some(warnings) = warnings else {
+            return self.apply_statements(sql);
+        };
+        for mut warning in self.apply_statements_tolerant(sql)? {
+            warning.file = Some(path.to_path_buf());
+            warnings.push(warning);
+        }
+        Ok(())
     }
 
-    /// Check if a table exists.
-    pub fn has_table(&self, name: &str) -> bool {
-        self.get_table(name).is_some()
+    /// Run `apply` as a psql session: a `SET search_path` in it only lasts
+    /// until its end.
+    fn in_session<T>(&mut self, apply: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let search_path = self.search_path.clone();
+        let result = apply(self);
+        self.search_path = search_path;
+        result
     }
-}
 
-/// A database table.
-#[derive(Debug)]
-pub struct Table {
-    pub name: String,
-    pub columns: Vec<Column>,
-    column_map: HashMap<String, usize>,
-}
+    /// Apply SQL DDL statements on top of the current schema.
+    fn apply_sql(&mut self, sql: &str) -> Result<()> {
+        self.in_session(|schema| schema.apply_statements(sql))
+    }
 
-impl Table {
-    /// Create a table from a CREATE TABLE statement.
-    fn from_create_table(create: &sqlparser::ast::CreateTable) -> Result<Self> {
-        let name = object_name_to_string(&create.name);
-        let mut columns = Vec::new();
-        let mut column_map = HashMap::new();
+    /// Apply SQL DDL statements within the current session.
+    fn apply_statements(&mut self, sql: &str) -> Result<()> {
+        let statements = parse_statements(sql)
+            .and_then(|statements| {
+                statements
+                    .into_iter()
+                    .map(|parsed| parsed.statement)
+                    .collect::<std::result::Result<Vec<_>, _>>()
+            })
+            .map_err(|e| Error::SchemaParse(e.to_string()))?;
 
-        // First pass: extract columns
-        for (idx, col_def) in create.columns.iter().enumerate() {
-            let column = Column::from_column_def(col_def)?;
-            column_map.insert(column.name.to_lowercase(), idx);
-            columns.push(column);
-        }
+        statements
+            .into_iter()
+            .try_for_each(|statement| self.apply_schema_statement(statement))
+    }
 
-        // Second pass: handle table constraints (PRIMARY KEY, UNIQUE, etc.)
-        for constraint in &create.constraints {
-            match constraint {
-                TableConstraint::PrimaryKey(pk) => {
-                    for pk_col in &pk.columns {
-                        // IndexColumn has a column field with OrderByExpr
-                        if let Expr::Identifier(ident) = &pk_col.column.expr {
-                            let col_name = ident.value.to_lowercase();
-                            if let Some(&idx) = column_map.get(&col_name) {
-                                columns[idx].is_primary_key = true;
-                                columns[idx].nullable = false; // PKs are never null
-                            }
-                        }
-                    }
+    /// Apply SQL DDL statements, skipping the ones that can't be parsed or
+    /// applied unless they define tables (or the types their columns use).
+    fn apply_sql_tolerant(&mut self, sql: &str) -> Result<Vec<SchemaWarning>> {
+        self.in_session(|schema| schema.apply_statements_tolerant(sql))
+    }
+
+    /// Apply SQL DDL statements tolerantly within the current session (see
+    /// [`Schema::apply_sql_tolerant`]).
+    fn apply_statements_tolerant(&mut self, sql: &str) -> Result<Vec<SchemaWarning>> {
+        let mut warnings = Vec::new();
+        let sql = skip_meta_commands(sql, &mut warnings);
+        let statements = parse_statements(&sql).map_err(|e| Error::SchemaParse(e.to_string()))?;
+
+        let mut result = Ok(());
+        for parsed in statements {
+            let view = match &parsed.statement {
+                Ok(SchemaStatement::Sql(statement)) => match statement.as_ref() {
+                    Statement::CreateView(create) => Some(object_name_to_string(&create.name)),
+                    _ => None,
+                },
+                _ => None,
+            };
+            let applied = parsed
+                .statement
+                .map_err(|e| Error::SchemaParse(e.to_string()))
+                .and_then(|statement| self.apply_schema_statement(statement));
+            match applied {
+                Err(e) if parsed.affects_tables => {
+                    result = Err(e);
+                    break;
                 }
-                TableConstraint::Unique(unique) => {
-                    for unique_col in &unique.columns {
-                        if let Expr::Identifier(ident) = &unique_col.column.expr {
-                            let col_name = ident.value.to_lowercase();
-                            if let Some(&idx) = column_map.get(&col_name) {
-                                columns[idx].is_unique = true;
-                            }
+                Err(e) => warnings.push(SchemaWarning {
+                    file: None,
+                    line: parsed.line,
+                    message: e.to_string(),
+                }),
+                Ok(()) => {
+                    // Views that can't be typed are kept, but worth a warning
+                    if let Some(view) = view.as_deref().and_then(|name| self.get_view(name)) {
+                        if let Some(error) = &view.error {
+                            warnings.push(SchemaWarning {
+                                file: None,
+                                line: parsed.line,
+                                message: format!(
+                                    "Columns of view {} are unknown: {}",
+                                    view.qualified_name(),
+                                    error
+                                ),
+                            });
                         }
                     }
                 }
-                _ => {}
             }
         }
+        warnings.sort_by_key(|warning| warning.line);
 
-        Ok(Table {
-            name,
-            columns,
-            column_map,
-        })
+        result.map(|()| warnings)
     }
 
-    /// Get a column by name.
-    pub fn get_column(&self, name: &str) -> Option<&Column> {
-        let name_lower = name.to_lowercase();
-        self.column_map
-            .get(&name_lower)
-            .map(|&idx| &self.columns[idx])
+    /// Apply a single parsed schema statement.
+    fn apply_schema_statement(&mut self, statement: SchemaStatement) -> Result<()> {
+        match statement {
+            SchemaStatement::Sql(statement) => self.apply_statement(*statement),
+            SchemaStatement::CreateFunction(create) => self.apply_create_function(create),
+            SchemaStatement::AlterFunction { name, args, rename } => {
+                self.apply_alter_function(&name, args.as_deref(), rename)
+            }
+            SchemaStatement::CreateSequence(create) => self.apply_create_sequence(create),
+            SchemaStatement::AlterSequence {
+                name,
+                if_exists,
+                options,
+                rename,
+            } => self.apply_alter_sequence(&name, if_exists, options, rename),
+            SchemaStatement::CreatePartition { create, bound } => {
+                self.apply_create_table(&create, Some(bound))
+            }
+            SchemaStatement::AttachPartition {
+                parent,
+                if_exists,
+                partition,
+                bound,
+            } => self.apply_attach_partition(&parent, if_exists, &partition, bound),
+            SchemaStatement::DetachPartition {
+                parent,
+                if_exists,
+                partition,
+            } => self.apply_detach_partition(&parent, if_exists, &partition),
+        }
     }
 
-    /// Check if a column exists.
-    pub fn has_column(&self, name: &str) -> bool {
-        self.get_column(name).is_some()
+    /// Apply CREATE TABLE, or CREATE TABLE ... PARTITION OF with the partition's `bound`.
+    fn apply_create_table(&mut self, create: &CreateTable, bound: Option<String>) -> Result<()> {
+        if create.if_not_exists && self.has_table(&object_name_to_string(&create.name)) {
+            return Ok(());
+        }
+        let (namespace, name) = self.creation_name(&create.name);
+        ensure_undefined(&self.tables, "table", &qualify(&namespace, &name))?;
+        let parents = create
+            .inherits
+            .iter()
+            .flatten()
+            .map(|parent| self.table_key(&object_name_to_string(parent)))
+            .collect::<Result<Vec<_>>>()?;
+        let parents: Vec<&Table> = parents.iter().map(|key| &self.tables[key]).collect();
+        // Columns declared again must keep the type they inherit
+        for col_def in &create.columns {
+            if col_def.data_type == DataType::Unspecified {
+                continue;
+            }
+            let declared = match serial_type(&col_def.data_type) {
+                Some(data_type) => data_type,
+                None => self.resolve_type(data_type_to_postgres(&col_def.data_type)?),
+            };
+            for parent in &parents {
+                if let Some(inherited) = parent.get_column(&col_def.name.value) {
+                    if inherited.data_type != declared {
+                        return Err(Error::SchemaParse(format!(
+                            "Column {} of {} has type {}, but inherits type {} from {}",
+                            col_def.name.value,
+                            qualify(&namespace, &name),
+                            declared,
+                            inherited.data_type,
+                            parent.qualified_name()
+                        )));
+                    }
+                }
+            }
+        }
+        let mut table = Table::from_create_table(create, namespace, name, &parents, bound)?;
+        self.resolve_table_types(&mut table);
+        self.resolve_foreign_keys(&mut table)?;
+        let key = table.qualified_name();
+        self.tables.insert(key.clone(), table);
+        self.add_column_sequences(&key);
+        Ok(())
     }
 
-    /// Get all column names.
-    pub fn column_names(&self) -> impl Iterator<Item = &str> {
-        self.columns.iter().map(|c| c.name.as_str())
+    /// Apply ALTER TABLE ... ATTACH PARTITION, making an existing table a
+    /// partition of `parent`. Both need the same columns.
+    fn apply_attach_partition(
+        &mut self,
+        parent: &ObjectName,
+        if_exists: bool,
+        partition: &ObjectName,
+        bound: String,
+    ) -> Result<()> {
+        let parent_name = object_name_to_string(parent);
+        let Some(parent_key) = self.lookup_key(&self.tables, &parent_name) else {
+            return if if_exists {
+                Ok(())
+            } else {
+                Err(Error::UnknownTable(parent_name))
+            };
+        };
+        let key = self.table_key(&object_name_to_string(partition))?;
+        let parent = &self.tables[&parent_key];
+        let table = &self.tables[&key];
+        if let Some(column) = parent.columns.iter().find(|c| !table.has_column(&c.name)) {
+            return Err(table.unknown_column(&column.name));
+        }
+        if let Some(column) = table.columns.iter().find(|c| !parent.has_column(&c.name)) {
+            return Err(parent.unknown_column(&column.name));
+        }
+
+        let mut table = self.tables.remove(&key).expect("partition key exists");
+        table.inherit(&self.tables[&parent_key], true);
+        table.partition_bound = Some(bound);
+        self.tables.insert(key, table);
+        Ok(())
     }
-}
 
-/// A table column.
-#[derive(Debug)]
-pub struct Column {
-    pub name: String,
-    pub data_type: PostgresType,
-    pub nullable: bool,
-    pub has_default: bool,
-    pub is_primary_key: bool,
-    pub is_unique: bool,
-}
+    /// Apply ALTER TABLE ... DETACH PARTITION. The table keeps its columns and
+    /// constraints.
+    fn apply_detach_partition(
+        &mut self,
+        parent: &ObjectName,
+        if_exists: bool,
+        partition: &ObjectName,
+    ) -> Result<()> {
+        let parent_name = object_name_to_string(parent);
+        let Some(parent_key) = self.lookup_key(&self.tables, &parent_name) else {
+            return if if_exists {
+                Ok(())
+            } else {
+                Err(Error::UnknownTable(parent_name))
+            };
+        };
+        let key = self.table_key(&object_name_to_string(partition))?;
+        let table = self.tables.get_mut(&key).expect("partition key exists");
+        if table.partition_bound.is_none() || !table.parents.contains(&parent_key) {
+            return Err(Error::SchemaParse(format!(
+                "Table {} is not a partition of {}",
+                key, parent_key
+            )));
+        }
+        table.parents.retain(|p| *p != parent_key);
+        table.partition_bound = None;
+        Ok(())
+    }
 
-impl Column {
-    /// Create a column from a ColumnDef.
-    fn from_column_def(col_def: &ColumnDef) -> Result<Self> {
-        let name = col_def.name.value.clone();
-        let data_type = data_type_to_postgres(&col_def.data_type)?;
+    /// The key of an existing table, erroring if there is none.
+    fn table_key(&self, name: &str) -> Result<String> {
+        self.lookup_key(&self.tables, name)
+            .ok_or_else(|| Error::UnknownTable(name.to_string()))
+    }
 
-        let mut nullable = true; // Default to nullable
-        let mut has_default = false;
-        let mut is_primary_key = false;
-        let mut is_unique = false;
+    /// Keys of the tables inheriting from a table, directly or through
+    /// others, including its partitions.
+    fn descendants(&self, key: &str) -> Vec<String> {
+        let mut descendants: Vec<String> = Vec::new();
+        let mut pending = vec![key.to_string()];
+        while let Some(parent) = pending.pop() {
+            let mut children: Vec<String> = self
+                .tables
+                .iter()
+                .filter(|(child, table)| {
+                    table.parents.contains(&parent) && !descendants.contains(child)
+                })
+                .map(|(child, _)| child.clone())
+                .collect();
+            children.sort();
+            descendants.extend(children.iter().cloned());
+            pending.extend(children);
+        }
+        descendants
+    }
 
-        for option in &col_def.options {
-            match &option.option {
-                ColumnOption::NotNull => nullable = false,
-                ColumnOption::Null => nullable = true,
-                ColumnOption::Default(_) => has_default = true,
-                ColumnOption::PrimaryKey(_) => {
-                    is_primary_key = true;
-                    nullable = false;
+    /// Apply a single DDL statement.
+    fn apply_statement(&mut self, statement: Statement) -> Result<()> {
+        match statement {
+            Statement::CreateTable(create) => self.apply_create_table(&create, None)?,
+            Statement::AlterTable(alter) => self.apply_alter_table(&alter)?,
+            Statement::Comment {
+                object_type,
+                object_name,
+                comment,
+                ..
+            } => self.apply_comment(object_type, &object_name, comment)?,
+            Statement::CreateIndex(create) => self.apply_create_index(&create)?,
+            Statement::AlterIndex {
+                name,
+                operation: AlterIndexOperation::RenameIndex { index_name },
+            } => {
+                let name = object_name_to_string(&name);
+                let index = self
+                    .find_index_mut(&name)
+                    .ok_or_else(|| Error::UnknownIndex(name.clone()))?;
+                index.name = unqualified_name(&object_name_to_string(&index_name)).to_string();
+            }
+            Statement::CreateType {
+                name,
+                representation: Some(UserDefinedTypeRepresentation::Enum { labels }),
+            } => {
+                let (namespace, name) = self.creation_name(&name);
+                self.ensure_type_undefined(&qualify(&namespace, &name))?;
+                let enum_type = EnumType {
+                    name,
+                    namespace,
+                    labels: labels.into_iter().map(|label| label.value).collect(),
+                };
+                self.enums.insert(enum_type.qualified_name(), enum_type);
+            }
+            Statement::CreateType {
+                name,
+                representation: Some(UserDefinedTypeRepresentation::Composite { attributes }),
+            } => {
+                let mut fields = Vec::new();
+                for attribute in &attributes {
+                    fields.push(CompositeField {
+                        name: attribute.name.value.clone(),
+                        data_type: self.resolve_type(data_type_to_postgres(&attribute.data_type)?),
+                    });
                 }
-                ColumnOption::Unique(_) => {
-                    is_unique = true;
+                let (namespace, name) = self.creation_name(&name);
+                self.ensure_type_undefined(&qualify(&namespace, &name))?;
+                let composite = CompositeType {
+                    name,
+                    namespace,
+                    fields,
+                };
+                self.composites
+                    .insert(composite.qualified_name(), composite);
+            }
+            Statement::AlterType(alter) => self.apply_alter_type(&alter)?,
+            Statement::CreateView(create) => {
+                if create.if_not_exists
+                    && self
+                        .get_view(&object_name_to_string(&create.name))
+                        .is_some()
+                {
+                    return Ok(());
                 }
-                _ => {}
+                let view = self.view_from_create(&create);
+                if !create.or_replace {
+                    ensure_undefined(&self.views, "view", &view.qualified_name())?;
+                }
+                self.views.insert(view.qualified_name(), view);
+            }
+            Statement::CreateDomain(domain) => {
+                let domain = self.domain_from_create(&domain)?;
+                self.ensure_type_undefined(&domain.qualified_name())?;
+                self.domains.insert(domain.qualified_name(), domain);
+            }
+            Statement::DropDomain(drop) => {
+                let name = object_name_to_string(&drop.name);
+                let removed = self
+                    .lookup_key(&self.domains, &name)
+                    .and_then(|key| self.domains.remove(&key))
+                    .is_some();
+                if !removed && !drop.if_exists {
+                    return Err(Error::UnknownType(name));
+                }
+            }
+            Statement::CreateExtension(create) => {
+                let name = create.name.value.to_lowercase();
+                if !create.if_not_exists {
+                    ensure_undefined(&self.extensions, "extension", &name)?;
+                }
+                // Its objects go in WITH SCHEMA, or where a table would
+                let mut parts: Vec<Ident> = create.schema.into_iter().collect();
+                parts.push(create.name);
+                let (namespace, _) = self.creation_name(&ObjectName::from(parts));
+                self.extensions
+                    .entry(name.clone())
+                    .or_insert(Extension { name, namespace });
             }
+            Statement::DropExtension(drop) => {
+                for name in &drop.names {
+                    let removed = self.extensions.remove(&name.value.to_lowercase());
+                    if removed.is_none() && !drop.if_exists {
+                        return Err(Error::SchemaParse(format!(
+                            "Unknown extension {}",
+                            name.value
+                        )));
+                    }
+                }
+            }
+            Statement::CreateSchema { schema_name, .. } => {
+                let name = match schema_name {
+                    SchemaName::Simple(name) | SchemaName::NamedAuthorization(name, _) => {
+                        object_name_to_string(&name)
+                    }
+                    SchemaName::UnnamedAuthorization(role) => role.value,
+                };
+                self.add_namespace(&name);
+            }
+            Statement::Set(Set::SingleAssignment {
+                variable, values, ..
+            }) if object_name_to_string(&variable).eq_ignore_ascii_case("search_path") => {
+                self.search_path = search_path_from_values(&values);
+            }
+            Statement::Query(query) => {
+                // pg_dump resets the path with `SELECT pg_catalog.set_config('search_path', '', false)`
+                if let Some(path) = set_config_search_path(&query) {
+                    self.search_path = path;
+                }
+            }
+            Statement::Drop {
+                object_type,
+                if_exists,
+                names,
+                ..
+            } => {
+                for name in &names {
+                    self.apply_drop(object_type, &object_name_to_string(name), if_exists)?;
+                }
+            }
+            Statement::DropFunction(drop) => {
+                for desc in &drop.func_desc {
+                    let name = object_name_to_string(&desc.name);
+                    let args = desc
+                        .args
+                        .as_ref()
+                        .map(|args| self.input_types(args.iter().map(|a| (&a.mode, &a.data_type))))
+                        .transpose()?;
+                    match self.find_function(&name, args.as_deref())? {
+                        Some((key, idx)) => {
+                            self.remove_function(&key, idx);
+                        }
+                        None if drop.if_exists => {}
+                        None => return Err(Error::UnknownFunction(name)),
+                    }
+                }
+            }
+            // Grants, comments, triggers etc. don't affect query checking
+            _ => {}
         }
 
-        Ok(Column {
-            name,
-            data_type,
-            nullable,
-            has_default,
-            is_primary_key,
-            is_unique,
-        })
+        Ok(())
     }
-}
 
-/// Convert an ObjectName to a simple string.
-fn object_name_to_string(name: &ObjectName) -> String {
-    name.0
-        .iter()
-        .filter_map(|part| part.as_ident().map(|i| i.value.clone()))
-        .collect::<Vec<_>>()
-        .join(".")
-}
+    /// Add a function from CREATE FUNCTION.
+    ///
+    /// Functions are overloaded by their input argument types; creating one
+    /// with the same types as an existing overload replaces it.
+    fn apply_create_function(&mut self, create: CreateFunction) -> Result<()> {
+        let (namespace, name) = self.creation_name(&create.name);
 
-/// Extract length from CharacterLength enum.
-fn extract_char_length(len: &Option<CharacterLength>) -> Option<u32> {
-    match len {
-        Some(CharacterLength::IntegerLength { length, .. }) => Some(*length as u32),
-        Some(CharacterLength::Max) => None,
-        None => None,
+        let mut args = Vec::new();
+        let mut out_columns = Vec::new();
+        for param in &create.args {
+            let data_type = self.resolve_type(data_type_to_postgres(&param.data_type)?);
+            let arg_name = param.name.as_ref().map(|name| name.value.clone());
+            if matches!(param.mode, Some(ArgMode::Out | ArgMode::InOut)) {
+                out_columns.push(CompositeField {
+                    name: arg_name
+                        .clone()
+                        .unwrap_or_else(|| format!("column{}", out_columns.len() + 1)),
+                    data_type: data_type.clone(),
+                });
+            }
+            if !matches!(param.mode, Some(ArgMode::Out)) {
+                args.push(FunctionArgument {
+                    name: arg_name,
+                    data_type,
+                    has_default: param.has_default,
+                    variadic: param.variadic,
+                });
+            }
+        }
+
+        let element = |data_type| {
+            if create.setof {
+                FunctionReturn::SetOf(data_type)
+            } else {
+                FunctionReturn::Scalar(data_type)
+            }
+        };
+        let returns = match &create.return_type {
+            Some(DataType::Table(Some(columns))) => FunctionReturn::Table(
+                columns
+                    .iter()
+                    .map(|column| {
+                        Ok(CompositeField {
+                            name: column.name.value.clone(),
+                            data_type: self.resolve_type(data_type_to_postgres(&column.data_type)?),
+                        })
+                    })
+                    .collect::<Result<_>>()?,
+            ),
+            // OUT arguments describe the result, whatever RETURNS says (`record`)
+            _ if out_columns.len() > 1 => FunctionReturn::Table(out_columns),
+            _ if out_columns.len() == 1 => element(out_columns.remove(0).data_type),
+            Some(data_type) => element(self.resolve_type(data_type_to_postgres(data_type)?)),
+            None => {
+                return Err(Error::SchemaParse(format!(
+                    "Function {} has no return type",
+                    qualify(&namespace, &name)
+                )))
+            }
+        };
+
+        let function = Function {
+            name,
+            namespace,
+            args,
+            returns,
+            strict: create.strict,
+        };
+        if !create.or_replace {
+            let key = function.qualified_name();
+            let overloads = self
+                .lookup_key(&self.functions, &key)
+                .map(|key| &self.functions[&key]);
+            if overloads.is_some_and(|overloads| {
+                overloads
+                    .iter()
+                    .any(|f| f.has_signature(&function.arg_types()))
+            }) {
+                return Err(Error::DuplicateDefinition {
+                    kind: "function",
+                    name: key,
+                });
+            }
+        }
+        self.add_function(function);
+        Ok(())
     }
-}
 
-/// Convert sqlparser DataType to our PostgresType.
-fn data_type_to_postgres(dt: &DataType) -> Result<PostgresType> {
-    Ok(match dt {
-        // Exact numeric types
-        DataType::SmallInt(_) => PostgresType::SmallInt,
-        DataType::Int(_) | DataType::Integer(_) => PostgresType::Integer,
-        DataType::BigInt(_) => PostgresType::BigInt,
-        DataType::Real => PostgresType::Real,
-        DataType::Double(_) | DataType::DoublePrecision => PostgresType::DoublePrecision,
-        DataType::Numeric(_) | DataType::Decimal(_) => PostgresType::Numeric,
+    /// Apply ALTER FUNCTION. Only renames change the schema.
+    fn apply_alter_function(
+        &mut self,
+        name: &ObjectName,
+        args: Option<&[FunctionParam]>,
+        rename: Option<Ident>,
+    ) -> Result<()> {
+        let Some(new_name) = rename else {
+            return Ok(());
+        };
 
-        // Character types
-        DataType::Text => PostgresType::Text,
-        DataType::Varchar(len) => PostgresType::Varchar(extract_char_length(len)),
-        DataType::Char(len) => PostgresType::Char(extract_char_length(len)),
-        DataType::CharacterVarying(len) => PostgresType::Varchar(extract_char_length(len)),
-        DataType::Character(len) => PostgresType::Char(extract_char_length(len)),
+        let name = object_name_to_string(name);
+        let args = args
+            .map(|args| self.input_types(args.iter().map(|a| (&a.mode, &a.data_type))))
+            .transpose()?;
+        let (key, idx) = self
+            .find_function(&name, args.as_deref())?
+            .ok_or(Error::UnknownFunction(name))?;
 
-        // Binary
-        DataType::Bytea => PostgresType::Bytea,
+        let mut function = self.remove_function(&key, idx);
+        function.name = new_name.value;
+        self.add_function(function);
+        Ok(())
+    }
 
-        // Boolean
-        DataType::Boolean | DataType::Bool => PostgresType::Boolean,
+    /// Add a function, replacing the overload with the same argument types.
+    fn add_function(&mut self, function: Function) {
+        let overloads = self.functions.entry(function.qualified_name()).or_default();
+        match overloads
+            .iter()
+            .position(|f| f.has_signature(&function.arg_types()))
+        {
+            Some(idx) => overloads[idx] = function,
+            None => overloads.push(function),
+        }
+    }
 
-        // Date/Time
-        DataType::Timestamp(_, tz) => {
-            if matches!(tz, TimezoneInfo::WithTimeZone | TimezoneInfo::Tz) {
-                PostgresType::TimestampTz
-            } else {
-                PostgresType::Timestamp
+    /// Remove one overload of a function.
+    fn remove_function(&mut self, key: &str, idx: usize) -> Function {
+        let overloads = self.functions.get_mut(key).expect("function key exists");
+        let function = overloads.remove(idx);
+        if overloads.is_empty() {
+            self.functions.remove(key);
+        }
+        function
+    }
+
+    /// Find a function overload by name and, optionally, argument types.
+    ///
+    /// Without argument types the name has to be unique, like in Postgres.
+    fn find_function(
+        &self,
+        name: &str,
+        args: Option<&[PostgresType]>,
+    ) -> Result<Option<(String, usize)>> {
+        let Some(key) = self.lookup_key(&self.functions, name) else {
+            return Ok(None);
+        };
+        let overloads = &self.functions[&key];
+        let idx = match args {
+            Some(args) => overloads.iter().position(|f| f.has_signature(args)),
+            None if overloads.len() == 1 => Some(0),
+            None => {
+                return Err(Error::SchemaParse(format!(
+                    "Function name {} is not unique",
+                    name
+                )))
             }
+        };
+        Ok(idx.map(|idx| (key, idx)))
+    }
+
+    /// Resolve the argument types that identify a function overload.
+    ///
+    /// OUT arguments aren't part of the signature.
+    fn input_types<'a>(
+        &self,
+        args: impl Iterator<Item = (&'a Option<ArgMode>, &'a DataType)>,
+    ) -> Result<Vec<PostgresType>> {
+        args.filter(|(mode, _)| !matches!(mode, Some(ArgMode::Out)))
+            .map(|(_, data_type)| Ok(self.resolve_type(data_type_to_postgres(data_type)?)))
+            .collect()
+    }
+
+    /// Add a sequence from CREATE SEQUENCE.
+    fn apply_create_sequence(&mut self, create: CreateSequence) -> Result<()> {
+        if create.if_not_exists
+            && self
+                .get_sequence(&object_name_to_string(&create.name))
+                .is_some()
+        {
+            return Ok(());
         }
-        DataType::Date => PostgresType::Date,
-        DataType::Time(_, tz) => {
-            if matches!(tz, TimezoneInfo::WithTimeZone | TimezoneInfo::Tz) {
-                PostgresType::TimeTz
+
+        let (namespace, name) = self.creation_name(&create.name);
+        ensure_undefined(&self.sequences, "sequence", &qualify(&namespace, &name))?;
+        let sequence = Sequence {
+            name,
+            namespace,
+            data_type: PostgresType::BigInt,
+        };
+        let key = sequence.qualified_name();
+        self.sequences.insert(key.clone(), sequence);
+        self.apply_sequence_options(&key, create.options)
+    }
+
+    /// Apply ALTER SEQUENCE to an existing sequence.
+    fn apply_alter_sequence(
+        &mut self,
+        name: &ObjectName,
+        if_exists: bool,
+        options: SequenceOptions,
+        rename: Option<Ident>,
+    ) -> Result<()> {
+        let name = object_name_to_string(name);
+        let Some(mut key) = self.lookup_key(&self.sequences, &name) else {
+            return if if_exists {
+                Ok(())
             } else {
-                PostgresType::Time
+                Err(Error::UnknownSequence(name))
+            };
+        };
+
+        if let Some(new_name) = rename {
+            let mut sequence = self.sequences.remove(&key).expect("sequence key exists");
+            sequence.name = new_name.value;
+            let new_key = sequence.qualified_name();
+            self.sequences.insert(new_key.clone(), sequence);
+            for column in self.tables.values_mut().flat_map(|t| &mut t.columns) {
+                if column.sequence.as_deref() == Some(key.as_str()) {
+                    column.sequence = Some(new_key.clone());
+                }
             }
+            key = new_key;
         }
-        DataType::Interval { .. } => PostgresType::Interval,
 
-        // UUID
-        DataType::Uuid => PostgresType::Uuid,
+        self.apply_sequence_options(&key, options)
+    }
 
-        // JSON
-        DataType::JSON => PostgresType::Json,
-        DataType::JSONB => PostgresType::Jsonb,
+    /// Apply the AS and OWNED BY options of CREATE/ALTER SEQUENCE.
+    fn apply_sequence_options(&mut self, key: &str, options: SequenceOptions) -> Result<()> {
+        if let Some(data_type) = &options.data_type {
+            let data_type = data_type_to_postgres(data_type)?;
+            if let Some(sequence) = self.sequences.get_mut(key) {
+                sequence.data_type = data_type;
+            }
+        }
 
-        // Array types
-        DataType::Array(inner) => match inner {
-            sqlparser::ast::ArrayElemTypeDef::AngleBracket(inner_dt)
-            | sqlparser::ast::ArrayElemTypeDef::SquareBracket(inner_dt, _)
-            | sqlparser::ast::ArrayElemTypeDef::Parenthesis(inner_dt) => {
-                PostgresType::Array(Box::new(data_type_to_postgres(inner_dt)?))
+        let Some(owner) = options.owned_by else {
+            return Ok(());
+        };
+        let owner = match owner {
+            Some(owner) => {
+                let owner = object_name_to_string(&owner);
+                let (Some(table_name), column) = split_qualified_name(&owner) else {
+                    return Err(Error::SchemaParse(format!(
+                        "Sequence {} must be owned by a table column, not {}",
+                        key, owner
+                    )));
+                };
+                let table = self
+                    .get_table(table_name)
+                    .ok_or_else(|| Error::UnknownTable(table_name.to_string()))?;
+                if !table.has_column(column) {
+                    return Err(table.unknown_column(column));
+                }
+                Some((table.qualified_name(), column.to_string()))
             }
-            sqlparser::ast::ArrayElemTypeDef::None => {
-                return Err(Error::SchemaParse("Array with no element type".to_string()));
+            None => None,
+        };
+        self.set_sequence_owner(key, owner);
+        Ok(())
+    }
+
+    /// Make a column (`(table key, column)`) the owner of a sequence, or
+    /// remove its owner. Ownership is recorded as the column's `sequence`.
+    fn set_sequence_owner(&mut self, key: &str, owner: Option<(String, String)>) {
+        for column in self.tables.values_mut().flat_map(|t| &mut t.columns) {
+            if column.sequence.as_deref() == Some(key) {
+                column.sequence = None;
             }
-        },
+        }
 
-        // Custom types (enums, etc.)
+        if let Some((table, column)) = owner {
+            if let Some(column) = self
+                .tables
+                .get_mut(&table)
+                .and_then(|table| table.get_column_mut(&column).ok())
+            {
+                column.sequence = Some(key.to_string());
+            }
+        }
+    }
+
+    /// Create the implicit sequences behind a table's serial and identity columns.
+    fn add_column_sequences(&mut self, table_key: &str) {
+        let Some(table) = self.tables.get(table_key) else {
+            return;
+        };
+        for column in &table.columns {
+            let Some(key) = &column.sequence else {
+                continue;
+            };
+            if self.sequences.contains_key(key) {
+                continue;
+            }
+            let (namespace, name) = split_qualified(key);
+            self.sequences.insert(
+                key.clone(),
+                Sequence {
+                    name: name.to_string(),
+                    namespace: namespace.unwrap_or(DEFAULT_NAMESPACE).to_string(),
+                    data_type: column.data_type.clone(),
+                },
+            );
+        }
+    }
+
+    /// Split the name of an object being created into its namespace and name.
+    ///
+    /// Unqualified names go into the first existing namespace on the search
+    /// path (or `public`); naming a namespace implicitly declares it.
+    fn creation_name(&mut self, name: &ObjectName) -> (String, String) {
+        let name = object_name_to_string(name);
+        match split_qualified(&name) {
+            (Some(namespace), name) => {
+                self.add_namespace(namespace);
+                (namespace.to_string(), name.to_string())
+            }
+            (None, name) => {
+                let namespace = self
+                    .search_path
+                    .iter()
+                    .find(|namespace| self.has_namespace(namespace))
+                    .map_or(DEFAULT_NAMESPACE, |namespace| namespace.as_str());
+                (namespace.to_string(), name.to_string())
+            }
+        }
+    }
+
+    /// Error if an enum, composite or domain type is already named `key`.
+    fn ensure_type_undefined(&self, key: &str) -> Result<()> {
+        ensure_undefined(&self.enums, "type", key)?;
+        ensure_undefined(&self.composites, "type", key)?;
+        ensure_undefined(&self.domains, "type", key)
+    }
+
+    /// Register a namespace (CREATE SCHEMA).
+    fn add_namespace(&mut self, name: &str) {
+        if !self.has_namespace(name) {
+            self.namespaces.push(name.to_string());
+        }
+    }
+
+    /// Find the key of a named schema object.
+    ///
+    /// Qualified names (`billing.invoices`) match exactly; unqualified ones
+    /// are looked up in each namespace on the search path in turn. Matching is
+    /// case-insensitive.
+    fn lookup_key<V>(&self, map: &HashMap<String, V>, name: &str) -> Option<String> {
+        lookup_key_in(map, name, &self.search_path)
+    }
+
+    /// Apply a DROP statement for a single object.
+    fn apply_drop(&mut self, object_type: ObjectType, name: &str, if_exists: bool) -> Result<()> {
+        let (removed, unknown) = match object_type {
+            ObjectType::Table => (
+                self.lookup_key(&self.tables, name)
+                    .inspect(|key| {
+                        // Partitions and inheriting tables go with their parent
+                        for child in self.descendants(key) {
+                            let _ = self.apply_drop(ObjectType::Table, &child, true);
+                        }
+                    })
+                    .and_then(|key| self.tables.remove(&key))
+                    .inspect(|table| {
+                        let key = table.qualified_name();
+                        self.drop_references(|referenced| referenced == key);
+                        // Owned sequences go with the table
+                        for sequence in table.columns.iter().filter_map(|c| c.sequence.as_ref()) {
+                            self.sequences.remove(sequence);
+                        }
+                    })
+                    .is_some(),
+                Error::UnknownTable(name.to_string()),
+            ),
+            ObjectType::View | ObjectType::MaterializedView => (
+                self.lookup_key(&self.views, name)
+                    .and_then(|key| self.views.remove(&key))
+                    .is_some(),
+                Error::UnknownTable(name.to_string()),
+            ),
+            ObjectType::Type => (
+                self.lookup_key(&self.enums, name)
+                    .and_then(|key| self.enums.remove(&key))
+                    .is_some()
+                    || self
+                        .lookup_key(&self.composites, name)
+                        .and_then(|key| self.composites.remove(&key))
+                        .is_some(),
+                Error::UnknownType(name.to_string()),
+            ),
+            ObjectType::Index => (self.drop_index(name), Error::UnknownIndex(name.to_string())),
+            ObjectType::Sequence => (
+                self.lookup_key(&self.sequences, name)
+                    .and_then(|key| self.sequences.remove(&key))
+                    .inspect(|sequence| self.set_sequence_owner(&sequence.qualified_name(), None))
+                    .is_some(),
+                Error::UnknownSequence(name.to_string()),
+            ),
+            ObjectType::Schema => (
+                self.drop_namespace(name),
+                Error::UnknownSchema(name.to_string()),
+            ),
+            _ => return Ok(()),
+        };
+
+        if removed || if_exists {
+            Ok(())
+        } else {
+            Err(unknown)
+        }
+    }
+
+    /// Drop a namespace along with everything in it. Returns whether it existed.
+    fn drop_namespace(&mut self, name: &str) -> bool {
+        let Some(idx) = self
+            .namespaces
+            .iter()
+            .position(|n| n.eq_ignore_ascii_case(name))
+        else {
+            return false;
+        };
+        self.namespaces.remove(idx);
+
+        let in_namespace = |key: &str| {
+            split_qualified(key)
+                .0
+                .is_some_and(|namespace| namespace.eq_ignore_ascii_case(name))
+        };
+        self.tables.retain(|key, _| !in_namespace(key));
+        self.drop_references(in_namespace);
+        self.views.retain(|key, _| !in_namespace(key));
+        self.enums.retain(|key, _| !in_namespace(key));
+        self.composites.retain(|key, _| !in_namespace(key));
+        self.domains.retain(|key, _| !in_namespace(key));
+        self.functions.retain(|key, _| !in_namespace(key));
+        self.sequences.retain(|key, _| !in_namespace(key));
+
+        true
+    }
+
+    /// Apply `COMMENT ON TABLE` / `COMMENT ON COLUMN`.
+    ///
+    /// Column comments also apply to view columns. `IS NULL` (or an empty
+    /// string) removes the comment; comments on other objects are ignored.
+    fn apply_comment(
+        &mut self,
+        object_type: CommentObject,
+        object_name: &ObjectName,
+        comment: Option<String>,
+    ) -> Result<()> {
+        let comment = comment.filter(|comment| !comment.is_empty());
+        match object_type {
+            CommentObject::Table => {
+                let name = object_name_to_string(object_name);
+                let table = self
+                    .lookup_key(&self.tables, &name)
+                    .and_then(|key| self.tables.get_mut(&key))
+                    .ok_or(Error::UnknownTable(name))?;
+                table.comment = comment;
+            }
+            CommentObject::Column => {
+                let Some((column, table)) = object_name.0.split_last() else {
+                    return Ok(());
+                };
+                let column = column
+                    .as_ident()
+                    .map(|i| i.value.clone())
+                    .unwrap_or_default();
+                let name = object_name_to_string(&ObjectName(table.to_vec()));
+                if let Some(key) = self.lookup_key(&self.tables, &name) {
+                    let table = self.tables.get_mut(&key).expect("table key exists");
+                    table.get_column_mut(&column)?.comment = comment;
+                } else if let Some(key) = self.lookup_key(&self.views, &name) {
+                    let view = self.views.get_mut(&key).expect("view key exists");
+                    // The columns of views that couldn't be typed are unknown
+                    if view.error.is_some() {
+                        return Ok(());
+                    }
+                    let view_column = view
+                        .columns
+                        .iter_mut()
+                        .find(|c| c.name.eq_ignore_ascii_case(&column))
+                        .ok_or_else(|| Error::UnknownColumn {
+                            table: name.clone(),
+                            column,
+                        })?;
+                    view_column.comment = comment;
+                } else if self.get_composite(&name).is_none() {
+                    // Composite type attributes can be commented too, but aren't tracked
+                    return Err(Error::UnknownTable(name));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Apply an ALTER TABLE statement to an existing table.
+    ///
+    /// pg_dump emits primary keys, unique constraints and foreign keys as
+    /// separate `ALTER TABLE ONLY ... ADD CONSTRAINT` statements after the
+    /// CREATE TABLE, and migrations evolve tables column by column, so both
+    /// have to be folded back into the table.
+    fn apply_alter_table(&mut self, alter: &sqlparser::ast::AlterTable) -> Result<()> {
+        let name = object_name_to_string(&alter.name);
+
+        let mut key = self.lookup_key(&self.tables, &name);
+
+        for operation in &alter.operations {
+            // Ownership, triggers, RLS etc. don't change the table's shape
+            // (and pg_dump also uses ALTER TABLE for sequences and views).
+            if !changes_table_shape(operation) {
+                continue;
+            }
+
+            let Some(current) = key.clone() else {
+                if alter.if_exists {
+                    return Ok(());
+                }
+                return Err(Error::UnknownTable(name));
+            };
+
+            if let AlterTableOperation::RenameTable { table_name } = operation {
+                let (RenameTableNameKind::As(new_name) | RenameTableNameKind::To(new_name)) =
+                    table_name;
+                key = Some(self.rename_table(&current, &object_name_to_string(new_name)));
+                continue;
+            }
+
+            self.apply_table_operation(&current, operation, false)?;
+            // Without ONLY, the change carries over to inheriting tables and partitions
+            if !alter.only {
+                for child in self.descendants(&current) {
+                    if applies_to_child(operation, &self.tables[&child]) {
+                        self.apply_table_operation(&child, operation, true)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Apply a single ALTER TABLE operation to the table `key`, or to a table
+    /// `inherited` from the one it was applied to.
+    fn apply_table_operation(
+        &mut self,
+        key: &str,
+        operation: &AlterTableOperation,
+        inherited: bool,
+    ) -> Result<()> {
+        let mut table = self
+            .tables
+            .remove(key)
+            .ok_or_else(|| Error::UnknownTable(key.to_string()))?;
+        let dropped_sequences = dropped_sequences(&table, operation);
+        let applied = table.apply_alter_operation(operation).and_then(|()| {
+            // A serial column added to the parent uses the parent's sequence
+            if let (true, AlterTableOperation::AddColumn { column_def, .. }) =
+                (inherited, operation)
+            {
+                table.get_column_mut(&column_def.name.value)?.sequence = None;
+            }
+            update_references(&mut table.foreign_keys, key, operation);
+            self.resolve_foreign_keys(&mut table)
+        });
+        self.resolve_table_types(&mut table);
+        self.tables.insert(key.to_string(), table);
+        applied?;
+
+        for sequence in dropped_sequences {
+            self.sequences.remove(&sequence);
+        }
+        self.add_column_sequences(key);
+
+        for table in self.tables.values_mut() {
+            update_references(&mut table.foreign_keys, key, operation);
+        }
+        Ok(())
+    }
+
+    /// Apply an ALTER TYPE statement to an existing user-defined type.
+    fn apply_alter_type(&mut self, alter: &sqlparser::ast::AlterType) -> Result<()> {
+        let name = object_name_to_string(&alter.name);
+
+        match &alter.operation {
+            AlterTypeOperation::Rename(rename) => {
+                self.rename_type(&name, &rename.new_name.value)?
+            }
+            AlterTypeOperation::AddValue(add) => {
+                let enum_type = self.get_enum_mut(&name)?;
+                let value = add.value.value.clone();
+                if enum_type.has_label(&value) {
+                    if add.if_not_exists {
+                        return Ok(());
+                    }
+                    return Err(Error::SchemaParse(format!(
+                        "Enum label '{}' already exists in type '{}'",
+                        value, enum_type.name
+                    )));
+                }
+
+                let position = match &add.position {
+                    Some(AlterTypeAddValuePosition::Before(other)) => {
+                        Some(enum_type.label_index(&other.value)?)
+                    }
+                    Some(AlterTypeAddValuePosition::After(other)) => {
+                        Some(enum_type.label_index(&other.value)? + 1)
+                    }
+                    None => None,
+                };
+                match position {
+                    Some(idx) => enum_type.labels.insert(idx, value),
+                    None => enum_type.labels.push(value),
+                }
+            }
+            AlterTypeOperation::RenameValue(rename) => {
+                let enum_type = self.get_enum_mut(&name)?;
+                let idx = enum_type.label_index(&rename.from.value)?;
+                enum_type.labels[idx] = rename.to.value.clone();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get a mutable enum type by name, erroring if it does not exist.
+    fn get_enum_mut(&mut self, name: &str) -> Result<&mut EnumType> {
+        self.lookup_key(&self.enums, name)
+            .and_then(|key| self.enums.get_mut(&key))
+            .ok_or_else(|| Error::UnknownType(name.to_string()))
+    }
+
+    /// Rename an enum or composite type within its namespace.
+    fn rename_type(&mut self, name: &str, new_name: &str) -> Result<()> {
+        let (key, new_name) = if let Some(key) = self.lookup_key(&self.enums, name) {
+            let mut enum_type = self
+                .enums
+                .remove(&key)
+                .ok_or_else(|| Error::UnknownType(name.to_string()))?;
+            enum_type.name = new_name.to_string();
+            let new_key = enum_type.qualified_name();
+            self.enums.insert(new_key.clone(), enum_type);
+            (key, new_key)
+        } else if let Some(key) = self.lookup_key(&self.composites, name) {
+            let mut composite = self
+                .composites
+                .remove(&key)
+                .ok_or_else(|| Error::UnknownType(name.to_string()))?;
+            composite.name = new_name.to_string();
+            let new_key = composite.qualified_name();
+            self.composites.insert(new_key.clone(), composite);
+            (key, new_key)
+        } else {
+            return Err(Error::UnknownType(name.to_string()));
+        };
+
+        // Columns and composite fields keep pointing at the renamed type
+        for table in self.tables.values_mut() {
+            for column in &mut table.columns {
+                column.data_type = column.data_type.renamed_type(&key, &new_name);
+            }
+        }
+        for composite in self.composites.values_mut() {
+            for field in &mut composite.fields {
+                field.data_type = field.data_type.renamed_type(&key, &new_name);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build a view from CREATE [MATERIALIZED] VIEW by validating its query.
+    ///
+    /// A view whose query can't be typed is still created, without columns;
+    /// only queries that use it fail.
+    fn view_from_create(&mut self, create: &sqlparser::ast::CreateView) -> View {
+        let (namespace, name) = self.creation_name(&create.name);
+        let (mut columns, error) = match validate_select(self, &create.query) {
+            Ok(result) => (result.columns, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+
+        // CREATE VIEW v (a, b) AS ... renames the leading columns
+        for (column, alias) in columns.iter_mut().zip(&create.columns) {
+            column.name = alias.name.value.clone();
+        }
+
+        View {
+            name,
+            namespace,
+            columns,
+            materialized: create.materialized,
+            definition: create.query.to_string(),
+            error,
+        }
+    }
+
+    /// Build a domain from CREATE DOMAIN, resolving its base type.
+    fn domain_from_create(&mut self, create: &CreateDomain) -> Result<DomainType> {
+        let (namespace, name) = self.creation_name(&create.name);
+        let base_type = data_type_to_postgres(&create.data_type)?;
+        // A domain over another domain keeps that domain's constraints
+        let parent = self.custom_domain(&base_type);
+
+        let not_null = create.constraints.iter().any(|constraint| {
+            matches!(
+                constraint,
+                TableConstraint::Check(CheckConstraint { expr, .. })
+                    if matches!(expr.as_ref(), Expr::IsNotNull(value) if is_domain_value(value))
+            )
+        });
+
+        Ok(DomainType {
+            name,
+            namespace,
+            not_null: not_null || parent.is_some_and(|d| d.not_null),
+            has_default: create.default.is_some() || parent.is_some_and(|d| d.has_default),
+            base_type: self.resolve_type(base_type),
+        })
+    }
+
+    /// The domain a not-yet-resolved type refers to, if any.
+    fn custom_domain(&self, data_type: &PostgresType) -> Option<&DomainType> {
+        match data_type {
+            PostgresType::Custom(name) => self.get_domain(name),
+            _ => None,
+        }
+    }
+
+    /// Resolve custom column types against the user-defined types in this schema.
+    ///
+    /// Columns typed with a domain also pick up the domain's NOT NULL and DEFAULT.
+    fn resolve_table_types(&self, table: &mut Table) {
+        for column in &mut table.columns {
+            if let Some(domain) = self.custom_domain(&column.data_type) {
+                column.nullable &= !domain.not_null;
+                column.has_default |= domain.has_default;
+                column.domain = Some(domain.qualified_name());
+            }
+            column.data_type = self.resolve_type(column.data_type.clone());
+        }
+    }
+
+    /// Resolve a type name that the parser couldn't map to a built-in type.
+    ///
+    /// `PostgresType::Custom` names matching a `CREATE TYPE ... AS ENUM` become
+    /// `PostgresType::Enum`, those matching a `CREATE TYPE ... AS (...)`
+    /// become `PostgresType::Composite`, names of tables are qualified, and
+    /// domains resolve to their base type, including inside arrays.
+    pub fn resolve_type(&self, data_type: PostgresType) -> PostgresType {
+        match data_type {
+            PostgresType::Custom(name) => {
+                if let Some(domain) = self.get_domain(&name) {
+                    domain.base_type.clone()
+                } else if let Some(key) = self.lookup_key(&self.enums, &name) {
+                    PostgresType::Enum(key)
+                } else if let Some(key) = self.lookup_key(&self.composites, &name) {
+                    PostgresType::Composite(key)
+                } else if let Some(key) = self.lookup_key(&self.tables, &name) {
+                    // A table's row type, under the table's key
+                    PostgresType::Custom(key)
+                } else if let Some(data_type) = extension_type(unqualified_name(&name))
+                    .filter(|(extension, _)| self.provides(extension, &name))
+                    .map(|(_, data_type)| data_type)
+                {
+                    data_type
+                } else {
+                    PostgresType::Custom(name)
+                }
+            }
+            PostgresType::Array(inner) => PostgresType::Array(Box::new(self.resolve_type(*inner))),
+            other => other,
+        }
+    }
+
+    /// Rename a table within its namespace. Returns the new key.
+    fn rename_table(&mut self, key: &str, new_name: &str) -> String {
+        let Some(mut table) = self.tables.remove(key) else {
+            return key.to_string();
+        };
+
+        table.name = unqualified_name(new_name).to_string();
+        let new_key = table.qualified_name();
+        self.tables.insert(new_key.clone(), table);
+
+        for table in self.tables.values_mut() {
+            for fk in &mut table.foreign_keys {
+                if fk.referenced_table == key {
+                    fk.referenced_table = new_key.clone();
+                }
+            }
+            rename_in(&mut table.parents, key, &new_key);
+        }
+
+        new_key
+    }
+
+    /// Apply CREATE [UNIQUE] INDEX to the table it indexes.
+    ///
+    /// pg_dump also indexes materialized views; those indexes are ignored.
+    fn apply_create_index(&mut self, create: &sqlparser::ast::CreateIndex) -> Result<()> {
+        let table_name = object_name_to_string(&create.table_name);
+        let Some(key) = self.lookup_key(&self.tables, &table_name) else {
+            if self.get_view(&table_name).is_some() {
+                return Ok(());
+            }
+            return Err(Error::UnknownTable(table_name));
+        };
+
+        if let Some(name) = &create.name {
+            let name = object_name_to_string(name);
+            let name = qualify(
+                split_qualified(&key).0.unwrap_or(DEFAULT_NAMESPACE),
+                unqualified_name(&name),
+            );
+            if self.find_index(&name).is_some() {
+                if create.if_not_exists {
+                    return Ok(());
+                }
+                return Err(Error::SchemaParse(format!("Index '{name}' already exists")));
+            }
+        }
+
+        let table = self
+            .tables
+            .get_mut(&key)
+            .ok_or(Error::UnknownTable(table_name))?;
+        table.add_index(create)
+    }
+
+    /// Find an index by (optionally qualified) name, as its table's key and position.
+    ///
+    /// Indexes live in their table's namespace, so unqualified names are looked
+    /// up on the search path.
+    fn find_index(&self, name: &str) -> Option<(String, usize)> {
+        let find_in = |namespace: &str, name: &str| {
+            self.tables.iter().find_map(|(key, table)| {
+                if !table.namespace.eq_ignore_ascii_case(namespace) {
+                    return None;
+                }
+                let idx = table
+                    .indexes
+                    .iter()
+                    .position(|index| index.name.eq_ignore_ascii_case(name))?;
+                Some((key.clone(), idx))
+            })
+        };
+
+        match split_qualified(name) {
+            (Some(namespace), name) => find_in(namespace, name),
+            (None, name) => self
+                .search_path
+                .iter()
+                .find_map(|namespace| find_in(namespace, name)),
+        }
+    }
+
+    /// Get a mutable index by (optionally qualified) name.
+    fn find_index_mut(&mut self, name: &str) -> Option<&mut Index> {
+        let (key, idx) = self.find_index(name)?;
+        self.tables
+            .get_mut(&key)
+            .map(|table| &mut table.indexes[idx])
+    }
+
+    /// Drop an index by name. Returns whether it existed.
+    fn drop_index(&mut self, name: &str) -> bool {
+        let Some((key, idx)) = self.find_index(name) else {
+            return false;
+        };
+        self.tables
+            .get_mut(&key)
+            .map(|table| {
+                table.indexes.remove(idx);
+                table.update_key_columns();
+            })
+            .is_some()
+    }
+
+    /// Remove foreign keys whose referenced table was dropped (`DROP ... CASCADE`).
+    fn drop_references(&mut self, dropped: impl Fn(&str) -> bool) {
+        for table in self.tables.values_mut() {
+            table
+                .foreign_keys
+                .retain(|fk| !dropped(&fk.referenced_table));
+        }
+    }
+
+    /// Resolve the tables and columns a table's foreign keys refer to.
+    ///
+    /// Referenced tables are looked up on the search path like any other name
+    /// (a table may reference itself), and a key that doesn't list referenced
+    /// columns points at the primary key. Keys referencing a table that
+    /// doesn't exist yet are left as written, for
+    /// [`Schema::resolve_pending_foreign_keys`] once the schema is loaded,
+    /// and keep looking the table up with the search path they were
+    /// declared with.
+    fn resolve_foreign_keys(&mut self, table: &mut Table) -> Result<()> {
+        let key = table.qualified_name();
+
+        for idx in 0..table.foreign_keys.len() {
+            let fk = &table.foreign_keys[idx];
+            if let Some(column) = fk.columns.iter().find(|c| !table.has_column(c)) {
+                return Err(table.unknown_column(column));
+            }
+
+            let pending = (key.clone(), fk.name.clone());
+            let search_path = self
+                .pending_search_paths
+                .get(&pending)
+                .unwrap_or(&self.search_path);
+            let Some(referenced_key) =
+                lookup_key_in(&self.tables, &fk.referenced_table, search_path)
+                    .or_else(|| table.is_named(&fk.referenced_table).then(|| key.clone()))
+            else {
+                self.pending_search_paths
+                    .entry(pending)
+                    .or_insert_with(|| self.search_path.clone());
+                continue;
+            };
+            self.pending_search_paths.remove(&pending);
+            let referenced = match self.tables.get(&referenced_key) {
+                Some(referenced) => referenced,
+                None => &*table,
+            };
+
+            if let Some(column) = fk
+                .referenced_columns
+                .iter()
+                .find(|c| !referenced.has_column(c))
+            {
+                return Err(referenced.unknown_column(column));
+            }
+
+            let referenced_columns = if fk.referenced_columns.is_empty() {
+                referenced.primary_key_columns()
+            } else {
+                fk.referenced_columns.clone()
+            };
+            let fk = &mut table.foreign_keys[idx];
+            fk.referenced_table = referenced_key;
+            fk.referenced_columns = referenced_columns;
+        }
+
+        Ok(())
+    }
+
+    /// Resolve the foreign keys that referenced tables created later in the
+    /// schema, failing on any whose table was never created.
+    fn resolve_pending_foreign_keys(&mut self) -> Result<()> {
+        let mut keys: Vec<_> = self
+            .tables
+            .iter()
+            .filter(|(_, table)| {
+                table
+                    .foreign_keys
+                    .iter()
+                    .any(|fk| !self.tables.contains_key(&fk.referenced_table))
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+
+        for key in keys {
+            let mut table = self.tables.remove(&key).expect("table exists");
+            let resolved = self.resolve_foreign_keys(&mut table);
+            let missing = table
+                .foreign_keys
+                .iter()
+                .find(|fk| {
+                    fk.referenced_table != key && !self.tables.contains_key(&fk.referenced_table)
+                })
+                .map(|fk| fk.referenced_table.clone());
+            self.tables.insert(key, table);
+            resolved?;
+            if let Some(missing) = missing {
+                return Err(Error::UnknownTable(missing));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the namespaces tables are looked up in, in order.
+    pub fn search_path(&self) -> &[String] {
+        &self.search_path
+    }
+
+    /// Set the namespaces unqualified names are looked up in (default: `public`).
+    ///
+    /// This is the path queries are checked with. `SET search_path` statements
+    /// in the schema SQL only apply while that SQL is being loaded.
+    pub fn set_search_path<I, S>(&mut self, path: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.search_path = path.into_iter().map(Into::into).collect();
+    }
+
+    /// Use the Rust type `rust_type` (a path like `crate::ids::UserId`) for
+    /// the values of a table column or of a Postgres type.
+    ///
+    /// `target` is a column (`users.id`, `billing.invoices.id`) or a type: a
+    /// built-in type (`bigint`, `timestamptz`, `uuid[]`), an enum, composite or
+    /// extension type, or a domain (`money_cents`). Column overrides win over
+    /// domain overrides, which win over type overrides; overriding a type also
+    /// changes arrays of it. The Rust type must implement `FromSql`.
+    ///
+    /// Overrides apply to the queries checked afterwards, wherever the values
+    /// end up (joins, CTEs, subqueries, RETURNING). Views are typed when the
+    /// schema is loaded, so overrides only apply to the view columns selecting
+    /// a table column unchanged (as they would to the column); computed view
+    /// columns (`id::bigint`, `count(*)`) keep the default types.
+    pub fn add_type_override(&mut self, target: &str, rust_type: &str) -> Result<()> {
+        let rust_type = rust_type.trim().to_string();
+        if let (Some(table_name), column_name) = split_qualified_name(target) {
+            if let Some(table) = self.get_table(table_name) {
+                let column = table
+                    .get_column(column_name)
+                    .ok_or_else(|| table.unknown_column(column_name))?;
+                self.column_overrides
+                    .insert(table.column_key(&column.name), rust_type);
+                return Ok(());
+            }
+        }
+
+        let key = match self.get_domain(target) {
+            Some(domain) => domain.qualified_name(),
+            None => match self.resolve_type(PostgresType::from_sql_name(target)) {
+                PostgresType::Custom(_) => return Err(Error::UnknownType(target.to_string())),
+                data_type => override_key(&data_type),
+            },
+        };
+        self.type_overrides.insert(key, rust_type);
+        Ok(())
+    }
+
+    /// The Rust type of values of a Postgres type, with type overrides applied.
+    pub fn rust_type(&self, data_type: &PostgresType) -> RustType {
+        if let Some(path) = self.type_overrides.get(&override_key(data_type)) {
+            return RustType::Path(path.clone());
+        }
+        match data_type {
+            PostgresType::Array(element) => RustType::Vec(Box::new(self.rust_type(element))),
+            other => other.to_rust_type(),
+        }
+    }
+
+    /// The Rust type of a table column's values (NULL aside), with the
+    /// column's override applied, or else its domain's or its type's.
+    pub fn column_rust_type(&self, table: &Table, column: &Column) -> RustType {
+        let path = self
+            .column_overrides
+            .get(&table.column_key(&column.name))
+            .or_else(|| {
+                column
+                    .domain
+                    .as_ref()
+                    .and_then(|domain| self.type_overrides.get(domain))
+            });
+        match path {
+            Some(path) => RustType::Path(path.clone()),
+            None => self.rust_type(&column.data_type),
+        }
+    }
+
+    /// The Rust type of the values of the table column `key` (see
+    /// [`Table::column_key`]) with overrides applied, as for
+    /// [`Schema::column_rust_type`], if the column exists.
+    pub fn source_rust_type(&self, key: &str) -> Option<RustType> {
+        // The qualified table name is quoted, since it contains a dot
+        let (table_name, column_name) = split_qualified_name(key);
+        let table = self.get_table(unquote(table_name?))?;
+        let column = table.get_column(column_name)?;
+        Some(self.column_rust_type(table, column))
+    }
+
+    /// Check if a namespace (`CREATE SCHEMA`) exists.
+    pub fn has_namespace(&self, name: &str) -> bool {
+        self.namespaces.iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// Get all namespace names.
+    pub fn namespace_names(&self) -> impl Iterator<Item = &str> {
+        self.namespaces.iter().map(|s| s.as_str())
+    }
+
+    /// Get a table by name, qualified (`billing.invoices`) or resolved via the search path.
+    pub fn get_table(&self, name: &str) -> Option<&Table> {
+        self.lookup_key(&self.tables, name)
+            .and_then(|key| self.tables.get(&key))
+    }
+
+    /// Get all qualified table names.
+    pub fn table_names(&self) -> impl Iterator<Item = &str> {
+        self.tables.keys().map(|s| s.as_str())
+    }
+
+    /// Check if a table exists.
+    pub fn has_table(&self, name: &str) -> bool {
+        self.get_table(name).is_some()
+    }
+
+    /// Get an enum type by name.
+    pub fn get_enum(&self, name: &str) -> Option<&EnumType> {
+        self.lookup_key(&self.enums, name)
+            .and_then(|key| self.enums.get(&key))
+    }
+
+    /// Get all enum type names.
+    pub fn enum_names(&self) -> impl Iterator<Item = &str> {
+        self.enums.keys().map(|s| s.as_str())
+    }
+
+    /// Get a composite type by name.
+    pub fn get_composite(&self, name: &str) -> Option<&CompositeType> {
+        self.lookup_key(&self.composites, name)
+            .and_then(|key| self.composites.get(&key))
+    }
+
+    /// Get all composite type names.
+    pub fn composite_names(&self) -> impl Iterator<Item = &str> {
+        self.composites.keys().map(|s| s.as_str())
+    }
+
+    /// Get a view (or materialized view) by name.
+    pub fn get_view(&self, name: &str) -> Option<&View> {
+        self.lookup_key(&self.views, name)
+            .and_then(|key| self.views.get(&key))
+    }
+
+    /// Get all view names.
+    pub fn view_names(&self) -> impl Iterator<Item = &str> {
+        self.views.keys().map(|s| s.as_str())
+    }
+
+    /// Get a domain by name.
+    pub fn get_domain(&self, name: &str) -> Option<&DomainType> {
+        self.lookup_key(&self.domains, name)
+            .and_then(|key| self.domains.get(&key))
+    }
+
+    /// Get all domain names.
+    pub fn domain_names(&self) -> impl Iterator<Item = &str> {
+        self.domains.keys().map(|s| s.as_str())
+    }
+
+    /// Get the overloads of a function, resolving unqualified names through the search path.
+    pub fn get_functions(&self, name: &str) -> &[Function] {
+        self.lookup_key(&self.functions, name)
+            .and_then(|key| self.functions.get(&key))
+            .map_or(&[], |overloads| overloads.as_slice())
+    }
+
+    /// Get all function names.
+    pub fn function_names(&self) -> impl Iterator<Item = &str> {
+        self.functions.keys().map(|s| s.as_str())
+    }
+
+    /// Get a sequence by name, resolving unqualified names through the search path.
+    pub fn get_sequence(&self, name: &str) -> Option<&Sequence> {
+        self.lookup_key(&self.sequences, name)
+            .and_then(|key| self.sequences.get(&key))
+    }
+
+    /// Get all sequence names.
+    pub fn sequence_names(&self) -> impl Iterator<Item = &str> {
+        self.sequences.keys().map(|s| s.as_str())
+    }
+
+    /// Get an installed extension by name.
+    pub fn get_extension(&self, name: &str) -> Option<&Extension> {
+        self.extensions.get(&name.to_lowercase())
+    }
+
+    /// Get all installed extension names.
+    pub fn extension_names(&self) -> impl Iterator<Item = &str> {
+        self.extensions.keys().map(|s| s.as_str())
+    }
+
+    /// The return type of a function that comes with an installed extension,
+    /// like `nlevel` (ltree) or `st_astext` (PostGIS), called with a first
+    /// argument of type `first_arg` (if known).
+    pub fn extension_function(
+        &self,
+        name: &str,
+        first_arg: Option<&PostgresType>,
+    ) -> Option<PostgresType> {
+        extension_function(unqualified_name(name), first_arg)
+            .filter(|(extension, _)| self.provides(extension, name))
+            .map(|(_, data_type)| data_type)
+    }
+
+    /// Whether `extension` is installed, and `name` can refer to one of its
+    /// objects: qualified with the extension's schema, or unqualified with
+    /// that schema on the search path.
+    fn provides(&self, extension: &str, name: &str) -> bool {
+        let Some(extension) = self.extensions.get(extension) else {
+            return false;
+        };
+        match split_qualified(name) {
+            (Some(namespace), _) => namespace == extension.namespace,
+            (None, _) => self.search_path.contains(&extension.namespace),
+        }
+    }
+}
+
+/// A user-defined enum type from `CREATE TYPE ... AS ENUM`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnumType {
+    pub name: String,
+    pub namespace: String,
+    /// Labels in declaration order (which is also their sort order).
+    pub labels: Vec<String>,
+}
+
+impl EnumType {
+    /// The schema key of this type (`public.mood`).
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+
+    /// Name of the Rust enum generated for this type (`order_status` -> `OrderStatus`).
+    pub fn rust_name(&self) -> String {
+        to_pascal_case(&self.name)
+    }
+
+    /// Check if a label is one of the enum's allowed values.
+    pub fn has_label(&self, label: &str) -> bool {
+        self.labels.iter().any(|l| l == label)
+    }
+
+    /// Position of a label, erroring if the enum doesn't have it.
+    fn label_index(&self, label: &str) -> Result<usize> {
+        self.labels.iter().position(|l| l == label).ok_or_else(|| {
+            Error::SchemaParse(format!(
+                "Enum label '{}' does not exist in type '{}'",
+                label, self.name
+            ))
+        })
+    }
+}
+
+/// A user-defined composite type from `CREATE TYPE ... AS (...)`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompositeType {
+    pub name: String,
+    pub namespace: String,
+    /// Fields in declaration order (which is also their wire order).
+    pub fields: Vec<CompositeField>,
+}
+
+impl CompositeType {
+    /// The schema key of this type (`public.postal_address`).
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+
+    /// Name of the Rust struct generated for this type (`postal_address` -> `PostalAddress`).
+    pub fn rust_name(&self) -> String {
+        to_pascal_case(&self.name)
+    }
+
+    /// Get a field by name.
+    pub fn get_field(&self, name: &str) -> Option<&CompositeField> {
+        self.fields
+            .iter()
+            .find(|f| f.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A field of a composite type.
+///
+/// Composite fields can't be declared NOT NULL, so they are always nullable.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompositeField {
+    pub name: String,
+    pub data_type: PostgresType,
+}
+
+/// A domain from `CREATE DOMAIN`: a base type plus constraints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DomainType {
+    pub name: String,
+    pub namespace: String,
+    /// The resolved underlying type (never another domain).
+    pub base_type: PostgresType,
+    /// Whether the domain is NOT NULL, making every column of it non-nullable.
+    pub not_null: bool,
+    pub has_default: bool,
+}
+
+impl DomainType {
+    /// The schema key of this domain (`public.email_address`).
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+
+    /// Name of the Rust newtype generated for this domain (`email_address` -> `EmailAddress`).
+    pub fn rust_name(&self) -> String {
+        to_pascal_case(&self.name)
+    }
+}
+
+/// A user-defined function from `CREATE FUNCTION`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Function {
+    pub name: String,
+    pub namespace: String,
+    /// Input arguments (`IN`, `INOUT` and `VARIADIC`) in declaration order.
+    pub args: Vec<FunctionArgument>,
+    pub returns: FunctionReturn,
+    /// Whether the function returns NULL whenever an argument is NULL (`STRICT`).
+    pub strict: bool,
+}
+
+impl Function {
+    /// The schema key of this function (`public.order_total`).
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+
+    /// Whether a call with this many arguments matches the signature.
+    ///
+    /// Arguments with defaults can be left out, and a variadic argument takes
+    /// one or more values.
+    pub fn accepts(&self, arg_count: usize) -> bool {
+        let required = self.args.iter().filter(|arg| !arg.has_default).count();
+        if self.args.iter().any(|arg| arg.variadic) {
+            arg_count >= required
+        } else {
+            (required..=self.args.len()).contains(&arg_count)
+        }
+    }
+
+    fn arg_types(&self) -> Vec<PostgresType> {
+        self.args.iter().map(|arg| arg.data_type.clone()).collect()
+    }
+
+    fn has_signature(&self, arg_types: &[PostgresType]) -> bool {
+        self.args.len() == arg_types.len()
+            && self
+                .args
+                .iter()
+                .zip(arg_types)
+                .all(|(arg, data_type)| arg.data_type == *data_type)
+    }
+}
+
+/// An input argument of a function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionArgument {
+    pub name: Option<String>,
+    pub data_type: PostgresType,
+    pub has_default: bool,
+    /// A `VARIADIC` argument, taking any number of trailing values.
+    pub variadic: bool,
+}
+
+/// What a function returns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FunctionReturn {
+    /// `RETURNS type`: one value per call.
+    Scalar(PostgresType),
+    /// `RETURNS SETOF type`: any number of values per call.
+    SetOf(PostgresType),
+    /// `RETURNS TABLE (...)`, or rows described by OUT arguments.
+    Table(Vec<CompositeField>),
+}
+
+/// A sequence from `CREATE SEQUENCE`, or the one behind a serial or identity column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sequence {
+    pub name: String,
+    pub namespace: String,
+    /// The sequence's integer type (`bigint` unless declared otherwise).
+    pub data_type: PostgresType,
+}
+
+impl Sequence {
+    /// The schema key of this sequence (`public.users_id_seq`).
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+}
+
+/// An extension from `CREATE EXTENSION`.
+///
+/// The types and functions of the extensions in
+/// [`KNOWN_EXTENSIONS`](crate::extensions::KNOWN_EXTENSIONS) are recognized
+/// once the extension is installed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Extension {
+    pub name: String,
+    /// The schema the extension's objects are created in.
+    pub namespace: String,
+}
+
+/// A statement skipped by [`Schema::from_sql_tolerant`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaWarning {
+    /// The file the statement is in, when loaded from files.
+    pub file: Option<PathBuf>,
+    /// The line the skipped statement starts on.
+    pub line: u64,
+    /// Why it was skipped.
+    pub message: String,
+}
+
+impl fmt::Display for SchemaWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}: {}", file.display(), self.line, self.message),
+            None => write!(f, "line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+/// A view or materialized view, queryable like a table.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct View {
+    pub name: String,
+    pub namespace: String,
+    /// Output columns, typed by validating the view's query.
+    pub columns: Vec<QueryColumn>,
+    pub materialized: bool,
+    /// The view's query, as SQL.
+    #[serde(default)]
+    pub definition: String,
+    /// Why the view's query couldn't be typed, if it couldn't. The view then
+    /// has no columns, and queries using it fail with this error.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl View {
+    /// The schema key of this view (`public.active_users`).
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+
+    /// Get an output column by name.
+    pub fn get_column(&self, name: &str) -> Option<&QueryColumn> {
+        self.columns
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A database table.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Table {
+    pub name: String,
+    pub namespace: String,
+    pub columns: Vec<Column>,
+    pub foreign_keys: Vec<ForeignKey>,
+    pub indexes: Vec<Index>,
+    pub checks: Vec<Check>,
+    /// The primary key and UNIQUE constraints, primary key first.
+    #[serde(default)]
+    pub unique_keys: Vec<UniqueKey>,
+    /// The table's `COMMENT ON TABLE` text.
+    pub comment: Option<String>,
+    /// Qualified names of the tables this one inherits from: its `INHERITS`
+    /// tables, or the partitioned table it is a partition of.
+    #[serde(default)]
+    pub parents: Vec<String>,
+    /// `PARTITION BY` of a partitioned table (`RANGE(occurred_at)`).
+    #[serde(default)]
+    pub partition_key: Option<String>,
+    /// The bound of a partition (`FOR VALUES FROM (...) TO (...)`, `DEFAULT`).
+    #[serde(default)]
+    pub partition_bound: Option<String>,
+    /// Rebuilt from `columns` when loading a snapshot.
+    #[serde(skip)]
+    column_map: HashMap<String, usize>,
+}
+
+impl Table {
+    /// The schema key of this table (`public.users`).
+    pub fn qualified_name(&self) -> String {
+        qualify(&self.namespace, &self.name)
+    }
+
+    /// The schema key of one of this table's columns (`public.users.id`).
+    pub fn column_key(&self, column: &str) -> String {
+        qualify(&self.qualified_name(), &column.to_lowercase())
+    }
+
+    /// Create a table from a CREATE TABLE statement.
+    ///
+    /// The table starts out with what it inherits from `parents`: its
+    /// `INHERITS` tables, or the table it is a partition of if it has a
+    /// partition `bound`. Columns it declares again are merged into the
+    /// inherited ones; its own columns follow.
+    fn from_create_table(
+        create: &CreateTable,
+        namespace: String,
+        name: String,
+        parents: &[&Table],
+        bound: Option<String>,
+    ) -> Result<Self> {
+        let mut table = Table {
+            name,
+            namespace,
+            columns: Vec::new(),
+            foreign_keys: Vec::new(),
+            indexes: Vec::new(),
+            checks: Vec::new(),
+            unique_keys: Vec::new(),
+            comment: None,
+            parents: Vec::new(),
+            partition_key: create.partition_by.as_ref().map(ToString::to_string),
+            partition_bound: None,
+            column_map: HashMap::new(),
+        };
+        for parent in parents {
+            table.inherit(parent, bound.is_some());
+        }
+        table.partition_bound = bound;
+
+        // First pass: extract columns
+        for col_def in &create.columns {
+            if let Some(&idx) = table.column_map.get(&col_def.name.value.to_lowercase()) {
+                table.columns[idx].apply_options(&col_def.options, &table.namespace, &table.name);
+            } else if col_def.data_type == DataType::Unspecified {
+                // Partitions can only add options to their parent's columns
+                return Err(table.unknown_column(&col_def.name.value));
+            } else {
+                let column = Column::from_column_def(col_def, &table.namespace, &table.name)?;
+                table.columns.push(column);
+                table.rebuild_column_map();
+            }
+        }
+
+        // Column-level REFERENCES and CHECK
+        for col_def in &create.columns {
+            table.add_column_constraints(col_def);
+        }
+
+        // Second pass: handle table constraints (PRIMARY KEY, UNIQUE, etc.)
+        for constraint in &create.constraints {
+            table.apply_constraint(constraint);
+        }
+
+        Ok(table)
+    }
+
+    /// Take over the columns and constraints of a parent table.
+    ///
+    /// Columns keep their NOT NULL, defaults and generation expressions, but
+    /// not identity or the sequence behind a serial column, which stays owned
+    /// by the parent. CHECK constraints are inherited. Partitions also get
+    /// the parent's primary key, unique constraints and foreign keys, which
+    /// Postgres creates on every partition; `INHERITS` children don't. A
+    /// column the table already has is merged with the parent's.
+    fn inherit(&mut self, parent: &Table, partition: bool) {
+        for column in &parent.columns {
+            let identity = matches!(column.generated, Some(Generated::Identity { .. }));
+            let inherited = Column {
+                has_default: column.has_default && !identity,
+                is_primary_key: partition && column.is_primary_key,
+                is_unique: partition && column.is_unique,
+                generated: column.generated.clone().filter(|_| !identity),
+                sequence: None,
+                comment: None,
+                ..column.clone()
+            };
+            match self.get_column_mut(&column.name) {
+                Ok(existing) => {
+                    existing.nullable &= inherited.nullable;
+                    existing.has_default |= inherited.has_default;
+                    existing.is_primary_key |= inherited.is_primary_key;
+                    existing.is_unique |= inherited.is_unique;
+                    if existing.generated.is_none() {
+                        existing.generated = inherited.generated;
+                    }
+                }
+                Err(_) => {
+                    self.columns.push(inherited);
+                    self.rebuild_column_map();
+                }
+            }
+        }
+
+        for check in &parent.checks {
+            if self.get_check(&check.name).is_none() {
+                self.checks.push(check.clone());
+            }
+        }
+        if partition {
+            for fk in &parent.foreign_keys {
+                if self.get_foreign_key(&fk.name).is_none() {
+                    self.foreign_keys.push(fk.clone());
+                }
+            }
+            // Each partition's keys are named after the partition
+            for key in &parent.unique_keys {
+                let exists = self
+                    .unique_keys
+                    .iter()
+                    .any(|own| own.primary == key.primary && own.columns == key.columns);
+                if !exists {
+                    self.add_unique_key(None, key.columns.clone(), key.primary);
+                }
+            }
+        }
+        self.parents.push(parent.qualified_name());
+    }
+
+    /// Apply a table-level constraint to the columns it covers.
+    fn apply_constraint(&mut self, constraint: &TableConstraint) {
+        match constraint {
+            TableConstraint::PrimaryKey(pk) => {
+                let columns = self.constraint_columns(&pk.columns);
+                for column in &columns {
+                    if let Ok(column) = self.get_column_mut(column) {
+                        column.nullable = false; // PKs are never null
+                    }
+                }
+                self.add_unique_key(pk.name.as_ref().map(|n| n.value.as_str()), columns, true);
+            }
+            TableConstraint::Unique(unique) => {
+                let columns = self.constraint_columns(&unique.columns);
+                self.add_unique_key(
+                    unique.name.as_ref().map(|n| n.value.as_str()),
+                    columns,
+                    false,
+                );
+            }
+            TableConstraint::ForeignKey(fk) => {
+                let columns = fk.columns.iter().map(|c| c.value.clone()).collect();
+                self.add_foreign_key(fk.name.as_ref(), columns, fk);
+            }
+            TableConstraint::Check(check) => self.add_check(check, None),
+            _ => {}
+        }
+    }
+
+    /// Add the constraints declared inline on a column (`REFERENCES users (id)`, `CHECK (...)`).
+    fn add_column_constraints(&mut self, col_def: &ColumnDef) {
+        for option in &col_def.options {
+            match &option.option {
+                ColumnOption::ForeignKey(fk) => {
+                    let name = option.name.as_ref().or(fk.name.as_ref());
+                    self.add_foreign_key(name, vec![col_def.name.value.clone()], fk);
+                }
+                ColumnOption::PrimaryKey(pk) => {
+                    let name = option.name.as_ref().or(pk.name.as_ref());
+                    let name = name.map(|n| n.value.as_str());
+                    self.add_unique_key(name, vec![col_def.name.value.clone()], true);
+                }
+                ColumnOption::Unique(unique) => {
+                    let name = option.name.as_ref().or(unique.name.as_ref());
+                    let name = name.map(|n| n.value.as_str());
+                    self.add_unique_key(name, vec![col_def.name.value.clone()], false);
+                }
+                ColumnOption::Check(check) => {
+                    let check = CheckConstraint {
+                        name: option.name.clone().or_else(|| check.name.clone()),
+                        ..check.clone()
+                    };
+                    self.add_check(&check, Some(&col_def.name.value));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The table's names of the columns a PRIMARY KEY or UNIQUE constraint lists.
+    fn constraint_columns(&self, columns: &[sqlparser::ast::IndexColumn]) -> Vec<String> {
+        columns
+            .iter()
+            .filter_map(|column| match &column.column.expr {
+                Expr::Identifier(ident) => self.get_column(&ident.value),
+                _ => None,
+            })
+            .map(|column| column.name.clone())
+            .collect()
+    }
+
+    /// Add a PRIMARY KEY or UNIQUE constraint and mark its columns.
+    ///
+    /// Unnamed constraints get Postgres' default name (`users_pkey`,
+    /// `users_email_key`). Keys are kept in the order Postgres lists them:
+    /// the primary key, then the rest by name.
+    fn add_unique_key(&mut self, name: Option<&str>, columns: Vec<String>, primary: bool) {
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => {
+                let base = if primary {
+                    format!("{}_pkey", self.name)
+                } else {
+                    format!("{}_{}_key", self.name, columns.join("_"))
+                };
+                let mut name = base.clone();
+                let mut suffix = 0;
+                while self.get_unique_key(&name).is_some() {
+                    suffix += 1;
+                    name = format!("{base}{suffix}");
+                }
+                name
+            }
+        };
+        if self.get_unique_key(&name).is_some() {
+            return;
+        }
+
+        self.unique_keys.push(UniqueKey {
+            name,
+            columns,
+            primary,
+        });
+        self.unique_keys
+            .sort_by(|a, b| b.primary.cmp(&a.primary).then_with(|| a.name.cmp(&b.name)));
+        self.update_key_columns();
+    }
+
+    /// Rebuild the primary key and UNIQUE constraints from the columns' flags,
+    /// for snapshots written before they were tracked.
+    ///
+    /// Constraints get Postgres' default names. Columns a unique index makes
+    /// unique don't get a constraint.
+    fn rebuild_unique_keys(&mut self) {
+        let primary_key = self.primary_key_columns();
+        let unique: Vec<_> = self
+            .columns
+            .iter()
+            .filter(|c| c.is_unique)
+            .map(|c| c.name.clone())
+            .filter(|name| !self.indexes.iter().any(|i| i.unique_column() == Some(name)))
+            .collect();
+
+        if !primary_key.is_empty() {
+            self.add_unique_key(None, primary_key, true);
+        }
+        for column in unique {
+            self.add_unique_key(None, vec![column], false);
+        }
+    }
+
+    /// Recompute which columns are primary key or unique columns from the
+    /// remaining constraints and unique indexes, after one was added or dropped.
+    fn update_key_columns(&mut self) {
+        for column in &mut self.columns {
+            let in_key = |primary: bool| {
+                self.unique_keys.iter().any(|key| {
+                    key.primary == primary
+                        && key
+                            .columns
+                            .iter()
+                            .any(|c| c.eq_ignore_ascii_case(&column.name))
+                })
+            };
+            column.is_primary_key = in_key(true);
+            column.is_unique = in_key(false)
+                || self
+                    .indexes
+                    .iter()
+                    .filter_map(Index::unique_column)
+                    .any(|c| c.eq_ignore_ascii_case(&column.name));
+        }
+    }
+
+    /// Add a CHECK constraint.
+    ///
+    /// An enforced `CHECK (col IS NOT NULL)` makes the column non-nullable.
+    /// Unnamed constraints get Postgres' default name (`orders_status_check`).
+    fn add_check(&mut self, check: &CheckConstraint, column: Option<&str>) {
+        let columns = expr_columns(&check.expr);
+
+        let name = match &check.name {
+            Some(name) => name.value.clone(),
+            None => {
+                let base = match column.or(columns.first().map(String::as_str)) {
+                    Some(column) => format!("{}_{}_check", self.name, column),
+                    None => format!("{}_check", self.name),
+                };
+                let mut name = base.clone();
+                let mut suffix = 0;
+                while self.get_check(&name).is_some() {
+                    suffix += 1;
+                    name = format!("{base}{suffix}");
+                }
+                name
+            }
+        };
+
+        if check.enforced != Some(false) {
+            for column in not_null_columns(&check.expr) {
+                if let Ok(column) = self.get_column_mut(&column) {
+                    column.nullable = false;
+                }
+            }
+        }
+
+        self.checks.push(Check {
+            name,
+            expression: check.expr.to_string(),
+            columns,
+            allowed_values: allowed_values(&check.expr),
+        });
+    }
+
+    /// Add a foreign key over `columns`.
+    ///
+    /// The referenced table is kept as written until the schema resolves it.
+    /// Unnamed constraints get Postgres' default name (`orders_user_id_fkey`).
+    fn add_foreign_key(
+        &mut self,
+        name: Option<&Ident>,
+        columns: Vec<String>,
+        fk: &ForeignKeyConstraint,
+    ) {
+        let name = match name {
+            Some(name) => name.value.clone(),
+            None => {
+                let base = format!("{}_{}_fkey", self.name, columns.join("_"));
+                let mut name = base.clone();
+                let mut suffix = 0;
+                while self.get_foreign_key(&name).is_some() {
+                    suffix += 1;
+                    name = format!("{base}{suffix}");
+                }
+                name
+            }
+        };
+
+        self.foreign_keys.push(ForeignKey {
+            name,
+            columns,
+            referenced_table: object_name_to_string(&fk.foreign_table),
+            referenced_columns: fk
+                .referred_columns
+                .iter()
+                .map(|c| c.value.clone())
+                .collect(),
+            on_delete: fk.on_delete.map_or(ReferentialAction::NoAction, Into::into),
+            on_update: fk.on_update.map_or(ReferentialAction::NoAction, Into::into),
+        });
+    }
+
+    /// Add an index from CREATE [UNIQUE] INDEX.
+    ///
+    /// A unique index over a single column without a predicate makes that
+    /// column unique, just like a UNIQUE constraint.
+    fn add_index(&mut self, create: &sqlparser::ast::CreateIndex) -> Result<()> {
+        let keys = create
+            .columns
+            .iter()
+            .map(|column| match &column.column.expr {
+                Expr::Identifier(ident) => IndexKey::Column(ident.value.clone()),
+                expr => IndexKey::Expression(expr.to_string()),
+            })
+            .collect::<Vec<_>>();
+
+        for key in &keys {
+            if let IndexKey::Column(column) = key {
+                if !self.has_column(column) {
+                    return Err(self.unknown_column(column));
+                }
+            }
+        }
+
+        let method = create
+            .using
+            .as_ref()
+            .or_else(|| {
+                create.index_options.iter().find_map(|option| match option {
+                    IndexOption::Using(method) => Some(method),
+                    _ => None,
+                })
+            })
+            .map_or_else(|| "btree".to_string(), |m| m.to_string().to_lowercase());
+
+        let name = match &create.name {
+            Some(name) => unqualified_name(&object_name_to_string(name)).to_string(),
+            // Postgres names unnamed indexes after the table and columns (or
+            // the functions of expression keys), numbering repeated names
+            None => {
+                let mut columns: Vec<String> = Vec::new();
+                for column in &create.columns {
+                    let base = index_column_name(&column.column.expr);
+                    let mut name = base.clone();
+                    let mut suffix = 0;
+                    while columns.contains(&name) {
+                        suffix += 1;
+                        name = format!("{base}{suffix}");
+                    }
+                    columns.push(name);
+                }
+                let base = format!("{}_{}_idx", self.name, columns.join("_"));
+                let mut name = base.clone();
+                let mut suffix = 0;
+                while self.get_index(&name).is_some() || self.get_unique_key(&name).is_some() {
+                    suffix += 1;
+                    name = format!("{base}{suffix}");
+                }
+                name
+            }
+        };
+
+        let index = Index {
+            name,
+            keys,
+            unique: create.unique,
+            predicate: create.predicate.as_ref().map(ToString::to_string),
+            method,
+            include: create.include.iter().map(|c| c.value.clone()).collect(),
+        };
+
+        if let Some(column) = index.unique_column() {
+            let column = column.to_string();
+            self.get_column_mut(&column)?.is_unique = true;
+        }
+        self.indexes.push(index);
+
+        Ok(())
+    }
+
+    /// Apply a single ALTER TABLE operation to this table.
+    fn apply_alter_operation(&mut self, operation: &AlterTableOperation) -> Result<()> {
+        match operation {
+            // Existing rows aren't checked against a NOT VALID constraint,
+            // so it says nothing about the column's nullability.
+            AlterTableOperation::AddConstraint {
+                constraint: TableConstraint::Check(check),
+                not_valid: true,
+            } => {
+                let check = CheckConstraint {
+                    enforced: Some(false),
+                    ..check.clone()
+                };
+                self.add_check(&check, None);
+            }
+            AlterTableOperation::AddConstraint { constraint, .. } => {
+                self.apply_constraint(constraint);
+            }
+            AlterTableOperation::AddColumn {
+                if_not_exists,
+                column_def,
+                ..
+            } => {
+                if self.has_column(&column_def.name.value) {
+                    if *if_not_exists {
+                        return Ok(());
+                    }
+                    return Err(Error::SchemaParse(format!(
+                        "Column '{}' already exists in table '{}'",
+                        column_def.name.value, self.name
+                    )));
+                }
+                self.columns.push(Column::from_column_def(
+                    column_def,
+                    &self.namespace,
+                    &self.name,
+                )?);
+                self.rebuild_column_map();
+                self.add_column_constraints(column_def);
+            }
+            AlterTableOperation::DropColumn {
+                column_names,
+                if_exists,
+                ..
+            } => {
+                for col_name in column_names {
+                    if !self.has_column(&col_name.value) {
+                        if *if_exists {
+                            continue;
+                        }
+                        return Err(self.unknown_column(&col_name.value));
+                    }
+                    self.columns
+                        .retain(|c| !c.name.eq_ignore_ascii_case(&col_name.value));
+                    self.rebuild_column_map();
+                    // Postgres drops indexes and constraints on a dropped column along with it
+                    self.indexes
+                        .retain(|index| !index.has_column(&col_name.value));
+                    self.checks.retain(|check| {
+                        !check
+                            .columns
+                            .iter()
+                            .any(|c| c.eq_ignore_ascii_case(&col_name.value))
+                    });
+                    self.foreign_keys.retain(|fk| {
+                        !fk.columns
+                            .iter()
+                            .any(|c| c.eq_ignore_ascii_case(&col_name.value))
+                    });
+                    self.unique_keys.retain(|key| {
+                        !key.columns
+                            .iter()
+                            .any(|c| c.eq_ignore_ascii_case(&col_name.value))
+                    });
+                    self.update_key_columns();
+                }
+            }
+            AlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                let column = self.get_column_mut(&old_column_name.value)?;
+                column.name = new_column_name.value.clone();
+                self.rebuild_column_map();
+                for fk in &mut self.foreign_keys {
+                    rename_in(
+                        &mut fk.columns,
+                        &old_column_name.value,
+                        &new_column_name.value,
+                    );
+                }
+                for key in &mut self.unique_keys {
+                    rename_in(
+                        &mut key.columns,
+                        &old_column_name.value,
+                        &new_column_name.value,
+                    );
+                }
+                for index in &mut self.indexes {
+                    for key in &mut index.keys {
+                        if let IndexKey::Column(column) = key {
+                            if column.eq_ignore_ascii_case(&old_column_name.value) {
+                                *column = new_column_name.value.clone();
+                            }
+                        }
+                    }
+                    rename_in(
+                        &mut index.include,
+                        &old_column_name.value,
+                        &new_column_name.value,
+                    );
+                }
+                for check in &mut self.checks {
+                    rename_in(
+                        &mut check.columns,
+                        &old_column_name.value,
+                        &new_column_name.value,
+                    );
+                    if let Some(allowed) = &mut check.allowed_values {
+                        if allowed.column.eq_ignore_ascii_case(&old_column_name.value) {
+                            allowed.column = new_column_name.value.clone();
+                        }
+                    }
+                }
+            }
+            AlterTableOperation::DropConstraint { name, .. } => {
+                // Columns stay NOT NULL when their primary key is dropped
+                self.foreign_keys
+                    .retain(|fk| !fk.name.eq_ignore_ascii_case(&name.value));
+                self.checks
+                    .retain(|check| !check.name.eq_ignore_ascii_case(&name.value));
+                self.unique_keys
+                    .retain(|key| !key.name.eq_ignore_ascii_case(&name.value));
+                self.update_key_columns();
+            }
+            AlterTableOperation::AlterColumn { column_name, op } => {
+                let sequence = implicit_sequence(&self.namespace, &self.name, &column_name.value);
+                let column = self.get_column_mut(&column_name.value)?;
+                match op {
+                    AlterColumnOperation::SetNotNull => column.nullable = false,
+                    AlterColumnOperation::DropNotNull => column.nullable = true,
+                    AlterColumnOperation::SetDefault { .. } => column.has_default = true,
+                    AlterColumnOperation::DropDefault => column.has_default = false,
+                    AlterColumnOperation::AddGenerated { generated_as, .. } => {
+                        column.has_default = true;
+                        column.nullable = false;
+                        column.generated = Some(Generated::Identity {
+                            always: *generated_as != Some(GeneratedAs::ByDefault),
+                        });
+                        column.sequence = Some(sequence);
+                    }
+                    AlterColumnOperation::SetDataType { data_type, .. } => {
+                        column.data_type = data_type_to_postgres(data_type)?;
+                        column.domain = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Get a mutable column by name, erroring if it does not exist.
+    fn get_column_mut(&mut self, name: &str) -> Result<&mut Column> {
+        match self.column_map.get(&name.to_lowercase()) {
+            Some(&idx) => Ok(&mut self.columns[idx]),
+            None => Err(self.unknown_column(name)),
+        }
+    }
+
+    /// Build an UnknownColumn error for this table.
+    fn unknown_column(&self, column: &str) -> Error {
+        Error::UnknownColumn {
+            table: self.name.clone(),
+            column: column.to_string(),
+        }
+    }
+
+    /// Rebuild the name -> index lookup after columns were added, removed or renamed.
+    fn rebuild_column_map(&mut self) {
+        self.column_map = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(idx, c)| (c.name.to_lowercase(), idx))
+            .collect();
+    }
+
+    /// Get a column by name.
+    pub fn get_column(&self, name: &str) -> Option<&Column> {
+        let name_lower = name.to_lowercase();
+        self.column_map
+            .get(&name_lower)
+            .map(|&idx| &self.columns[idx])
+    }
+
+    /// Check if a column exists.
+    pub fn has_column(&self, name: &str) -> bool {
+        self.get_column(name).is_some()
+    }
+
+    /// Get all column names.
+    pub fn column_names(&self) -> impl Iterator<Item = &str> {
+        self.columns.iter().map(|c| c.name.as_str())
+    }
+
+    /// Check whether an (optionally qualified) name refers to this table.
+    fn is_named(&self, name: &str) -> bool {
+        match split_qualified(name) {
+            (Some(namespace), name) => {
+                namespace.eq_ignore_ascii_case(&self.namespace)
+                    && name.eq_ignore_ascii_case(&self.name)
+            }
+            (None, name) => name.eq_ignore_ascii_case(&self.name),
+        }
+    }
+
+    /// Get a foreign key by constraint name.
+    pub fn get_foreign_key(&self, name: &str) -> Option<&ForeignKey> {
+        self.foreign_keys
+            .iter()
+            .find(|fk| fk.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Get the foreign keys a column is part of.
+    pub fn foreign_keys_for_column<'a>(
+        &'a self,
+        column: &'a str,
+    ) -> impl Iterator<Item = &'a ForeignKey> {
+        self.foreign_keys
+            .iter()
+            .filter(move |fk| fk.columns.iter().any(|c| c.eq_ignore_ascii_case(column)))
+    }
+
+    /// Get an index by name.
+    pub fn get_index(&self, name: &str) -> Option<&Index> {
+        self.indexes
+            .iter()
+            .find(|index| index.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Get a PRIMARY KEY or UNIQUE constraint by name.
+    pub fn get_unique_key(&self, name: &str) -> Option<&UniqueKey> {
+        self.unique_keys
+            .iter()
+            .find(|key| key.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Get a CHECK constraint by name.
+    pub fn get_check(&self, name: &str) -> Option<&Check> {
+        self.checks
+            .iter()
+            .find(|check| check.name.eq_ignore_ascii_case(name))
+    }
+
+    /// The values a column is limited to by a `CHECK (col IN (...))` constraint.
+    pub fn allowed_values(&self, column: &str) -> Option<&[String]> {
+        self.checks.iter().find_map(|check| {
+            check
+                .allowed_values
+                .as_ref()
+                .filter(|allowed| allowed.column.eq_ignore_ascii_case(column))
+                .map(|allowed| allowed.values.as_slice())
+        })
+    }
+
+    /// The primary key columns, in the order the key declares them.
+    fn primary_key_columns(&self) -> Vec<String> {
+        match self.unique_keys.iter().find(|key| key.primary) {
+            Some(key) => key.columns.clone(),
+            // Snapshots written before keys were tracked only have the flags
+            None => self
+                .columns
+                .iter()
+                .filter(|c| c.is_primary_key)
+                .map(|c| c.name.clone())
+                .collect(),
+        }
+    }
+}
+
+/// A table column.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Column {
+    pub name: String,
+    pub data_type: PostgresType,
+    pub nullable: bool,
+    pub has_default: bool,
+    pub is_primary_key: bool,
+    pub is_unique: bool,
+    /// How the column's value is generated, for generated and identity columns.
+    pub generated: Option<Generated>,
+    /// The sequence owned by this column (`public.users_id_seq`): the one
+    /// behind a `serial` or identity column, or one attached with
+    /// `ALTER SEQUENCE ... OWNED BY`.
+    pub sequence: Option<String>,
+    /// The column's `COMMENT ON COLUMN` text.
+    pub comment: Option<String>,
+    /// The domain the column is declared with (`public.email_address`);
+    /// `data_type` is the domain's base type.
+    #[serde(default)]
+    pub domain: Option<String>,
+}
+
+impl Column {
+    /// Create a column of the table `namespace.table` from a ColumnDef.
+    ///
+    /// `serial` columns become NOT NULL integers defaulting to
+    /// `nextval('<table>_<column>_seq')`, like Postgres expands them.
+    fn from_column_def(col_def: &ColumnDef, namespace: &str, table: &str) -> Result<Self> {
+        let name = col_def.name.value.clone();
+        let (data_type, sequence) = match serial_type(&col_def.data_type) {
+            Some(data_type) => (data_type, Some(implicit_sequence(namespace, table, &name))),
+            None => (data_type_to_postgres(&col_def.data_type)?, None),
+        };
+
+        let mut column = Column {
+            name,
+            data_type,
+            nullable: sequence.is_none(), // Serial columns are implicitly NOT NULL
+            has_default: sequence.is_some(),
+            is_primary_key: false,
+            is_unique: false,
+            generated: None,
+            sequence,
+            comment: None,
+            domain: None,
+        };
+        column.apply_options(&col_def.options, namespace, table);
+        Ok(column)
+    }
+
+    /// Apply the options of a column definition (`NOT NULL`, `DEFAULT ...`,
+    /// `GENERATED ...`) to this column of the table `namespace.table`.
+    fn apply_options(&mut self, options: &[ColumnOptionDef], namespace: &str, table: &str) {
+        for option in options {
+            match &option.option {
+                ColumnOption::NotNull => self.nullable = false,
+                ColumnOption::Null => self.nullable = true,
+                ColumnOption::Default(_) => self.has_default = true,
+                ColumnOption::PrimaryKey(_) => {
+                    self.is_primary_key = true;
+                    self.nullable = false;
+                }
+                ColumnOption::Unique(_) => {
+                    self.is_unique = true;
+                }
+                ColumnOption::Generated {
+                    generated_as,
+                    generation_expr,
+                    ..
+                } => {
+                    self.has_default = true;
+                    self.generated = Some(match generation_expr {
+                        Some(expr) => Generated::Expression(expr.to_string()),
+                        None => {
+                            // Identity columns are implicitly NOT NULL
+                            self.nullable = false;
+                            self.sequence = Some(implicit_sequence(namespace, table, &self.name));
+                            Generated::Identity {
+                                always: *generated_as != GeneratedAs::ByDefault,
+                            }
+                        }
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Why this column can't be written to, if it can't.
+    ///
+    /// Generated columns only accept DEFAULT. So do `GENERATED ALWAYS`
+    /// identity columns, unless the INSERT says `OVERRIDING SYSTEM VALUE`.
+    pub fn write_restriction(&self, overriding_system_value: bool) -> Option<&'static str> {
+        match self.generated {
+            Some(Generated::Expression(_)) => Some("it is a generated column"),
+            Some(Generated::Identity { always: true }) if !overriding_system_value => {
+                Some("it is a GENERATED ALWAYS identity column")
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How a generated column gets its value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Generated {
+    /// `GENERATED ALWAYS AS (expr) STORED`, with the expression as SQL.
+    Expression(String),
+    /// `GENERATED { ALWAYS | BY DEFAULT } AS IDENTITY`.
+    Identity { always: bool },
+}
+
+/// A foreign key constraint (`FOREIGN KEY (user_id) REFERENCES users (id)`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForeignKey {
+    /// Constraint name, as declared or as Postgres would generate it.
+    pub name: String,
+    /// Referencing columns of this table.
+    pub columns: Vec<String>,
+    /// Qualified name of the referenced table (`public.users`).
+    pub referenced_table: String,
+    /// Referenced columns; the referenced table's primary key if none were listed.
+    pub referenced_columns: Vec<String>,
+    pub on_delete: ReferentialAction,
+    pub on_update: ReferentialAction,
+}
+
+/// What happens to referencing rows when the referenced row is deleted or updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReferentialAction {
+    NoAction,
+    Restrict,
+    Cascade,
+    SetNull,
+    SetDefault,
+}
+
+impl From<sqlparser::ast::ReferentialAction> for ReferentialAction {
+    fn from(action: sqlparser::ast::ReferentialAction) -> Self {
+        use sqlparser::ast::ReferentialAction as Action;
+        match action {
+            Action::NoAction => ReferentialAction::NoAction,
+            Action::Restrict => ReferentialAction::Restrict,
+            Action::Cascade => ReferentialAction::Cascade,
+            Action::SetNull => ReferentialAction::SetNull,
+            Action::SetDefault => ReferentialAction::SetDefault,
+        }
+    }
+}
+
+/// An index from CREATE [UNIQUE] INDEX.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Index {
+    /// Index name, in the table's namespace.
+    pub name: String,
+    /// Indexed columns and expressions, in order.
+    pub keys: Vec<IndexKey>,
+    pub unique: bool,
+    /// The WHERE clause of a partial index, as SQL.
+    pub predicate: Option<String>,
+    /// Access method (`btree`, `gin`, `gist`, ...).
+    pub method: String,
+    /// Non-key columns from `INCLUDE (...)`.
+    pub include: Vec<String>,
+}
+
+impl Index {
+    /// The column this index makes unique, if it is a non-partial unique
+    /// index over exactly one column.
+    pub fn unique_column(&self) -> Option<&str> {
+        match self.keys.as_slice() {
+            [IndexKey::Column(column)] if self.unique && self.predicate.is_none() => Some(column),
+            _ => None,
+        }
+    }
+
+    /// Check whether a column is one of this index's keys or INCLUDE columns.
+    ///
+    /// Columns only used inside expressions aren't tracked.
+    pub fn has_column(&self, column: &str) -> bool {
+        self.keys
+            .iter()
+            .any(|key| matches!(key, IndexKey::Column(c) if c.eq_ignore_ascii_case(column)))
+            || self.include.iter().any(|c| c.eq_ignore_ascii_case(column))
+    }
+}
+
+/// A single key of an index.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IndexKey {
+    Column(String),
+    /// An expression key (`lower(email)`), as SQL.
+    Expression(String),
+}
+
+/// A PRIMARY KEY or UNIQUE constraint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UniqueKey {
+    /// Constraint name, as declared or as Postgres would generate it.
+    pub name: String,
+    /// The columns whose values are unique together.
+    pub columns: Vec<String>,
+    /// Whether this is the primary key.
+    pub primary: bool,
+}
+
+/// A CHECK constraint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Check {
+    /// Constraint name, as declared or as Postgres would generate it.
+    pub name: String,
+    /// The checked expression, as SQL.
+    pub expression: String,
+    /// Columns the expression refers to.
+    pub columns: Vec<String>,
+    /// The value list of a `CHECK (col IN (...))` constraint.
+    pub allowed_values: Option<AllowedValues>,
+}
+
+/// The values a column is limited to by a CHECK constraint.
+///
+/// Both `status IN ('a', 'b')` and pg_dump's
+/// `status = ANY (ARRAY['a'::text, 'b'::text])` are recognized.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AllowedValues {
+    pub column: String,
+    pub values: Vec<String>,
+}
+
+/// Columns referenced by a CHECK expression, in order of appearance.
+fn expr_columns(expr: &Expr) -> Vec<String> {
+    fn collect(expr: &Expr, columns: &mut Vec<String>) {
+        match expr {
+            Expr::Identifier(ident)
+                if !columns.iter().any(|c| c.eq_ignore_ascii_case(&ident.value)) =>
+            {
+                columns.push(ident.value.clone());
+            }
+            Expr::BinaryOp { left, right, .. }
+            | Expr::AnyOp { left, right, .. }
+            | Expr::AllOp { left, right, .. } => {
+                collect(left, columns);
+                collect(right, columns);
+            }
+            Expr::Like { expr, pattern, .. }
+            | Expr::ILike { expr, pattern, .. }
+            | Expr::SimilarTo { expr, pattern, .. } => {
+                collect(expr, columns);
+                collect(pattern, columns);
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                collect(expr, columns);
+                collect(low, columns);
+                collect(high, columns);
+            }
+            Expr::InList { expr, list, .. } => {
+                collect(expr, columns);
+                list.iter().for_each(|e| collect(e, columns));
+            }
+            Expr::Array(array) => array.elem.iter().for_each(|e| collect(e, columns)),
+            Expr::Function(func) => {
+                if let FunctionArguments::List(list) = &func.args {
+                    for arg in &list.args {
+                        if let FunctionArg::Unnamed(FunctionArgExpr::Expr(e)) = arg {
+                            collect(e, columns);
+                        }
+                    }
+                }
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                else_result,
+                ..
+            } => {
+                operand.iter().for_each(|e| collect(e, columns));
+                for when in conditions {
+                    collect(&when.condition, columns);
+                    collect(&when.result, columns);
+                }
+                else_result.iter().for_each(|e| collect(e, columns));
+            }
+            Expr::Nested(inner)
+            | Expr::UnaryOp { expr: inner, .. }
+            | Expr::Cast { expr: inner, .. }
+            | Expr::IsNull(inner)
+            | Expr::IsNotNull(inner)
+            | Expr::IsTrue(inner)
+            | Expr::IsFalse(inner) => collect(inner, columns),
+            _ => {}
+        }
+    }
+
+    let mut columns = Vec::new();
+    collect(expr, &mut columns);
+    columns
+}
+
+/// Columns a CHECK expression requires to be non-null (`a IS NOT NULL AND ...`).
+fn not_null_columns(expr: &Expr) -> Vec<String> {
+    match expr {
+        Expr::IsNotNull(inner) => column_ref(inner).into_iter().collect(),
+        Expr::BinaryOp {
+            left,
+            op: BinaryOperator::And,
+            right,
+        } => {
+            let mut columns = not_null_columns(left);
+            columns.extend(not_null_columns(right));
+            columns
+        }
+        Expr::Nested(inner) => not_null_columns(inner),
+        _ => Vec::new(),
+    }
+}
+
+/// The value list of `col IN (...)` or `col = ANY (ARRAY[...])`.
+fn allowed_values(expr: &Expr) -> Option<AllowedValues> {
+    let (column, list) = match strip_parens(expr) {
+        Expr::InList {
+            expr,
+            list,
+            negated: false,
+        } => (column_ref(expr)?, list),
+        Expr::AnyOp {
+            left,
+            compare_op: BinaryOperator::Eq,
+            right,
+            ..
+        } => match strip_casts(right) {
+            Expr::Array(array) => (column_ref(left)?, &array.elem),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let values = list.iter().map(literal_value).collect::<Option<Vec<_>>>()?;
+    Some(AllowedValues { column, values })
+}
+
+/// The column an expression refers to, looking through parentheses and casts.
+fn column_ref(expr: &Expr) -> Option<String> {
+    match strip_casts(expr) {
+        Expr::Identifier(ident) => Some(ident.value.clone()),
+        _ => None,
+    }
+}
+
+/// The text of a string or number literal, looking through parentheses and casts.
+pub(crate) fn literal_value(expr: &Expr) -> Option<String> {
+    match strip_casts(expr) {
+        Expr::Value(value) => match &value.value {
+            Value::SingleQuotedString(s) => Some(s.clone()),
+            Value::Number(n, _) => Some(n.clone()),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Remove surrounding parentheses.
+fn strip_parens(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Nested(inner) => strip_parens(inner),
+        other => other,
+    }
+}
+
+/// Remove surrounding parentheses and casts (`('a'::text)`).
+pub(crate) fn strip_casts(expr: &Expr) -> &Expr {
+    match expr {
+        Expr::Nested(inner) | Expr::Cast { expr: inner, .. } => strip_casts(inner),
+        other => other,
+    }
+}
+
+/// Follow a column rename or drop in another table's foreign keys to `key`.
+fn update_references(
+    foreign_keys: &mut Vec<ForeignKey>,
+    key: &str,
+    operation: &AlterTableOperation,
+) {
+    match operation {
+        AlterTableOperation::RenameColumn {
+            old_column_name,
+            new_column_name,
+        } => {
+            for fk in foreign_keys.iter_mut() {
+                if fk.referenced_table == key {
+                    rename_in(
+                        &mut fk.referenced_columns,
+                        &old_column_name.value,
+                        &new_column_name.value,
+                    );
+                }
+            }
+        }
+        AlterTableOperation::DropColumn { column_names, .. } => {
+            foreign_keys.retain(|fk| {
+                fk.referenced_table != key
+                    || !fk.referenced_columns.iter().any(|c| {
+                        column_names
+                            .iter()
+                            .any(|dropped| c.eq_ignore_ascii_case(&dropped.value))
+                    })
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Rename every case-insensitive match of `from` in a list of column names.
+fn rename_in(names: &mut [String], from: &str, to: &str) {
+    for name in names.iter_mut() {
+        if name.eq_ignore_ascii_case(from) {
+            *name = to.to_string();
+        }
+    }
+}
+
+/// The JSON snapshot format: the schema tagged with its format version.
+#[derive(Serialize, Deserialize)]
+struct Snapshot<S> {
+    version: u32,
+    schema: S,
+}
+
+/// Serialize a map sorted by key, so snapshots don't depend on hash order.
+fn serialize_sorted<V: Serialize, S: serde::Serializer>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+/// A statement of a schema script.
+struct ParsedStatement {
+    /// The line the statement starts on.
+    line: u64,
+    /// Whether the statement creates, alters or drops a table, type or domain
+    /// (see [`affects_tables`]).
+    affects_tables: bool,
+    statement: std::result::Result<SchemaStatement, ParserError>,
+}
+
+/// Parse DDL into statements.
+///
+/// This is `Parser::parse_statements`, with the pg_dump-isms sqlparser rejects
+/// handled here: CREATE DOMAIN with a `NOT NULL` constraint, the trailing
+/// `WITH [NO] DATA` of CREATE MATERIALIZED VIEW, the sequence options of
+/// identity columns, and function and sequence DDL. A statement that can't be
+/// parsed doesn't stop the others: its error is recorded and parsing resumes
+/// after its `;`.
+fn parse_statements(sql: &str) -> std::result::Result<Vec<ParsedStatement>, ParserError> {
+    let dialect = PostgreSqlDialect {};
+    let tokens = Tokenizer::new(&dialect, sql).tokenize_with_location()?;
+    let mut parser = Parser::new(&dialect)
+        .with_tokens_with_locations(strip_index_only(strip_identity_options(tokens)));
+    let mut statements = Vec::new();
+
+    loop {
+        while parser.consume_token(&Token::SemiColon) {}
+        if parser.peek_token().token == Token::EOF {
+            break;
+        }
+
+        if at_unsupported_comment(&parser) || at_index_partition(&parser) {
+            skip_statement(&mut parser);
+            continue;
+        }
+
+        let line = parser.peek_token().span.start.line;
+        let affects_tables = affects_tables(&parser);
+        let statement = parser.try_parse(parse_schema_statement);
+        if statement.is_err() {
+            skip_statement(&mut parser);
+        }
+        statements.push(ParsedStatement {
+            line,
+            affects_tables,
+            statement,
+        });
+    }
+
+    Ok(statements)
+}
+
+/// Parse one statement, up to (not including) its `;`.
+fn parse_schema_statement(
+    parser: &mut Parser,
+) -> std::result::Result<SchemaStatement, ParserError> {
+    let statement = if parser.parse_keywords(&[Keyword::CREATE, Keyword::DOMAIN]) {
+        SchemaStatement::Sql(Box::new(parse_create_domain(parser)?))
+    } else if parser.parse_keywords(&[Keyword::CREATE, Keyword::FUNCTION]) {
+        SchemaStatement::CreateFunction(parse_create_function(parser, false)?)
+    } else if parser.parse_keywords(&[
+        Keyword::CREATE,
+        Keyword::OR,
+        Keyword::REPLACE,
+        Keyword::FUNCTION,
+    ]) {
+        SchemaStatement::CreateFunction(parse_create_function(parser, true)?)
+    } else if parser.parse_keywords(&[Keyword::ALTER, Keyword::FUNCTION]) {
+        parse_alter_function(parser)?
+    } else if [
+        &[Keyword::CREATE, Keyword::SEQUENCE][..],
+        &[Keyword::CREATE, Keyword::TEMP, Keyword::SEQUENCE],
+        &[Keyword::CREATE, Keyword::TEMPORARY, Keyword::SEQUENCE],
+        &[Keyword::CREATE, Keyword::UNLOGGED, Keyword::SEQUENCE],
+    ]
+    .iter()
+    .any(|keywords| parser.parse_keywords(keywords))
+    {
+        SchemaStatement::CreateSequence(parse_create_sequence(parser)?)
+    } else if parser.parse_keywords(&[Keyword::ALTER, Keyword::SEQUENCE]) {
+        parse_alter_sequence(parser)?
+    } else if let Some(statement) = parse_create_partition(parser)? {
+        statement
+    } else if let Some(statement) = parse_alter_partition(parser)? {
+        statement
+    } else {
+        SchemaStatement::Sql(Box::new(parser.parse_statement()?))
+    };
+    if matches!(&statement, SchemaStatement::Sql(statement) if matches!(statement.as_ref(), Statement::CreateView(view) if view.materialized))
+        && parser.parse_keyword(Keyword::WITH)
+    {
+        let _ = parser.parse_keyword(Keyword::NO);
+        parser.expect_keyword_is(Keyword::DATA)?;
+    }
+
+    let next = parser.peek_token();
+    if !matches!(next.token, Token::SemiColon | Token::EOF) {
+        return parser.expected("end of statement", next);
+    }
+    Ok(statement)
+}
+
+/// Whether the parser is at a CREATE, ALTER or DROP of a table, type or
+/// domain, other than an ALTER that only changes what queries don't depend on.
+fn affects_tables(parser: &Parser) -> bool {
+    let tokens = parser.peek_tokens::<5>();
+    let mut words = tokens.iter().map_while(|token| match token {
+        Token::Word(word) => Some(word.keyword),
+        _ => None,
+    });
+    if !matches!(
+        words.next(),
+        Some(Keyword::CREATE | Keyword::ALTER | Keyword::DROP)
+    ) {
+        return false;
+    }
+    // Skip `OR REPLACE`, `TEMPORARY`, `UNLOGGED`, ...
+    words
+        .find(|keyword| {
+            !matches!(
+                keyword,
+                Keyword::OR
+                    | Keyword::REPLACE
+                    | Keyword::GLOBAL
+                    | Keyword::LOCAL
+                    | Keyword::TEMP
+                    | Keyword::TEMPORARY
+                    | Keyword::UNLOGGED
+            )
+        })
+        .is_some_and(|keyword| matches!(keyword, Keyword::TABLE | Keyword::TYPE | Keyword::DOMAIN))
+        && !only_storage_actions(parser)
+}
+
+/// Whether the parser is at an ALTER statement whose every action is one
+/// pg_dump writes that doesn't change the shape of a table or type:
+/// `OWNER TO`, `CLUSTER ON`, `SET STATISTICS`, `SET STORAGE` or
+/// `REPLICA IDENTITY`.
+fn only_storage_actions(parser: &Parser) -> bool {
+    if !matches!(&parser.peek_token_ref().token, Token::Word(word) if word.keyword == Keyword::ALTER)
+    {
+        return false;
+    }
+
+    let mut actions = 1;
+    let mut storage_actions = 0;
+    let mut depth = 0;
+    let mut previous = Keyword::NoKeyword;
+    for n in 0.. {
+        let keyword = match &parser.peek_nth_token_ref(n).token {
+            Token::SemiColon | Token::EOF => break,
+            Token::LParen => {
+                depth += 1;
+                Keyword::NoKeyword
+            }
+            Token::RParen => {
+                depth -= 1;
+                Keyword::NoKeyword
+            }
+            Token::Comma if depth == 0 => {
+                actions += 1;
+                Keyword::NoKeyword
+            }
+            Token::Word(word) => word.keyword,
+            _ => Keyword::NoKeyword,
+        };
+        if matches!(
+            (previous, keyword),
+            (Keyword::OWNER, Keyword::TO)
+                | (Keyword::CLUSTER, Keyword::ON)
+                | (Keyword::SET, Keyword::STATISTICS | Keyword::STORAGE)
+                | (Keyword::REPLICA, Keyword::IDENTITY)
+        ) {
+            storage_actions += 1;
+        }
+        previous = keyword;
+    }
+    storage_actions == actions
+}
+
+/// Blank out psql meta-command lines (`\restrict ...`), warning about each.
+///
+/// Lines are kept, so parse errors still point at the right line.
+fn skip_meta_commands(sql: &str, warnings: &mut Vec<SchemaWarning>) -> String {
+    let mut result = String::with_capacity(sql.len());
+    for (idx, line) in sql.lines().enumerate() {
+        match meta_command(line) {
+            Some((command @ ("i" | "include" | "ir" | "include_relative"), argument)) => {
+                warnings.push(SchemaWarning {
+                    file: None,
+                    line: idx as u64 + 1,
+                    message: format!(
+                        "Skipped psql include \\{} {} (load the file with Schema::from_file_tolerant to follow it)",
+                        command, argument
+                    ),
+                })
+            }
+            Some((command, _)) => warnings.push(SchemaWarning {
+                file: None,
+                line: idx as u64 + 1,
+                message: format!("Skipped psql meta-command \\{}", command),
+            }),
+            None => result.push_str(line),
+        }
+        result.push('\n');
+    }
+    result
+}
+
+/// Remove the `ONLY` of `CREATE INDEX ... ON ONLY table`.
+///
+/// pg_dump creates the indexes of partitioned tables on the parent only and
+/// then on each partition, which sqlparser can't parse. Either way the
+/// index belongs to the named table.
+fn strip_index_only(tokens: Vec<TokenWithSpan>) -> Vec<TokenWithSpan> {
+    let mut result: Vec<TokenWithSpan> = Vec::with_capacity(tokens.len());
+    let mut in_create_index = false;
+
+    for token in tokens {
+        let keyword = match &token.token {
+            Token::Word(word) => Some(word.keyword),
+            _ => None,
+        };
+        let previous = result
+            .iter()
+            .rev()
+            .find(|t| !matches!(t.token, Token::Whitespace(_)))
+            .and_then(|t| match &t.token {
+                Token::Word(word) => Some(word.keyword),
+                _ => None,
+            });
+        match keyword {
+            Some(Keyword::INDEX) if matches!(previous, Some(Keyword::CREATE | Keyword::UNIQUE)) => {
+                in_create_index = true
+            }
+            Some(Keyword::ONLY) if in_create_index && previous == Some(Keyword::ON) => continue,
+            _ if token.token == Token::SemiColon => in_create_index = false,
+            _ => {}
+        }
+        result.push(token);
+    }
+
+    result
+}
+
+/// Remove the sequence options of identity columns
+/// (`GENERATED {ALWAYS | BY DEFAULT} AS IDENTITY (...)`).
+///
+/// pg_dump spells out the sequence behind every identity column
+/// (`ADD GENERATED ALWAYS AS IDENTITY (SEQUENCE NAME public.t_id_seq START WITH 1 ...)`),
+/// which sqlparser can't parse. The sequence is assumed to have its default
+/// `<table>_<column>_seq` name; the other options don't affect query checking.
+fn strip_identity_options(tokens: Vec<TokenWithSpan>) -> Vec<TokenWithSpan> {
+    let mut result: Vec<TokenWithSpan> = Vec::with_capacity(tokens.len());
+    let mut depth = 0;
+
+    for token in tokens {
+        if depth > 0 {
+            match token.token {
+                Token::LParen => depth += 1,
+                Token::RParen => depth -= 1,
+                _ => {}
+            }
+            continue;
+        }
+
+        if token.token == Token::LParen && ends_with_generated_identity(&result) {
+            depth = 1;
+            continue;
+        }
+        result.push(token);
+    }
+
+    result
+}
+
+/// Whether the tokens end with `GENERATED {ALWAYS | BY DEFAULT} AS IDENTITY`.
+fn ends_with_generated_identity(tokens: &[TokenWithSpan]) -> bool {
+    let mut keywords = tokens
+        .iter()
+        .rev()
+        .filter(|t| !matches!(t.token, Token::Whitespace(_)))
+        .map(|t| match &t.token {
+            Token::Word(w) if w.quote_style.is_none() => Some(w.keyword),
+            _ => None,
+        });
+    let mut next = || keywords.next().flatten();
+    next() == Some(Keyword::IDENTITY)
+        && next() == Some(Keyword::AS)
+        && match next() {
+            Some(Keyword::ALWAYS) => true,
+            Some(Keyword::DEFAULT) => next() == Some(Keyword::BY),
+            _ => false,
+        }
+        && next() == Some(Keyword::GENERATED)
+}
+
+/// Parse `CREATE TABLE [IF NOT EXISTS] name PARTITION OF parent [(...)] bound
+/// [PARTITION BY ...]`, if that's what comes next; sqlparser doesn't support it.
+///
+/// The partition becomes a CREATE TABLE inheriting from its parent. Its
+/// optional `(...)` can only add constraints and options to the parent's
+/// columns, so those columns have no type (`DataType::Unspecified`).
+fn parse_create_partition(
+    parser: &mut Parser,
+) -> std::result::Result<Option<SchemaStatement>, ParserError> {
+    let Some((if_not_exists, name, parent)) = parser.maybe_parse(|parser| {
+        parser.expect_keywords(&[Keyword::CREATE, Keyword::TABLE])?;
+        let if_not_exists = parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = parser.parse_object_name(false)?;
+        parser.expect_keywords(&[Keyword::PARTITION, Keyword::OF])?;
+        Ok((if_not_exists, name, parser.parse_object_name(false)?))
+    })?
+    else {
+        return Ok(None);
+    };
+
+    let mut columns = Vec::new();
+    let mut constraints = Vec::new();
+    if parser.consume_token(&Token::LParen) {
+        loop {
+            if let Some(constraint) = parser.parse_optional_table_constraint()? {
+                constraints.push(constraint);
+            } else {
+                let name = parser.parse_identifier()?;
+                let _ = parser.parse_keywords(&[Keyword::WITH, Keyword::OPTIONS]);
+                let mut options = Vec::new();
+                loop {
+                    let constraint_name = if parser.parse_keyword(Keyword::CONSTRAINT) {
+                        Some(parser.parse_identifier()?)
+                    } else {
+                        None
+                    };
+                    match parser.parse_optional_column_option()? {
+                        Some(option) => options.push(ColumnOptionDef {
+                            name: constraint_name,
+                            option,
+                        }),
+                        None if constraint_name.is_some() => {
+                            return parser.expected(
+                                "constraint details after CONSTRAINT <name>",
+                                parser.peek_token(),
+                            )
+                        }
+                        None => break,
+                    }
+                }
+                columns.push(ColumnDef {
+                    name,
+                    data_type: DataType::Unspecified,
+                    options,
+                });
+            }
+            if !parser.consume_token(&Token::Comma) {
+                parser.expect_token(&Token::RParen)?;
+                break;
+            }
+        }
+    }
+
+    let bound = parse_partition_bound(parser)?;
+    let partition_by = if parser.parse_keywords(&[Keyword::PARTITION, Keyword::BY]) {
+        Some(Box::new(parser.parse_expr()?))
+    } else {
+        None
+    };
+
+    let Statement::CreateTable(create) = CreateTableBuilder::new(name)
+        .if_not_exists(if_not_exists)
+        .columns(columns)
+        .constraints(constraints)
+        .inherits(Some(vec![parent]))
+        .partition_by(partition_by)
+        .build()
+    else {
+        unreachable!("CreateTableBuilder builds a CREATE TABLE");
+    };
+    Ok(Some(SchemaStatement::CreatePartition {
+        create: Box::new(create),
+        bound,
+    }))
+}
+
+/// Parse `ALTER TABLE [IF EXISTS] [ONLY] parent { ATTACH | DETACH } PARTITION ...`,
+/// if that's what comes next; sqlparser only knows ClickHouse's ATTACH PARTITION.
+fn parse_alter_partition(
+    parser: &mut Parser,
+) -> std::result::Result<Option<SchemaStatement>, ParserError> {
+    let Some((parent, if_exists, attach)) = parser.maybe_parse(|parser| {
+        parser.expect_keywords(&[Keyword::ALTER, Keyword::TABLE])?;
+        let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let _ = parser.parse_keyword(Keyword::ONLY);
+        let parent = parser.parse_object_name(false)?;
+        let attach = parser.parse_keyword(Keyword::ATTACH);
+        if !attach {
+            parser.expect_keyword_is(Keyword::DETACH)?;
+        }
+        parser.expect_keyword_is(Keyword::PARTITION)?;
+        Ok((parent, if_exists, attach))
+    })?
+    else {
+        return Ok(None);
+    };
+
+    let partition = parser.parse_object_name(false)?;
+    if attach {
+        let bound = parse_partition_bound(parser)?;
+        return Ok(Some(SchemaStatement::AttachPartition {
+            parent,
+            if_exists,
+            partition,
+            bound,
+        }));
+    }
+    if !parser.parse_keyword(Keyword::CONCURRENTLY) {
+        // FINALIZE isn't a keyword
+        if matches!(&parser.peek_token().token, Token::Word(word) if word.value.eq_ignore_ascii_case("finalize"))
+        {
+            parser.next_token();
+        }
+    }
+    Ok(Some(SchemaStatement::DetachPartition {
+        parent,
+        if_exists,
+        partition,
+    }))
+}
+
+/// Parse a partition bound, normalized to SQL: `DEFAULT`, `FOR VALUES IN (...)`,
+/// `FOR VALUES FROM (...) TO (...)` or `FOR VALUES WITH (MODULUS m, REMAINDER r)`.
+fn parse_partition_bound(parser: &mut Parser) -> std::result::Result<String, ParserError> {
+    if parser.parse_keyword(Keyword::DEFAULT) {
+        return Ok("DEFAULT".to_string());
+    }
+    parser.expect_keywords(&[Keyword::FOR, Keyword::VALUES])?;
+
+    let values = |parser: &mut Parser| -> std::result::Result<String, ParserError> {
+        parser.expect_token(&Token::LParen)?;
+        let values = parser.parse_comma_separated(Parser::parse_expr)?;
+        parser.expect_token(&Token::RParen)?;
+        Ok(values
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", "))
+    };
+    if parser.parse_keyword(Keyword::IN) {
+        Ok(format!("FOR VALUES IN ({})", values(parser)?))
+    } else if parser.parse_keyword(Keyword::FROM) {
+        let from = values(parser)?;
+        parser.expect_keyword_is(Keyword::TO)?;
+        Ok(format!(
+            "FOR VALUES FROM ({}) TO ({})",
+            from,
+            values(parser)?
+        ))
+    } else {
+        parser.expect_keyword_is(Keyword::WITH)?;
+        parser.expect_token(&Token::LParen)?;
+        // MODULUS m, REMAINDER r
+        let options = parser.parse_comma_separated(|parser| {
+            let name = parser.parse_identifier()?;
+            Ok(format!(
+                "{} {}",
+                name.value.to_uppercase(),
+                parser.parse_expr()?
+            ))
+        })?;
+        parser.expect_token(&Token::RParen)?;
+        Ok(format!("FOR VALUES WITH ({})", options.join(", ")))
+    }
+}
+
+/// Parse the rest of `CREATE DOMAIN name AS type [COLLATE c] [DEFAULT expr] [constraint ...]`.
+///
+/// Domain constraints share the column option grammar. `NOT NULL` is kept as
+/// the equivalent `CHECK (VALUE IS NOT NULL)` and a bare `NULL` is dropped.
+fn parse_create_domain(parser: &mut Parser) -> std::result::Result<Statement, ParserError> {
+    let name = parser.parse_object_name(false)?;
+    parser.expect_keyword_is(Keyword::AS)?;
+    let data_type = parser.parse_data_type()?;
+    let collation = if parser.parse_keyword(Keyword::COLLATE) {
+        Some(parser.parse_identifier()?)
+    } else {
+        None
+    };
+
+    let mut default = None;
+    let mut constraints = Vec::new();
+    loop {
+        let constraint_name = if parser.parse_keyword(Keyword::CONSTRAINT) {
+            Some(parser.parse_identifier()?)
+        } else {
+            None
+        };
+        let Some(option) = parser.parse_optional_column_option()? else {
+            if constraint_name.is_some() {
+                return parser.expected("domain constraint", parser.peek_token());
+            }
+            break;
+        };
+
+        match option {
+            ColumnOption::Default(expr) => default = Some(expr),
+            ColumnOption::Null => {}
+            ColumnOption::NotNull => {
+                let value = Expr::Identifier(Ident::new("VALUE"));
+                constraints.push(TableConstraint::Check(CheckConstraint {
+                    name: constraint_name,
+                    expr: Box::new(Expr::IsNotNull(Box::new(value))),
+                    enforced: None,
+                }));
+            }
+            ColumnOption::Check(check) => {
+                constraints.push(TableConstraint::Check(CheckConstraint {
+                    name: constraint_name.or(check.name),
+                    ..check
+                }))
+            }
+            other => {
+                return Err(ParserError::ParserError(format!(
+                    "Unsupported domain constraint: {}",
+                    other
+                )))
+            }
+        }
+    }
+
+    Ok(Statement::CreateDomain(CreateDomain {
+        name,
+        data_type,
+        collation,
+        default,
+        constraints,
+    }))
+}
+
+/// Whether an expression is the `VALUE` placeholder of a domain constraint.
+fn is_domain_value(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(ident) if ident.value.eq_ignore_ascii_case("value"))
+}
+
+/// A statement of a schema script.
+///
+/// Function and sequence DDL is parsed by hand, since sqlparser can't parse
+/// what pg_dump writes for them (`RETURNS SETOF`, `SECURITY DEFINER`,
+/// `ALTER FUNCTION`, `ALTER SEQUENCE`, ...).
+enum SchemaStatement {
+    Sql(Box<Statement>),
+    CreateFunction(CreateFunction),
+    AlterFunction {
+        name: ObjectName,
+        args: Option<Vec<FunctionParam>>,
+        rename: Option<Ident>,
+    },
+    CreateSequence(CreateSequence),
+    AlterSequence {
+        name: ObjectName,
+        if_exists: bool,
+        options: SequenceOptions,
+        rename: Option<Ident>,
+    },
+    /// `CREATE TABLE ... PARTITION OF parent`, as a CREATE TABLE inheriting
+    /// from the parent, and the partition bound.
+    CreatePartition {
+        create: Box<CreateTable>,
+        bound: String,
+    },
+    /// `ALTER TABLE parent ATTACH PARTITION partition bound`
+    AttachPartition {
+        parent: ObjectName,
+        if_exists: bool,
+        partition: ObjectName,
+        bound: String,
+    },
+    /// `ALTER TABLE parent DETACH PARTITION partition`
+    DetachPartition {
+        parent: ObjectName,
+        if_exists: bool,
+        partition: ObjectName,
+    },
+}
+
+/// A parsed `CREATE SEQUENCE`.
+struct CreateSequence {
+    name: ObjectName,
+    if_not_exists: bool,
+    options: SequenceOptions,
+}
+
+/// The sequence options we model; START, INCREMENT, CACHE etc. are skipped.
+#[derive(Default)]
+struct SequenceOptions {
+    /// `AS type`
+    data_type: Option<DataType>,
+    /// `OWNED BY table.column`, or `Some(None)` for `OWNED BY NONE`
+    owned_by: Option<Option<ObjectName>>,
+}
+
+/// A parsed `CREATE FUNCTION` signature. The body isn't kept.
+struct CreateFunction {
+    or_replace: bool,
+    name: ObjectName,
+    args: Vec<FunctionParam>,
+    /// `RETURNS SETOF type`
+    setof: bool,
+    /// `DataType::Table` for `RETURNS TABLE (...)`; `None` when only OUT arguments describe the result.
+    return_type: Option<DataType>,
+    strict: bool,
+}
+
+/// An argument in a function signature: `[mode] [name] type [DEFAULT expr]`.
+struct FunctionParam {
+    mode: Option<ArgMode>,
+    variadic: bool,
+    name: Option<Ident>,
+    data_type: DataType,
+    has_default: bool,
+}
+
+/// Parse the rest of `CREATE [OR REPLACE] FUNCTION name (args) [RETURNS ...] options...`.
+///
+/// Only the signature is kept; the options and body are skipped over,
+/// including `BEGIN ATOMIC ... END` bodies.
+fn parse_create_function(
+    parser: &mut Parser,
+    or_replace: bool,
+) -> std::result::Result<CreateFunction, ParserError> {
+    let name = parser.parse_object_name(false)?;
+    let args = parse_function_params(parser)?;
+
+    let mut setof = false;
+    let mut return_type = None;
+    if parser.parse_keyword(Keyword::RETURNS) {
+        setof = parse_word(parser, "SETOF");
+        return_type = Some(parser.parse_data_type()?);
+    }
+
+    let mut strict = false;
+    loop {
+        if parser.parse_keyword(Keyword::AS) {
+            parse_function_body_string(parser)?;
+            if parser.consume_token(&Token::Comma) {
+                parse_function_body_string(parser)?;
+            }
+        } else if parser.parse_keyword(Keyword::LANGUAGE) {
+            // A name like `sql` (itself a keyword) or a string
+            parser.next_token();
+        } else if parser.parse_keyword(Keyword::STRICT)
+            || parser.parse_keywords(&[
+                Keyword::RETURNS,
+                Keyword::NULL,
+                Keyword::ON,
+                Keyword::NULL,
+                Keyword::INPUT,
+            ])
+        {
+            strict = true;
+        } else if parser.parse_keywords(&[
+            Keyword::CALLED,
+            Keyword::ON,
+            Keyword::NULL,
+            Keyword::INPUT,
+        ]) {
+            strict = false;
+        } else if parser.parse_keyword(Keyword::SECURITY) {
+            if !parse_word(parser, "DEFINER") && !parse_word(parser, "INVOKER") {
+                return parser.expected("DEFINER or INVOKER", parser.peek_token());
+            }
+        } else if parser.parse_keyword(Keyword::PARALLEL)
+            || parser.parse_keyword(Keyword::ROWS)
+            || parse_word(parser, "COST")
+            || parse_word(parser, "SUPPORT")
+        {
+            parser.next_token();
+        } else if parser.parse_keyword(Keyword::SET) {
+            // SET param { TO | = } value [, ...] | SET param FROM CURRENT
+            parser.parse_identifier()?;
+            if parser.parse_keywords(&[Keyword::FROM, Keyword::CURRENT]) {
+                continue;
+            }
+            if !parser.parse_keyword(Keyword::TO) {
+                parser.expect_token(&Token::Eq)?;
+            }
+            loop {
+                parser.next_token();
+                if !parser.consume_token(&Token::Comma) {
+                    break;
+                }
+            }
+        } else if parser.parse_keyword(Keyword::RETURN) {
+            parser.parse_expr()?;
+        } else if parser.parse_keywords(&[Keyword::BEGIN, Keyword::ATOMIC]) {
+            skip_atomic_body(parser)?;
+        } else if parser.parse_keyword(Keyword::IMMUTABLE)
+            || parser.parse_keyword(Keyword::STABLE)
+            || parser.parse_keyword(Keyword::VOLATILE)
+            || parser.parse_keyword(Keyword::EXTERNAL)
+            || parser.parse_keyword(Keyword::WINDOW)
+            || parser.parse_keyword(Keyword::LEAKPROOF)
+            || parser.parse_keywords(&[Keyword::NOT, Keyword::LEAKPROOF])
+        {
+        } else {
+            break;
+        }
+    }
+
+    Ok(CreateFunction {
+        or_replace,
+        name,
+        args,
+        setof,
+        return_type,
+        strict,
+    })
+}
+
+/// Parse the rest of `ALTER FUNCTION name [(args)] ...`.
+///
+/// Only `RENAME TO` changes anything we model; owners, options and the like are skipped.
+fn parse_alter_function(parser: &mut Parser) -> std::result::Result<SchemaStatement, ParserError> {
+    let name = parser.parse_object_name(false)?;
+    let args = if parser.peek_token().token == Token::LParen {
+        Some(parse_function_params(parser)?)
+    } else {
+        None
+    };
+
+    let rename = if parser.parse_keywords(&[Keyword::RENAME, Keyword::TO]) {
+        Some(parser.parse_identifier()?)
+    } else {
+        skip_statement(parser);
+        None
+    };
+
+    Ok(SchemaStatement::AlterFunction { name, args, rename })
+}
+
+/// Parse the rest of `CREATE [TEMP | UNLOGGED] SEQUENCE [IF NOT EXISTS] name options...`.
+fn parse_create_sequence(parser: &mut Parser) -> std::result::Result<CreateSequence, ParserError> {
+    let if_not_exists = parser.parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+    let name = parser.parse_object_name(false)?;
+    let options = parse_sequence_options(parser)?;
+
+    Ok(CreateSequence {
+        name,
+        if_not_exists,
+        options,
+    })
+}
+
+/// Parse the rest of `ALTER SEQUENCE [IF EXISTS] name ...`.
+///
+/// Renames and the AS and OWNED BY options change what we model; owners,
+/// schemas and the like are skipped.
+fn parse_alter_sequence(parser: &mut Parser) -> std::result::Result<SchemaStatement, ParserError> {
+    let if_exists = parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+    let name = parser.parse_object_name(false)?;
+    let rename = if parser.parse_keywords(&[Keyword::RENAME, Keyword::TO]) {
+        Some(parser.parse_identifier()?)
+    } else {
+        None
+    };
+    let options = parse_sequence_options(parser)?;
+    skip_statement(parser);
+
+    Ok(SchemaStatement::AlterSequence {
+        name,
+        if_exists,
+        options,
+        rename,
+    })
+}
+
+/// Parse sequence options, which pg_dump writes in a different order than sqlparser expects.
+fn parse_sequence_options(
+    parser: &mut Parser,
+) -> std::result::Result<SequenceOptions, ParserError> {
+    let mut options = SequenceOptions::default();
+    loop {
+        if parser.parse_keyword(Keyword::AS) {
+            options.data_type = Some(parser.parse_data_type()?);
+        } else if parser.parse_keywords(&[Keyword::OWNED, Keyword::BY]) {
+            options.owned_by = Some(if parser.parse_keyword(Keyword::NONE) {
+                None
+            } else {
+                Some(parser.parse_object_name(false)?)
+            });
+        } else if parser.parse_keyword(Keyword::NO) {
+            parser.expect_one_of_keywords(&[
+                Keyword::MINVALUE,
+                Keyword::MAXVALUE,
+                Keyword::CYCLE,
+            ])?;
+        } else if parser
+            .parse_one_of_keywords(&[
+                Keyword::INCREMENT,
+                Keyword::START,
+                Keyword::RESTART,
+                Keyword::MINVALUE,
+                Keyword::MAXVALUE,
+                Keyword::CACHE,
+            ])
+            .is_some()
+        {
+            let _ = parser.parse_keyword(Keyword::BY) || parser.parse_keyword(Keyword::WITH);
+            let _ = parser.consume_token(&Token::Minus);
+            if matches!(parser.peek_token().token, Token::Number(..)) {
+                parser.next_token();
+            }
+        } else if !parser.parse_keyword(Keyword::CYCLE) {
+            break;
+        }
+    }
+
+    Ok(options)
+}
+
+/// Whether the parser is at a `COMMENT ON` sqlparser can't parse.
+///
+/// pg_dump also comments on functions, types, indexes, views, ...; only table
+/// and column comments are kept, so the others are skipped.
+fn at_unsupported_comment(parser: &Parser) -> bool {
+    match parser.peek_tokens::<3>() {
+        [Token::Word(comment), Token::Word(on), Token::Word(object)] => {
+            comment.keyword == Keyword::COMMENT
+                && on.keyword == Keyword::ON
+                && !matches!(
+                    object.keyword,
+                    Keyword::TABLE
+                        | Keyword::COLUMN
+                        | Keyword::EXTENSION
+                        | Keyword::SCHEMA
+                        | Keyword::DATABASE
+                        | Keyword::USER
+                        | Keyword::ROLE
+                )
+        }
+        _ => false,
+    }
+}
+
+/// Whether the parser is at `ALTER INDEX ... ATTACH PARTITION`.
+///
+/// pg_dump attaches the indexes of partitions to those of their parent with
+/// it; partition indexes are already created on the partitions themselves.
+fn at_index_partition(parser: &Parser) -> bool {
+    let tokens = parser.peek_tokens::<7>();
+    let is =
+        |idx: usize, keyword| matches!(&tokens[idx], Token::Word(word) if word.keyword == keyword);
+    is(0, Keyword::ALTER)
+        && is(1, Keyword::INDEX)
+        && ((is(3, Keyword::ATTACH) && is(4, Keyword::PARTITION))
+            // A qualified index name
+            || (tokens[3] == Token::Period && is(5, Keyword::ATTACH) && is(6, Keyword::PARTITION)))
+}
+
+/// Skip to the end of the current statement.
+fn skip_statement(parser: &mut Parser) {
+    while !matches!(parser.peek_token().token, Token::SemiColon | Token::EOF) {
+        parser.next_token();
+    }
+}
+
+/// Parse a parenthesized list of function arguments.
+fn parse_function_params(
+    parser: &mut Parser,
+) -> std::result::Result<Vec<FunctionParam>, ParserError> {
+    parser.expect_token(&Token::LParen)?;
+    if parser.consume_token(&Token::RParen) {
+        return Ok(Vec::new());
+    }
+    let params = parser.parse_comma_separated(parse_function_param)?;
+    parser.expect_token(&Token::RParen)?;
+    Ok(params)
+}
+
+/// Parse `[IN | OUT | INOUT | VARIADIC] [name] type [{DEFAULT | =} expr]`.
+fn parse_function_param(parser: &mut Parser) -> std::result::Result<FunctionParam, ParserError> {
+    let mut variadic = false;
+    let mode = if parser.parse_keyword(Keyword::IN) {
+        Some(ArgMode::In)
+    } else if parser.parse_keyword(Keyword::OUT) {
+        Some(ArgMode::Out)
+    } else if parser.parse_keyword(Keyword::INOUT) {
+        Some(ArgMode::InOut)
+    } else {
+        variadic = parse_word(parser, "VARIADIC");
+        None
+    };
+
+    // The name is optional, so `a integer` and `double precision` both start
+    // with a word: the first word is a name only if a type follows it
+    let mut name = None;
+    let mut data_type = parser.parse_data_type()?;
+    if let Token::Word(word) = &parser.token_at(parser.get_current_index()).token {
+        let word = Ident::new(word.value.clone());
+        let next_type = parser.maybe_parse(|parser| {
+            if parser.peek_keyword(Keyword::DEFAULT) {
+                parser.expected("a type", parser.peek_token())
+            } else {
+                parser.parse_data_type()
+            }
+        })?;
+        if let Some(next_type) = next_type {
+            name = Some(word);
+            data_type = next_type;
+        }
+    }
+
+    let has_default = if parser.parse_keyword(Keyword::DEFAULT) || parser.consume_token(&Token::Eq)
+    {
+        parser.parse_expr()?;
+        true
+    } else {
+        false
+    };
+
+    Ok(FunctionParam {
+        mode,
+        variadic,
+        name,
+        data_type,
+        has_default,
+    })
+}
+
+/// Consume a non-keyword word like `SETOF`, if it's next.
+fn parse_word(parser: &mut Parser, expected: &str) -> bool {
+    match parser.peek_token().token {
+        Token::Word(word) if word.value.eq_ignore_ascii_case(expected) => {
+            parser.next_token();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Consume a quoted or dollar-quoted function body.
+fn parse_function_body_string(parser: &mut Parser) -> std::result::Result<(), ParserError> {
+    let token = parser.next_token();
+    match token.token {
+        Token::SingleQuotedString(_)
+        | Token::EscapedStringLiteral(_)
+        | Token::DollarQuotedString(_) => Ok(()),
+        _ => parser.expected("function body string", token),
+    }
+}
+
+/// Skip a `BEGIN ATOMIC ... END` body, which holds `;`-terminated statements.
+fn skip_atomic_body(parser: &mut Parser) -> std::result::Result<(), ParserError> {
+    let mut depth = 1;
+    loop {
+        let token = parser.next_token();
+        match &token.token {
+            Token::Word(word) if word.keyword == Keyword::CASE => depth += 1,
+            Token::Word(word) if word.keyword == Keyword::END => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(());
+                }
+            }
+            Token::EOF => return parser.expected("END", token),
+            _ => {}
+        }
+    }
+}
+
+/// Find a qualified key in a map, ignoring case.
+fn find_key<V>(map: &HashMap<String, V>, key: &str) -> Option<String> {
+    if map.contains_key(key) {
+        return Some(key.to_string());
+    }
+    map.keys().find(|k| k.eq_ignore_ascii_case(key)).cloned()
+}
+
+/// Error if `map` already has an object keyed `key`: Postgres rejects a
+/// second CREATE of the same object (without `OR REPLACE`/`IF NOT EXISTS`).
+fn ensure_undefined<V>(map: &HashMap<String, V>, kind: &'static str, key: &str) -> Result<()> {
+    match find_key(map, key) {
+        Some(name) => Err(Error::DuplicateDefinition { kind, name }),
+        None => Ok(()),
+    }
+}
+
+/// Build the key of an object in a namespace.
+///
+/// Parts containing a dot are quoted (`"my.schema".t`), so keys split back
+/// into the same parts.
+fn qualify(namespace: &str, name: &str) -> String {
+    format!("{}.{}", quote_name_part(namespace), quote_name_part(name))
+}
+
+/// The name Postgres gives an index key in a generated index name: the
+/// column, the function of a function call, or else `expr`.
+fn index_column_name(expr: &Expr) -> String {
+    match expr {
+        Expr::Identifier(ident) => ident.value.clone(),
+        Expr::CompoundIdentifier(idents) => idents
+            .last()
+            .map_or_else(|| "expr".to_string(), |ident| ident.value.clone()),
+        Expr::Function(function) => {
+            unqualified_name(&object_name_to_string(&function.name)).to_lowercase()
+        }
+        Expr::Cast { expr, .. } | Expr::Nested(expr) => index_column_name(expr),
+        _ => "expr".to_string(),
+    }
+}
+
+/// Find the key of an object in `map`, looking an unqualified name up in the
+/// namespaces of `search_path` in order.
+fn lookup_key_in<V>(
+    map: &HashMap<String, V>,
+    name: &str,
+    search_path: &[String],
+) -> Option<String> {
+    match split_qualified(name) {
+        (Some(namespace), name) => find_key(map, &qualify(namespace, name)),
+        (None, name) => search_path
+            .iter()
+            .find_map(|namespace| find_key(map, &qualify(namespace, name))),
+    }
+}
+
+/// The name a type is overridden by: its name without length modifiers.
+fn override_key(data_type: &PostgresType) -> String {
+    match data_type {
+        PostgresType::Varchar(_) => PostgresType::Varchar(None).to_string(),
+        PostgresType::Char(_) => PostgresType::Char(None).to_string(),
+        PostgresType::Array(element) => format!("{}[]", override_key(element)),
+        other => other.to_string(),
+    }
+}
+
+/// Split `namespace.name` (or `database.namespace.name`) into its namespace and name.
+///
+/// Dots inside quoted parts (`"my.schema".t`) don't split.
+fn split_qualified(name: &str) -> (Option<&str>, &str) {
+    match split_qualified_name(name) {
+        (Some(qualifier), name) => (Some(unqualified_name(qualifier)), name),
+        (None, name) => (None, name),
+    }
+}
+
+/// Parse the values of `SET search_path = ...`.
+///
+/// Accepts identifiers (`public, billing`) as well as a quoted list
+/// (`'billing, public'`); `DEFAULT` restores the default path.
+fn search_path_from_values(values: &[Expr]) -> Vec<String> {
+    let mut path = Vec::new();
+    for value in values {
+        match value {
+            Expr::Identifier(ident)
+                if ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case("default") =>
+            {
+                return vec![DEFAULT_NAMESPACE.to_string()];
+            }
+            Expr::Identifier(ident) => path.push(ident.value.clone()),
+            Expr::Value(v) => {
+                if let Value::SingleQuotedString(list) = &v.value {
+                    path.extend(parse_search_path_list(list));
+                }
+            }
+            _ => {}
+        }
+    }
+    path
+}
+
+/// Split a textual search path (`"$user", public`) into namespaces.
+fn parse_search_path_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|part| part.trim().trim_matches('"'))
+        .filter(|part| !part.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Extract the path from `SELECT pg_catalog.set_config('search_path', '...', ...)`.
+fn set_config_search_path(query: &Query) -> Option<Vec<String>> {
+    let SetExpr::Select(select) = query.body.as_ref() else {
+        return None;
+    };
+    let [SelectItem::UnnamedExpr(Expr::Function(function))] = select.projection.as_slice() else {
+        return None;
+    };
+    let is_set_config = function
+        .name
+        .0
+        .last()
+        .and_then(|part| part.as_ident())
+        .is_some_and(|ident| ident.value.eq_ignore_ascii_case("set_config"));
+    let FunctionArguments::List(list) = &function.args else {
+        return None;
+    };
+
+    let string_arg = |idx: usize| match list.args.get(idx) {
+        Some(FunctionArg::Unnamed(FunctionArgExpr::Expr(Expr::Value(v)))) => match &v.value {
+            Value::SingleQuotedString(s) => Some(s.as_str()),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    if !is_set_config || !string_arg(0)?.eq_ignore_ascii_case("search_path") {
+        return None;
+    }
+    Some(parse_search_path_list(string_arg(1)?))
+}
+
+/// Check whether an ALTER TABLE operation affects the columns we model.
+fn changes_table_shape(operation: &AlterTableOperation) -> bool {
+    matches!(
+        operation,
+        AlterTableOperation::AddConstraint { .. }
+            | AlterTableOperation::AddColumn { .. }
+            | AlterTableOperation::DropColumn { .. }
+            | AlterTableOperation::RenameColumn { .. }
+            | AlterTableOperation::AlterColumn { .. }
+            | AlterTableOperation::RenameTable { .. }
+            | AlterTableOperation::DropConstraint { .. }
+    )
+}
+
+/// Split a psql meta-command line (`\ir users.sql`) into the command and
+/// its argument, with quotes removed.
+fn meta_command(line: &str) -> Option<(&str, &str)> {
+    let command = line.trim_start().strip_prefix('\\')?;
+    let (command, argument) = command
+        .split_once(char::is_whitespace)
+        .unwrap_or((command, ""));
+    let argument = argument.trim();
+    let argument = argument
+        .strip_prefix('\'')
+        .and_then(|a| a.strip_suffix('\''))
+        .unwrap_or(argument);
+    Some((command, argument))
+}
+
+/// Attribute an error to the schema file it happened in.
+fn schema_file_error(path: &Path, source: Error) -> Error {
+    Error::SchemaFile {
+        path: path.display().to_string(),
+        source: Box::new(source),
+    }
+}
+
+/// Expand a glob pattern (`schema/*.sql`) into the matching files, sorted by path.
+pub fn glob_paths(pattern: &str) -> Result<Vec<PathBuf>> {
+    let paths = glob::glob(pattern)
+        .map_err(|e| Error::SchemaParse(format!("Invalid glob pattern {}: {}", pattern, e)))?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::Io(e.into()))?;
+    if paths.is_empty() {
+        return Err(Error::SchemaParse(format!(
+            "No schema files match {}",
+            pattern
+        )));
+    }
+    Ok(paths)
+}
+
+/// Check whether a file is an "up" migration (`*.sql`, but not `*.down.sql`).
+fn is_up_migration(path: &Path) -> bool {
+    let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    file_name.ends_with(".sql") && !file_name.ends_with(".down.sql")
+}
+
+/// The sort key of a migration: its numeric prefix compared as a number
+/// (`2_x` before `10_x`), then its file name.
+fn migration_order(path: &Path) -> (usize, String, String) {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default()
+        .to_string();
+    let digits: String = name.chars().take_while(char::is_ascii_digit).collect();
+    let number = digits.trim_start_matches('0').to_string();
+    (number.len(), number, name)
+}
+
+/// Convert an ObjectName to a simple string.
+///
+/// Parts containing a dot are quoted (`"my.schema".t`).
+pub(crate) fn object_name_to_string(name: &ObjectName) -> String {
+    name.0
+        .iter()
+        .filter_map(|part| part.as_ident().map(|i| quote_name_part(&i.value)))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Extract length from CharacterLength enum.
+fn extract_char_length(len: &Option<CharacterLength>) -> Option<u32> {
+    match len {
+        Some(CharacterLength::IntegerLength { length, .. }) => Some(*length as u32),
+        Some(CharacterLength::Max) => None,
+        None => None,
+    }
+}
+
+/// The name of the sequence Postgres creates for a serial or identity column.
+fn implicit_sequence(namespace: &str, table: &str, column: &str) -> String {
+    qualify(namespace, &format!("{table}_{column}_seq"))
+}
+
+/// Whether an ALTER TABLE operation on a table also applies to a table
+/// inheriting from it.
+///
+/// Columns added to the parent are merged with ones the child already has,
+/// and constraints the child already has (by name) are kept. Constraints
+/// other than CHECK only carry over to partitions, and identity never does.
+fn applies_to_child(operation: &AlterTableOperation, child: &Table) -> bool {
+    match operation {
+        AlterTableOperation::AddColumn { column_def, .. } => {
+            !child.has_column(&column_def.name.value)
+        }
+        AlterTableOperation::AddConstraint { constraint, .. } => {
+            let name = match constraint {
+                TableConstraint::Check(check) => check.name.as_ref(),
+                TableConstraint::ForeignKey(fk) => fk.name.as_ref(),
+                _ => None,
+            };
+            let exists = name.is_some_and(|name| {
+                child.get_check(&name.value).is_some()
+                    || child.get_foreign_key(&name.value).is_some()
+            });
+            !exists
+                && (matches!(constraint, TableConstraint::Check(_))
+                    || child.partition_bound.is_some())
+        }
+        AlterTableOperation::AlterColumn { op, .. } => {
+            !matches!(op, AlterColumnOperation::AddGenerated { .. })
+        }
+        AlterTableOperation::DropColumn { .. }
+        | AlterTableOperation::RenameColumn { .. }
+        | AlterTableOperation::DropConstraint { .. } => true,
+        _ => false,
+    }
+}
+
+/// The sequences owned by the columns an ALTER TABLE operation drops.
+fn dropped_sequences(table: &Table, operation: &AlterTableOperation) -> Vec<String> {
+    let AlterTableOperation::DropColumn { column_names, .. } = operation else {
+        return Vec::new();
+    };
+    column_names
+        .iter()
+        .filter_map(|name| table.get_column(&name.value)?.sequence.clone())
+        .collect()
+}
+
+/// The integer type behind a `serial` pseudo-type, if the type is one.
+fn serial_type(dt: &DataType) -> Option<PostgresType> {
+    let DataType::Custom(name, modifiers) = dt else {
+        return None;
+    };
+    if !modifiers.is_empty() {
+        return None;
+    }
+    match object_name_to_string(name).to_lowercase().as_str() {
+        "smallserial" | "serial2" => Some(PostgresType::SmallInt),
+        "serial" | "serial4" => Some(PostgresType::Integer),
+        "bigserial" | "serial8" => Some(PostgresType::BigInt),
+        _ => None,
+    }
+}
+
+/// Convert sqlparser DataType to our PostgresType.
+fn data_type_to_postgres(dt: &DataType) -> Result<PostgresType> {
+    Ok(match dt {
+        // Exact numeric types
+        DataType::SmallInt(_) => PostgresType::SmallInt,
+        DataType::Int(_) | DataType::Integer(_) => PostgresType::Integer,
+        DataType::BigInt(_) => PostgresType::BigInt,
+        DataType::Real => PostgresType::Real,
+        DataType::Double(_) | DataType::DoublePrecision => PostgresType::DoublePrecision,
+        DataType::Numeric(_) | DataType::Decimal(_) => PostgresType::Numeric,
+
+        // Character types
+        DataType::Text => PostgresType::Text,
+        DataType::Varchar(len) => PostgresType::Varchar(extract_char_length(len)),
+        DataType::Char(len) => PostgresType::Char(extract_char_length(len)),
+        DataType::CharacterVarying(len) => PostgresType::Varchar(extract_char_length(len)),
+        DataType::Character(len) => PostgresType::Char(extract_char_length(len)),
+
+        // Binary
+        DataType::Bytea => PostgresType::Bytea,
+
+        // Boolean
+        DataType::Boolean | DataType::Bool => PostgresType::Boolean,
+
+        // Date/Time
+        DataType::Timestamp(_, tz) => {
+            if matches!(tz, TimezoneInfo::WithTimeZone | TimezoneInfo::Tz) {
+                PostgresType::TimestampTz
+            } else {
+                PostgresType::Timestamp
+            }
+        }
+        DataType::Date => PostgresType::Date,
+        DataType::Time(_, tz) => {
+            if matches!(tz, TimezoneInfo::WithTimeZone | TimezoneInfo::Tz) {
+                PostgresType::TimeTz
+            } else {
+                PostgresType::Time
+            }
+        }
+        DataType::Interval { .. } => PostgresType::Interval,
+
+        // UUID
+        DataType::Uuid => PostgresType::Uuid,
+
+        // JSON
+        DataType::JSON => PostgresType::Json,
+        DataType::JSONB => PostgresType::Jsonb,
+
+        // Array types
+        DataType::Array(inner) => match inner {
+            sqlparser::ast::ArrayElemTypeDef::AngleBracket(inner_dt)
+            | sqlparser::ast::ArrayElemTypeDef::SquareBracket(inner_dt, _)
+            | sqlparser::ast::ArrayElemTypeDef::Parenthesis(inner_dt) => {
+                PostgresType::Array(Box::new(data_type_to_postgres(inner_dt)?))
+            }
+            sqlparser::ast::ArrayElemTypeDef::None => {
+                return Err(Error::SchemaParse("Array with no element type".to_string()));
+            }
+        },
+
+        // Custom types (enums, etc.)
         DataType::Custom(name, _) => PostgresType::Custom(object_name_to_string(name)),
 
-        // Fallback for other types
-        other => PostgresType::Custom(format!("{:?}", other)),
-    })
-}
+        // Fallback for other types
+        other => PostgresType::Custom(format!("{:?}", other)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_table() {
+        let sql = r#"
+            CREATE TABLE users (
+                id uuid NOT NULL,
+                name text NOT NULL,
+                email text NOT NULL,
+                created_at timestamp with time zone NOT NULL DEFAULT now(),
+                CONSTRAINT users_pkey PRIMARY KEY (id)
+            );
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let table = schema.get_table("users").unwrap();
+
+        assert_eq!(table.name, "users");
+        assert_eq!(table.columns.len(), 4);
+
+        let id_col = table.get_column("id").unwrap();
+        assert_eq!(id_col.data_type, PostgresType::Uuid);
+        assert!(!id_col.nullable);
+        assert!(id_col.is_primary_key);
+
+        let name_col = table.get_column("name").unwrap();
+        assert_eq!(name_col.data_type, PostgresType::Text);
+        assert!(!name_col.nullable);
+
+        let created_col = table.get_column("created_at").unwrap();
+        assert_eq!(created_col.data_type, PostgresType::TimestampTz);
+        assert!(created_col.has_default);
+    }
+
+    #[test]
+    fn test_parse_jsonb_column() {
+        let sql = r#"
+            CREATE TABLE items (
+                id integer NOT NULL,
+                metadata jsonb NOT NULL DEFAULT '{}'
+            );
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let table = schema.get_table("items").unwrap();
+
+        let metadata_col = table.get_column("metadata").unwrap();
+        assert_eq!(metadata_col.data_type, PostgresType::Jsonb);
+    }
+
+    #[test]
+    fn test_parse_nullable_columns() {
+        let sql = r#"
+            CREATE TABLE profiles (
+                id uuid NOT NULL,
+                bio text,
+                avatar_url text
+            );
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let table = schema.get_table("profiles").unwrap();
+
+        let bio_col = table.get_column("bio").unwrap();
+        assert!(bio_col.nullable);
+
+        let avatar_col = table.get_column("avatar_url").unwrap();
+        assert!(avatar_col.nullable);
+    }
+
+    #[test]
+    fn test_alter_table_add_constraint() {
+        let sql = r#"
+            CREATE TABLE public.users (
+                id uuid,
+                email text NOT NULL
+            );
+
+            ALTER TABLE ONLY public.users
+                ADD CONSTRAINT users_pkey PRIMARY KEY (id);
+
+            ALTER TABLE ONLY public.users
+                ADD CONSTRAINT users_email_key UNIQUE (email);
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let table = schema.get_table("public.users").unwrap();
+
+        let id_col = table.get_column("id").unwrap();
+        assert!(id_col.is_primary_key);
+        assert!(!id_col.nullable);
+
+        let email_col = table.get_column("email").unwrap();
+        assert!(email_col.is_unique);
+        assert!(!email_col.is_primary_key);
+    }
+
+    #[test]
+    fn test_alter_table_qualified_name_matches_unqualified_table() {
+        let sql = r#"
+            CREATE TABLE users (
+                id uuid
+            );
+
+            ALTER TABLE ONLY public.users
+                ADD CONSTRAINT users_pkey PRIMARY KEY (id);
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let id_col = schema.get_table("users").unwrap().get_column("id").unwrap();
+        assert!(id_col.is_primary_key);
+        assert!(!id_col.nullable);
+    }
+
+    #[test]
+    fn test_alter_table_unknown_table() {
+        let sql = "ALTER TABLE ONLY public.missing ADD CONSTRAINT missing_pkey PRIMARY KEY (id);";
+
+        let result = Schema::from_sql(sql);
+        assert!(matches!(result, Err(Error::UnknownTable(_))));
+    }
+
+    #[test]
+    fn test_alter_table_column_operations() {
+        let sql = r#"
+            CREATE TABLE users (
+                id uuid PRIMARY KEY,
+                name text,
+                legacy_flag boolean
+            );
+
+            ALTER TABLE users ADD COLUMN email varchar(255);
+            ALTER TABLE users DROP COLUMN legacy_flag;
+            ALTER TABLE users RENAME COLUMN name TO full_name;
+            ALTER TABLE users ALTER COLUMN full_name SET NOT NULL;
+            ALTER TABLE users ALTER COLUMN email TYPE text;
+            ALTER TABLE users ALTER COLUMN email SET DEFAULT '';
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let table = schema.get_table("users").unwrap();
+
+        assert_eq!(
+            table.column_names().collect::<Vec<_>>(),
+            vec!["id", "full_name", "email"]
+        );
+        assert!(!table.has_column("legacy_flag"));
+        assert!(!table.has_column("name"));
+
+        let full_name = table.get_column("full_name").unwrap();
+        assert!(!full_name.nullable);
+
+        let email = table.get_column("email").unwrap();
+        assert_eq!(email.data_type, PostgresType::Text);
+        assert!(email.has_default);
+        assert!(email.nullable);
+    }
+
+    #[test]
+    fn test_alter_table_rename_and_drop_table() {
+        let sql = r#"
+            CREATE TABLE accounts (id uuid NOT NULL);
+            CREATE TABLE scratch (id uuid NOT NULL);
+
+            ALTER TABLE accounts RENAME TO users;
+            DROP TABLE scratch;
+            DROP TABLE IF EXISTS never_created;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        assert!(schema.has_table("users"));
+        assert!(!schema.has_table("accounts"));
+        assert!(!schema.has_table("scratch"));
+        assert_eq!(schema.get_table("users").unwrap().name, "users");
+    }
+
+    #[test]
+    fn test_alter_table_drop_unknown_column() {
+        let sql = r#"
+            CREATE TABLE users (id uuid NOT NULL);
+            ALTER TABLE users DROP COLUMN nope;
+        "#;
+
+        let result = Schema::from_sql(sql);
+        assert!(matches!(result, Err(Error::UnknownColumn { .. })));
+    }
+
+    #[test]
+    fn test_from_migrations() {
+        let dir = std::env::temp_dir().join(format!(
+            "sql-check-migrations-{}-{}",
+            std::process::id(),
+            "test_from_migrations"
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("0003_add_bio")).unwrap();
+
+        std::fs::write(
+            dir.join("0001_create_users.sql"),
+            "CREATE TABLE users (id uuid PRIMARY KEY, name text NOT NULL);",
+        )
+        .unwrap();
+        std::fs::write(dir.join("0001_create_users.down.sql"), "DROP TABLE users;").unwrap();
+        std::fs::write(
+            dir.join("0002_rename_name.sql"),
+            "ALTER TABLE users RENAME COLUMN name TO display_name;",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("0003_add_bio").join("up.sql"),
+            "ALTER TABLE users ADD COLUMN bio text;",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("0003_add_bio").join("down.sql"),
+            "ALTER TABLE users DROP COLUMN bio;",
+        )
+        .unwrap();
+
+        let schema = Schema::from_migrations(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let table = schema.get_table("users").unwrap();
+        assert_eq!(
+            table.column_names().collect::<Vec<_>>(),
+            vec!["id", "display_name", "bio"]
+        );
+        assert!(table.get_column("id").unwrap().is_primary_key);
+        assert!(table.get_column("bio").unwrap().nullable);
+    }
+
+    #[test]
+    fn test_from_files_and_includes() {
+        let dir = std::env::temp_dir().join(format!(
+            "sql-check-schema-files-{}-{}",
+            std::process::id(),
+            "test_from_files_and_includes"
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("schema").join("types")).unwrap();
+
+        std::fs::write(
+            dir.join("schema").join("1_users.sql"),
+            "\\restrict abc123\n\\ir types/mood.sql\nCREATE TABLE users (id integer PRIMARY KEY, mood mood);",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("schema").join("types").join("mood.sql"),
+            "CREATE TYPE mood AS ENUM ('happy', 'sad');",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("schema").join("2_billing.sql"),
+            "CREATE SCHEMA billing;\nCREATE TABLE billing.invoices (user_id integer REFERENCES users (id));",
+        )
+        .unwrap();
+
+        let pattern = format!("{}/schema/*.sql", dir.display());
+        let schema = Schema::from_glob(&pattern).unwrap();
+        assert!(matches!(
+            schema
+                .get_table("users")
+                .unwrap()
+                .get_column("mood")
+                .unwrap()
+                .data_type,
+            PostgresType::Enum(_)
+        ));
+        assert_eq!(
+            schema.get_table("billing.invoices").unwrap().foreign_keys[0].referenced_table,
+            "public.users"
+        );
+
+        // The same definitions loaded twice are duplicates, blamed on the later file
+        let users = dir.join("schema").join("1_users.sql");
+        let result = Schema::from_files(&[&users, &users]);
+        match result {
+            Err(Error::SchemaFile { path, source }) => {
+                assert!(path.ends_with("1_users.sql"));
+                assert!(matches!(
+                    *source,
+                    Error::SchemaFile { ref source, .. }
+                        if matches!(**source, Error::DuplicateDefinition { kind: "type", .. })
+                ));
+            }
+            other => panic!("expected schema file error, got {:?}", other),
+        }
+
+        // A SET search_path lasts across includes, until the end of the file
+        std::fs::write(
+            dir.join("app.sql"),
+            "CREATE SCHEMA app;\nSET search_path = app;\n\\ir app_tables.sql\nCREATE TABLE after_include (id integer);",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("app_tables.sql"),
+            "CREATE TABLE included (id integer);",
+        )
+        .unwrap();
+        let schema = Schema::from_files(&[dir.join("app.sql"), users.clone()]).unwrap();
+        assert!(schema.get_table("app.included").is_some());
+        assert!(schema.get_table("app.after_include").is_some());
+        assert!(schema.get_table("public.users").is_some());
+        assert_eq!(schema.search_path(), ["public"]);
+
+        // Include cycles are rejected instead of recursing forever
+        std::fs::write(dir.join("loop.sql"), "\\ir loop.sql").unwrap();
+        let result = Schema::from_file(&dir.join("loop.sql"));
+        assert!(matches!(result, Err(Error::SchemaFile { .. })));
+
+        let result = Schema::from_glob(&format!("{}/missing/*.sql", dir.display()));
+        assert!(matches!(result, Err(Error::SchemaParse(_))));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_duplicate_definitions() {
+        for sql in [
+            "CREATE TABLE users (id integer); CREATE TABLE public.users (id integer);",
+            "CREATE TYPE mood AS ENUM ('a'); CREATE DOMAIN mood AS text;",
+            "CREATE VIEW v AS SELECT 1 AS one; CREATE VIEW v AS SELECT 2 AS two;",
+            "CREATE SEQUENCE s; CREATE SEQUENCE s;",
+            "CREATE TABLE t (id serial); CREATE SEQUENCE t_id_seq;",
+            "CREATE FUNCTION f(a integer) RETURNS integer LANGUAGE sql AS 'SELECT a'; CREATE FUNCTION f(b integer) RETURNS bigint LANGUAGE sql AS 'SELECT b';",
+        ] {
+            let result = Schema::from_sql(sql);
+            assert!(
+                matches!(result, Err(Error::DuplicateDefinition { .. })),
+                "{sql}: {result:?}"
+            );
+        }
+
+        Schema::from_sql(
+            r#"
+            CREATE TABLE users (id integer);
+            CREATE TABLE IF NOT EXISTS users (id bigint);
+            CREATE VIEW v AS SELECT 1 AS one;
+            CREATE OR REPLACE VIEW v AS SELECT 2 AS two;
+            CREATE SEQUENCE s;
+            CREATE SEQUENCE IF NOT EXISTS s;
+            CREATE FUNCTION f(a integer) RETURNS integer LANGUAGE sql AS 'SELECT a';
+            CREATE FUNCTION f(a text) RETURNS integer LANGUAGE sql AS 'SELECT 1';
+            CREATE OR REPLACE FUNCTION f(a integer) RETURNS bigint LANGUAGE sql AS 'SELECT a';
+            CREATE SCHEMA billing;
+            CREATE TABLE billing.users (id integer);
+            "#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_from_migrations_numeric_order() {
+        let dir = std::env::temp_dir().join(format!(
+            "sql-check-migrations-{}-{}",
+            std::process::id(),
+            "test_from_migrations_numeric_order"
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(
+            dir.join("1_create_users.sql"),
+            "CREATE TABLE users (id uuid);",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("2_add_name.sql"),
+            "ALTER TABLE users ADD COLUMN name text;",
+        )
+        .unwrap();
+        // Sorted as strings, this would run before the column exists
+        std::fs::write(
+            dir.join("10_rename_name.sql"),
+            "ALTER TABLE users RENAME COLUMN name TO display_name;",
+        )
+        .unwrap();
+
+        let schema = Schema::from_migrations(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let table = schema.get_table("users").unwrap();
+        assert_eq!(
+            table.column_names().collect::<Vec<_>>(),
+            vec!["id", "display_name"]
+        );
+    }
+
+    #[test]
+    fn test_from_migrations_reports_failing_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "sql-check-migrations-{}-{}",
+            std::process::id(),
+            "test_from_migrations_reports_failing_file"
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(
+            dir.join("0001_alter_missing.sql"),
+            "ALTER TABLE missing ADD COLUMN id uuid;",
+        )
+        .unwrap();
+
+        let result = Schema::from_migrations(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        match result {
+            Err(Error::Migration { path, source }) => {
+                assert!(path.ends_with("0001_alter_missing.sql"));
+                assert!(matches!(*source, Error::UnknownTable(_)));
+            }
+            other => panic!("expected migration error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_enum_type() {
+        let sql = r#"
+            CREATE TYPE public.mood AS ENUM ('sad', 'ok', 'happy');
+
+            CREATE TABLE public.people (
+                id uuid NOT NULL,
+                current_mood public.mood NOT NULL,
+                past_moods mood[]
+            );
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+
+        let mood = schema.get_enum("mood").unwrap();
+        assert_eq!(mood.name, "mood");
+        assert_eq!(mood.qualified_name(), "public.mood");
+        assert_eq!(mood.labels, vec!["sad", "ok", "happy"]);
+        assert!(mood.has_label("ok"));
+        assert!(!mood.has_label("angry"));
+
+        let table = schema.get_table("public.people").unwrap();
+        assert_eq!(
+            table.get_column("current_mood").unwrap().data_type,
+            PostgresType::Enum("public.mood".to_string())
+        );
+        assert_eq!(
+            table.get_column("past_moods").unwrap().data_type,
+            PostgresType::Array(Box::new(PostgresType::Enum("public.mood".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_alter_enum_type() {
+        let sql = r#"
+            CREATE TYPE mood AS ENUM ('sad', 'happy');
+            CREATE TABLE people (current_mood mood NOT NULL);
+
+            ALTER TYPE mood ADD VALUE 'ok' BEFORE 'happy';
+            ALTER TYPE mood ADD VALUE 'ecstatic' AFTER 'happy';
+            ALTER TYPE mood ADD VALUE 'meh';
+            ALTER TYPE mood ADD VALUE IF NOT EXISTS 'meh';
+            ALTER TYPE mood RENAME VALUE 'sad' TO 'down';
+            ALTER TYPE mood RENAME TO feeling;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        assert!(schema.get_enum("mood").is_none());
+
+        let feeling = schema.get_enum("feeling").unwrap();
+        assert_eq!(
+            feeling.labels,
+            vec!["down", "ok", "happy", "ecstatic", "meh"]
+        );
+
+        let column = schema
+            .get_table("people")
+            .unwrap()
+            .get_column("current_mood")
+            .unwrap();
+        assert_eq!(
+            column.data_type,
+            PostgresType::Enum("public.feeling".to_string())
+        );
+    }
+
+    #[test]
+    fn test_drop_enum_type() {
+        let sql = r#"
+            CREATE TYPE mood AS ENUM ('sad', 'happy');
+            DROP TYPE mood;
+            DROP TYPE IF EXISTS mood;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        assert_eq!(schema.enum_names().count(), 0);
+
+        let result = Schema::from_sql("ALTER TYPE missing ADD VALUE 'x';");
+        assert!(matches!(result, Err(Error::UnknownType(_))));
+    }
+
+    #[test]
+    fn test_parse_composite_type() {
+        let sql = r#"
+            CREATE TYPE mood AS ENUM ('sad', 'happy');
+            CREATE TYPE postal_address AS (
+                street TEXT,
+                city VARCHAR(100),
+                zip TEXT
+            );
+            CREATE TYPE checkin AS (
+                feeling mood,
+                location postal_address,
+                tags TEXT[]
+            );
+            CREATE TABLE venues (
+                id UUID PRIMARY KEY,
+                address postal_address NOT NULL,
+                history checkin[]
+            );
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let address = schema.get_composite("postal_address").unwrap();
+        assert_eq!(address.rust_name(), "PostalAddress");
+        assert_eq!(address.fields.len(), 3);
+        assert_eq!(address.fields[0].name, "street");
+        assert_eq!(
+            address.get_field("city").unwrap().data_type,
+            PostgresType::Varchar(Some(100))
+        );
+
+        let checkin = schema.get_composite("checkin").unwrap();
+        assert_eq!(
+            checkin.get_field("feeling").unwrap().data_type,
+            PostgresType::Enum("public.mood".to_string())
+        );
+        assert_eq!(
+            checkin.get_field("location").unwrap().data_type,
+            PostgresType::Composite("public.postal_address".to_string())
+        );
+
+        let venues = schema.get_table("venues").unwrap();
+        let column = venues.get_column("address").unwrap();
+        assert_eq!(
+            column.data_type,
+            PostgresType::Composite("public.postal_address".to_string())
+        );
+        assert!(!column.nullable);
+        assert_eq!(
+            venues.get_column("history").unwrap().data_type,
+            PostgresType::Array(Box::new(PostgresType::Composite(
+                "public.checkin".to_string()
+            )))
+        );
+    }
+
+    #[test]
+    fn test_rename_and_drop_composite_type() {
+        let sql = r#"
+            CREATE TYPE address AS (street TEXT, city TEXT);
+            CREATE TYPE shipment AS (destination address);
+            CREATE TABLE venues (id UUID PRIMARY KEY, address address);
+            ALTER TYPE address RENAME TO postal_address;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        assert!(schema.get_composite("address").is_none());
+        assert!(schema.get_composite("postal_address").is_some());
+        assert_eq!(
+            schema
+                .get_table("venues")
+                .unwrap()
+                .get_column("address")
+                .unwrap()
+                .data_type,
+            PostgresType::Composite("public.postal_address".to_string())
+        );
+        assert_eq!(
+            schema.get_composite("shipment").unwrap().fields[0].data_type,
+            PostgresType::Composite("public.postal_address".to_string())
+        );
+
+        let schema = Schema::from_sql(
+            "CREATE TYPE address AS (street TEXT); DROP TYPE address; DROP TYPE IF EXISTS address;",
+        )
+        .unwrap();
+        assert_eq!(schema.composite_names().count(), 0);
+
+        let result = Schema::from_sql(
+            "CREATE TYPE address AS (street TEXT); ALTER TYPE address ADD VALUE 'x';",
+        );
+        assert!(matches!(result, Err(Error::UnknownType(_))));
+    }
+
+    #[test]
+    fn test_parse_domains() {
+        let sql = r#"
+            CREATE DOMAIN public.email_address AS text NOT NULL
+                CONSTRAINT email_address_check CHECK ((VALUE ~~ '%@%'::text));
+            CREATE DOMAIN nickname AS varchar(50) COLLATE "C" DEFAULT 'anon'::character varying;
+            CREATE DOMAIN work_email AS email_address CHECK (VALUE LIKE '%@example.com');
+            CREATE DOMAIN maybe_text AS text NULL;
+            CREATE TABLE contacts (
+                id UUID PRIMARY KEY,
+                email public.email_address,
+                work work_email,
+                nick nickname,
+                aliases email_address[],
+                notes maybe_text
+            );
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let email = schema.get_domain("email_address").unwrap();
+        assert_eq!(email.base_type, PostgresType::Text);
+        assert!(email.not_null);
+        assert_eq!(email.rust_name(), "EmailAddress");
+
+        let work = schema.get_domain("work_email").unwrap();
+        assert_eq!(work.base_type, PostgresType::Text);
+        assert!(work.not_null);
+
+        let contacts = schema.get_table("contacts").unwrap();
+        let column = contacts.get_column("email").unwrap();
+        assert_eq!(column.data_type, PostgresType::Text);
+        assert!(!column.nullable);
+        assert!(!contacts.get_column("work").unwrap().nullable);
+
+        let nick = contacts.get_column("nick").unwrap();
+        assert_eq!(nick.data_type, PostgresType::Varchar(Some(50)));
+        assert!(nick.nullable);
+        assert!(nick.has_default);
+
+        let aliases = contacts.get_column("aliases").unwrap();
+        assert_eq!(
+            aliases.data_type,
+            PostgresType::Array(Box::new(PostgresType::Text))
+        );
+        assert!(aliases.nullable);
+        assert!(contacts.get_column("notes").unwrap().nullable);
+    }
+
+    #[test]
+    fn test_drop_domain() {
+        let schema = Schema::from_sql(
+            "CREATE DOMAIN code AS text; DROP DOMAIN code; DROP DOMAIN IF EXISTS code;",
+        )
+        .unwrap();
+        assert_eq!(schema.domain_names().count(), 0);
+
+        let result = Schema::from_sql("DROP DOMAIN missing;");
+        assert!(matches!(result, Err(Error::UnknownType(_))));
+
+        let result = Schema::from_sql("CREATE DOMAIN code AS text CONSTRAINT c;");
+        assert!(matches!(result, Err(Error::SchemaParse(_))));
+    }
+
+    #[test]
+    fn test_parse_views() {
+        let sql = r#"
+            CREATE TABLE users (
+                id UUID PRIMARY KEY,
+                name TEXT NOT NULL,
+                active BOOLEAN NOT NULL
+            );
+            CREATE VIEW active_users AS SELECT id, name FROM users WHERE active;
+            CREATE VIEW user_names (user_id, display_name) AS SELECT id, name FROM active_users;
+            CREATE MATERIALIZED VIEW public.user_counts AS
+             SELECT count(*) AS total
+               FROM public.users
+              WITH NO DATA;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let view = schema.get_view("active_users").unwrap();
+        assert!(!view.materialized);
+        assert_eq!(view.columns.len(), 2);
+        assert_eq!(
+            view.get_column("name").unwrap().rust_type,
+            crate::types::RustType::String
+        );
+
+        let renamed = schema.get_view("user_names").unwrap();
+        let names: Vec<_> = renamed.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["user_id", "display_name"]);
+
+        let counts = schema.get_view("user_counts").unwrap();
+        assert!(counts.materialized);
+        assert_eq!(
+            counts.get_column("total").unwrap().rust_type,
+            crate::types::RustType::I64
+        );
+
+        // Views are not tables
+        assert!(!schema.has_table("active_users"));
+    }
+
+    #[test]
+    fn test_drop_and_invalid_views() {
+        let sql = r#"
+            CREATE TABLE users (id UUID PRIMARY KEY);
+            CREATE VIEW all_users AS SELECT id FROM users;
+            CREATE MATERIALIZED VIEW cached_users AS SELECT id FROM users;
+            DROP VIEW all_users;
+            DROP MATERIALIZED VIEW cached_users;
+            DROP VIEW IF EXISTS all_users;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        assert_eq!(schema.view_names().count(), 0);
+
+        // Views that can't be typed only fail the queries that use them
+        let schema = Schema::from_sql(
+            r#"
+            CREATE TABLE t (id int NOT NULL, data jsonb);
+            CREATE VIEW broken AS SELECT id FROM missing;
+            CREATE VIEW pairs AS SELECT id, x.value FROM t, jsonb_each(t.data) x;
+            CREATE VIEW on_broken AS SELECT id FROM broken;
+            COMMENT ON COLUMN broken.id IS 'unknown';
+            "#,
+        )
+        .unwrap();
+        let broken = schema.get_view("broken").unwrap();
+        assert!(broken.columns.is_empty());
+        assert!(broken.error.as_deref().unwrap().contains("missing"));
+        assert!(schema.get_view("on_broken").unwrap().error.is_some());
+        assert!(schema.get_view("pairs").is_some());
+        assert!(matches!(
+            crate::validate_query(&schema, "SELECT id FROM broken"),
+            Err(Error::View { name, .. }) if name == "public.broken"
+        ));
+    }
+
+    #[test]
+    fn test_quoted_names_with_dots() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE SCHEMA "my.schema";
+            CREATE TABLE "my.schema".t (id int NOT NULL);
+            CREATE TABLE "my.schema"."a.b" (x text);
+            "#,
+        )
+        .unwrap();
+        let table = schema.get_table("\"my.schema\".t").unwrap();
+        assert_eq!(
+            (table.namespace.as_str(), table.name.as_str()),
+            ("my.schema", "t")
+        );
+        assert_eq!(
+            schema.get_table("\"my.schema\".\"a.b\"").unwrap().name,
+            "a.b"
+        );
+        assert!(schema.get_table("my.schema.t").is_none());
+
+        let result = crate::validate_query(
+            &schema,
+            r#"SELECT t.id, x FROM "my.schema".t, "my.schema"."a.b""#,
+        )
+        .unwrap();
+        assert_eq!(result.columns.len(), 2);
+    }
+
+    #[test]
+    fn test_schema_qualified_tables() {
+        let sql = r#"
+            SELECT pg_catalog.set_config('search_path', '', false);
+
+            CREATE SCHEMA billing;
+
+            CREATE TABLE public.users (id uuid NOT NULL, email text NOT NULL);
+            CREATE TABLE billing.users (id uuid NOT NULL, account_code text NOT NULL);
+
+            SET search_path = billing, public;
+            CREATE TABLE invoices (id uuid NOT NULL, user_id uuid NOT NULL);
+            ALTER TABLE invoices ADD COLUMN paid boolean NOT NULL;
+        "#;
+
+        let mut schema = Schema::from_sql(sql).unwrap();
+        assert!(schema.has_namespace("billing"));
+
+        // The dump's search_path doesn't outlive the dump
+        assert_eq!(schema.search_path(), ["public"]);
+        assert!(schema.get_table("users").unwrap().has_column("email"));
+        assert!(schema
+            .get_table("public.users")
+            .unwrap()
+            .has_column("email"));
+        assert!(schema
+            .get_table("billing.users")
+            .unwrap()
+            .has_column("account_code"));
+
+        // Created while billing was first on the path
+        let invoices = schema.get_table("billing.invoices").unwrap();
+        assert_eq!(invoices.namespace, "billing");
+        assert_eq!(invoices.qualified_name(), "billing.invoices");
+        assert!(invoices.has_column("paid"));
+        assert!(schema.get_table("invoices").is_none());
+
+        schema.set_search_path(["billing", "public"]);
+        assert!(schema.get_table("invoices").is_some());
+        assert!(schema
+            .get_table("users")
+            .unwrap()
+            .has_column("account_code"));
+    }
+
+    #[test]
+    fn test_search_path_values() {
+        let sql = r#"
+            CREATE SCHEMA app;
+            SET search_path TO '"$user", app';
+            CREATE TYPE mood AS ENUM ('sad', 'happy');
+            CREATE TABLE people (current_mood mood);
+            SET search_path TO DEFAULT;
+            CREATE TABLE audit (id uuid);
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        assert!(schema.get_enum("app.mood").is_some());
+        assert_eq!(
+            schema
+                .get_table("app.people")
+                .unwrap()
+                .get_column("current_mood")
+                .unwrap()
+                .data_type,
+            PostgresType::Enum("app.mood".to_string())
+        );
+        assert!(schema.get_table("public.audit").is_some());
+    }
+
+    #[test]
+    fn test_drop_schema() {
+        let sql = r#"
+            CREATE SCHEMA IF NOT EXISTS billing;
+            CREATE TABLE billing.invoices (id uuid);
+            CREATE TABLE users (id uuid);
+            DROP SCHEMA billing CASCADE;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        assert!(!schema.has_namespace("billing"));
+        assert!(schema.get_table("billing.invoices").is_none());
+        assert!(schema.has_table("users"));
+
+        let result = Schema::from_sql("DROP SCHEMA missing;");
+        assert!(matches!(result, Err(Error::UnknownSchema(_))));
+    }
+
+    #[test]
+    fn test_parse_foreign_keys() {
+        let sql = r#"
+            CREATE TABLE users (
+                id uuid PRIMARY KEY,
+                email text NOT NULL UNIQUE
+            );
+
+            CREATE TABLE categories (
+                id uuid PRIMARY KEY,
+                parent_id uuid,
+                CONSTRAINT categories_parent_fkey FOREIGN KEY (parent_id)
+                    REFERENCES categories(id) ON DELETE SET NULL
+            );
+
+            CREATE TABLE orders (
+                id uuid PRIMARY KEY,
+                user_id uuid NOT NULL REFERENCES users ON DELETE CASCADE ON UPDATE RESTRICT,
+                user_email text,
+                category_id uuid
+            );
+
+            ALTER TABLE ONLY public.orders
+                ADD CONSTRAINT orders_category_fkey FOREIGN KEY (category_id) REFERENCES public.categories(id);
+            ALTER TABLE orders ADD FOREIGN KEY (user_email) REFERENCES users (email);
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+
+        let categories = schema.get_table("categories").unwrap();
+        let parent = categories
+            .get_foreign_key("categories_parent_fkey")
+            .unwrap();
+        assert_eq!(parent.columns, ["parent_id"]);
+        assert_eq!(parent.referenced_table, "public.categories");
+        assert_eq!(parent.referenced_columns, ["id"]);
+        assert_eq!(parent.on_delete, ReferentialAction::SetNull);
+        assert_eq!(parent.on_update, ReferentialAction::NoAction);
+
+        let orders = schema.get_table("orders").unwrap();
+        assert_eq!(orders.foreign_keys.len(), 3);
+
+        // Column-level REFERENCES without columns points at the primary key
+        let user = orders.get_foreign_key("orders_user_id_fkey").unwrap();
+        assert_eq!(user.referenced_table, "public.users");
+        assert_eq!(user.referenced_columns, ["id"]);
+        assert_eq!(user.on_delete, ReferentialAction::Cascade);
+        assert_eq!(user.on_update, ReferentialAction::Restrict);
+
+        let category = orders.get_foreign_key("orders_category_fkey").unwrap();
+        assert_eq!(category.referenced_table, "public.categories");
+
+        let email = orders.get_foreign_key("orders_user_email_fkey").unwrap();
+        assert_eq!(email.referenced_columns, ["email"]);
+
+        assert_eq!(orders.foreign_keys_for_column("user_id").count(), 1);
+        assert_eq!(orders.foreign_keys_for_column("id").count(), 0);
+
+        // The primary key's columns are referenced in the order it declares them
+        let sql = r#"
+            CREATE TABLE line_items (
+                order_id uuid,
+                position integer,
+                PRIMARY KEY (position, order_id)
+            );
+            CREATE TABLE refunds (
+                position integer,
+                order_id uuid,
+                FOREIGN KEY (position, order_id) REFERENCES line_items
+            );
+        "#;
+        let schema = Schema::from_sql(sql).unwrap();
+        let refunds = schema.get_table("refunds").unwrap();
+        assert_eq!(
+            refunds.foreign_keys[0].referenced_columns,
+            ["position", "order_id"]
+        );
+    }
+
+    #[test]
+    fn test_foreign_keys_follow_alterations() {
+        let sql = r#"
+            CREATE SCHEMA billing;
+            CREATE TABLE users (id uuid PRIMARY KEY, legacy_id integer UNIQUE);
+            CREATE TABLE billing.invoices (
+                id uuid PRIMARY KEY,
+                user_id uuid REFERENCES users (id),
+                legacy_user_id integer REFERENCES users (legacy_id),
+                replaces_id uuid CONSTRAINT invoices_replaces_fkey REFERENCES billing.invoices
+            );
+
+            ALTER TABLE users RENAME TO accounts;
+            ALTER TABLE accounts RENAME COLUMN id TO account_id;
+            ALTER TABLE accounts DROP COLUMN legacy_id CASCADE;
+            ALTER TABLE billing.invoices RENAME COLUMN user_id TO account_id;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let invoices = schema.get_table("billing.invoices").unwrap();
+        assert_eq!(invoices.foreign_keys.len(), 2);
+
+        let account = invoices.get_foreign_key("invoices_user_id_fkey").unwrap();
+        assert_eq!(account.columns, ["account_id"]);
+        assert_eq!(account.referenced_table, "public.accounts");
+        assert_eq!(account.referenced_columns, ["account_id"]);
+
+        let replaces = invoices.get_foreign_key("invoices_replaces_fkey").unwrap();
+        assert_eq!(replaces.referenced_table, "billing.invoices");
+        assert_eq!(replaces.referenced_columns, ["id"]);
+
+        let sql = r#"
+            CREATE TABLE users (id uuid PRIMARY KEY);
+            CREATE TABLE orders (id uuid, user_id uuid REFERENCES users);
+            ALTER TABLE orders DROP CONSTRAINT orders_user_id_fkey;
+        "#;
+        let schema = Schema::from_sql(sql).unwrap();
+        assert!(schema.get_table("orders").unwrap().foreign_keys.is_empty());
+
+        let sql = r#"
+            CREATE TABLE users (id uuid PRIMARY KEY);
+            CREATE TABLE orders (id uuid, user_id uuid REFERENCES users);
+            DROP TABLE users CASCADE;
+        "#;
+        let schema = Schema::from_sql(sql).unwrap();
+        assert!(schema.get_table("orders").unwrap().foreign_keys.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_foreign_keys() {
+        let result = Schema::from_sql("CREATE TABLE orders (user_id uuid REFERENCES users);");
+        assert!(matches!(result, Err(Error::UnknownTable(t)) if t == "users"));
+
+        let result = Schema::from_sql(
+            r#"
+            CREATE TABLE users (id uuid PRIMARY KEY);
+            CREATE TABLE orders (user_id uuid REFERENCES users (uid));
+            "#,
+        );
+        assert!(matches!(result, Err(Error::UnknownColumn { column, .. }) if column == "uid"));
+    }
+
+    #[test]
+    fn test_forward_foreign_keys() {
+        let sql = r#"
+            CREATE TABLE orders (id uuid PRIMARY KEY, user_id uuid REFERENCES users);
+            CREATE TABLE users (id uuid PRIMARY KEY, last_order uuid);
+            ALTER TABLE users ADD FOREIGN KEY (last_order) REFERENCES orders (id);
+        "#;
+        let schema = Schema::from_sql(sql).unwrap();
+        let fk = &schema.get_table("orders").unwrap().foreign_keys[0];
+        assert_eq!(fk.referenced_table, "public.users");
+        assert_eq!(fk.referenced_columns, vec!["id"]);
+        let fk = &schema.get_table("users").unwrap().foreign_keys[0];
+        assert_eq!(fk.referenced_table, "public.orders");
+
+        // Looked up with the search path the key was declared with
+        let sql = r#"
+            CREATE SCHEMA billing;
+            SET search_path = billing;
+            CREATE TABLE invoices (id uuid PRIMARY KEY, account_id uuid REFERENCES accounts);
+            CREATE TABLE accounts (id uuid PRIMARY KEY);
+            SET search_path = public;
+            CREATE TABLE accounts (id integer PRIMARY KEY);
+        "#;
+        let schema = Schema::from_sql(sql).unwrap();
+        let fk = &schema.get_table("billing.invoices").unwrap().foreign_keys[0];
+        assert_eq!(fk.referenced_table, "billing.accounts");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let result = Schema::from_sql(
+            r#"
+            CREATE TABLE orders (user_id uuid REFERENCES users (uid));
+            CREATE TABLE users (id uuid PRIMARY KEY);
+            "#,
+        );
+        assert!(matches!(result, Err(Error::UnknownColumn { column, .. }) if column == "uid"));
+
+        let dir = std::env::temp_dir().join(format!("sql_check_fk_files_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("1_orders.sql"),
+            "CREATE TABLE orders (user_id uuid REFERENCES users);",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("2_users.sql"),
+            "CREATE TABLE users (id uuid PRIMARY KEY);",
+        )
+        .unwrap();
+        let schema = Schema::from_files(&[dir.join("1_orders.sql"), dir.join("2_users.sql")]);
+        let missing = Schema::from_files(&[dir.join("1_orders.sql")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let schema = schema.unwrap();
+        let fk = &schema.get_table("orders").unwrap().foreign_keys[0];
+        assert_eq!(fk.referenced_columns, vec!["id"]);
+        assert!(matches!(missing, Err(Error::UnknownTable(t)) if t == "users"));
+    }
 
     #[test]
-    fn test_parse_simple_table() {
+    fn test_parse_indexes() {
         let sql = r#"
             CREATE TABLE users (
-                id uuid NOT NULL,
-                name text NOT NULL,
+                id uuid PRIMARY KEY,
                 email text NOT NULL,
-                created_at timestamp with time zone NOT NULL DEFAULT now(),
-                CONSTRAINT users_pkey PRIMARY KEY (id)
+                username text,
+                org_id uuid,
+                deleted_at timestamptz
             );
+
+            CREATE UNIQUE INDEX users_email_key ON public.users USING btree (email);
+            CREATE UNIQUE INDEX users_username_active ON users (username) WHERE deleted_at IS NULL;
+            CREATE UNIQUE INDEX ON users (org_id, lower(username)) INCLUDE (email);
+            CREATE INDEX IF NOT EXISTS users_email_key ON users (email);
+            CREATE INDEX users_email_trgm ON users USING gin (email gin_trgm_ops);
+
+            CREATE MATERIALIZED VIEW user_emails AS SELECT email FROM users;
+            CREATE INDEX user_emails_email ON user_emails (email);
         "#;
 
         let schema = Schema::from_sql(sql).unwrap();
-        let table = schema.get_table("users").unwrap();
+        let users = schema.get_table("users").unwrap();
+        assert_eq!(users.indexes.len(), 4);
 
-        assert_eq!(table.name, "users");
-        assert_eq!(table.columns.len(), 4);
+        let email = users.get_index("users_email_key").unwrap();
+        assert!(email.unique);
+        assert_eq!(email.method, "btree");
+        assert_eq!(email.keys, [IndexKey::Column("email".to_string())]);
+        assert_eq!(email.unique_column(), Some("email"));
 
-        let id_col = table.get_column("id").unwrap();
-        assert_eq!(id_col.data_type, PostgresType::Uuid);
-        assert!(!id_col.nullable);
-        assert!(id_col.is_primary_key);
+        let username = users.get_index("users_username_active").unwrap();
+        assert_eq!(username.predicate.as_deref(), Some("deleted_at IS NULL"));
+        assert_eq!(username.unique_column(), None);
 
-        let name_col = table.get_column("name").unwrap();
-        assert_eq!(name_col.data_type, PostgresType::Text);
-        assert!(!name_col.nullable);
+        let composite = users.get_index("users_org_id_lower_idx").unwrap();
+        assert_eq!(
+            composite.keys,
+            [
+                IndexKey::Column("org_id".to_string()),
+                IndexKey::Expression("lower(username)".to_string())
+            ]
+        );
+        assert_eq!(composite.include, ["email"]);
 
-        let created_col = table.get_column("created_at").unwrap();
-        assert_eq!(created_col.data_type, PostgresType::TimestampTz);
-        assert!(created_col.has_default);
+        assert_eq!(users.get_index("users_email_trgm").unwrap().method, "gin");
+
+        // Only the full single-column unique index makes a column unique
+        assert!(users.get_column("email").unwrap().is_unique);
+        assert!(!users.get_column("username").unwrap().is_unique);
+        assert!(!users.get_column("org_id").unwrap().is_unique);
+    }
+    #[test]
+    fn test_unnamed_index_names() {
+        // The names Postgres gives these indexes
+        let sql = r#"
+            CREATE TABLE t (a text, b text, c integer);
+            CREATE INDEX ON t (lower(a));
+            CREATE INDEX ON t (upper(b));
+            CREATE INDEX ON t (lower(a), upper(b), c, c);
+            CREATE INDEX ON t ((c::text));
+            CREATE INDEX ON t ((c + 1));
+            CREATE INDEX ON t ((c + 1));
+            CREATE INDEX ON t ((c + 1), (c + 2));
+            CREATE UNIQUE INDEX ON t (c);
+            ALTER TABLE t ADD UNIQUE (a);
+            CREATE INDEX ON t (a);
+        "#;
+        let mut schema = Schema::from_sql(sql).unwrap();
+        let names: Vec<_> = schema
+            .get_table("t")
+            .unwrap()
+            .indexes
+            .iter()
+            .map(|index| index.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "t_lower_idx",
+                "t_upper_idx",
+                "t_lower_upper_c_c1_idx",
+                "t_c_idx",
+                "t_expr_idx",
+                "t_expr_idx1",
+                "t_expr_expr1_idx",
+                "t_c_idx1",
+                "t_a_idx",
+            ]
+        );
+
+        // Each can be dropped on its own
+        schema.apply_sql("DROP INDEX t_expr_idx;").unwrap();
+        let t = schema.get_table("t").unwrap();
+        assert!(t.get_index("t_expr_idx").is_none());
+        assert_eq!(
+            t.get_index("t_expr_idx1").unwrap().keys,
+            [IndexKey::Expression("(c + 1)".to_string())]
+        );
     }
 
     #[test]
-    fn test_parse_jsonb_column() {
+    fn test_index_alterations() {
         let sql = r#"
-            CREATE TABLE items (
-                id integer NOT NULL,
-                metadata jsonb NOT NULL DEFAULT '{}'
+            CREATE SCHEMA billing;
+            CREATE TABLE billing.invoices (id uuid, number text, user_id uuid);
+            CREATE INDEX invoices_number ON billing.invoices (number);
+            CREATE INDEX invoices_user ON billing.invoices (user_id);
+
+            ALTER INDEX billing.invoices_number RENAME TO invoices_number_idx;
+            ALTER TABLE billing.invoices RENAME COLUMN number TO invoice_number;
+            ALTER TABLE billing.invoices DROP COLUMN user_id;
+        "#;
+
+        let mut schema = Schema::from_sql(sql).unwrap();
+        let invoices = schema.get_table("billing.invoices").unwrap();
+        assert_eq!(invoices.indexes.len(), 1);
+        assert_eq!(
+            invoices.get_index("invoices_number_idx").unwrap().keys,
+            [IndexKey::Column("invoice_number".to_string())]
+        );
+
+        // Unqualified index names are looked up on the search path
+        let result = schema.apply_sql("DROP INDEX invoices_number_idx;");
+        assert!(matches!(result, Err(Error::UnknownIndex(_))));
+        schema
+            .apply_sql("DROP INDEX billing.invoices_number_idx;")
+            .unwrap();
+        assert!(schema
+            .get_table("billing.invoices")
+            .unwrap()
+            .indexes
+            .is_empty());
+
+        let result = Schema::from_sql("CREATE INDEX missing_idx ON missing (id);");
+        assert!(matches!(result, Err(Error::UnknownTable(_))));
+
+        let result = Schema::from_sql(
+            "CREATE TABLE users (id uuid); CREATE INDEX users_email ON users (email);",
+        );
+        assert!(matches!(result, Err(Error::UnknownColumn { .. })));
+    }
+
+    #[test]
+    fn test_dropping_unique_constraints() {
+        let sql = r#"
+            CREATE TABLE users (
+                id uuid PRIMARY KEY,
+                email text UNIQUE,
+                username text,
+                CONSTRAINT users_username_unique UNIQUE (username)
             );
+            CREATE UNIQUE INDEX users_email_idx ON users (email);
+            CREATE UNIQUE INDEX users_username_idx ON users (username);
         "#;
+        let mut schema = Schema::from_sql(sql).unwrap();
+        let users = schema.get_table("users").unwrap();
+        let names: Vec<_> = users.unique_keys.iter().map(|k| k.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["users_pkey", "users_email_key", "users_username_unique"]
+        );
 
-        let schema = Schema::from_sql(sql).unwrap();
-        let table = schema.get_table("items").unwrap();
+        // The UNIQUE constraint still makes email unique
+        schema.apply_sql("DROP INDEX users_email_idx;").unwrap();
+        assert!(
+            schema
+                .get_table("users")
+                .unwrap()
+                .get_column("email")
+                .unwrap()
+                .is_unique
+        );
+        schema
+            .apply_sql("ALTER TABLE users DROP CONSTRAINT users_email_key;")
+            .unwrap();
+        assert!(
+            !schema
+                .get_table("users")
+                .unwrap()
+                .get_column("email")
+                .unwrap()
+                .is_unique
+        );
 
-        let metadata_col = table.get_column("metadata").unwrap();
-        assert_eq!(metadata_col.data_type, PostgresType::Jsonb);
+        schema
+            .apply_sql("ALTER TABLE users DROP CONSTRAINT users_username_unique;")
+            .unwrap();
+        assert!(
+            schema
+                .get_table("users")
+                .unwrap()
+                .get_column("username")
+                .unwrap()
+                .is_unique
+        );
+        schema.apply_sql("DROP INDEX users_username_idx;").unwrap();
+        assert!(
+            !schema
+                .get_table("users")
+                .unwrap()
+                .get_column("username")
+                .unwrap()
+                .is_unique
+        );
+
+        schema
+            .apply_sql("ALTER TABLE users DROP CONSTRAINT users_pkey;")
+            .unwrap();
+        let id = schema.get_table("users").unwrap().get_column("id").unwrap();
+        assert!(!id.is_primary_key);
+        assert!(!id.nullable);
     }
 
     #[test]
-    fn test_parse_nullable_columns() {
+    fn test_parse_check_constraints() {
         let sql = r#"
-            CREATE TABLE profiles (
+            CREATE TABLE orders (
                 id uuid NOT NULL,
-                bio text,
-                avatar_url text
+                status text CHECK (status IN ('pending', 'shipped')),
+                priority integer,
+                shipped_at timestamptz,
+                notes text,
+                CONSTRAINT orders_priority_check CHECK ((priority = ANY (ARRAY[1, 2, 3]))),
+                CHECK (shipped_at IS NOT NULL AND priority > 0)
             );
+
+            ALTER TABLE ONLY public.orders
+                ADD CONSTRAINT orders_kind_check CHECK (((status)::text = ANY ((ARRAY['pending'::character varying, 'shipped'::character varying])::text[])));
+            ALTER TABLE orders ADD CONSTRAINT orders_notes_present CHECK (notes IS NOT NULL) NOT VALID;
         "#;
 
         let schema = Schema::from_sql(sql).unwrap();
-        let table = schema.get_table("profiles").unwrap();
+        let orders = schema.get_table("orders").unwrap();
+        assert_eq!(orders.checks.len(), 5);
 
-        let bio_col = table.get_column("bio").unwrap();
-        assert!(bio_col.nullable);
+        let status = orders.get_check("orders_status_check").unwrap();
+        assert_eq!(status.columns, ["status"]);
+        assert_eq!(
+            status.allowed_values,
+            Some(AllowedValues {
+                column: "status".to_string(),
+                values: vec!["pending".to_string(), "shipped".to_string()],
+            })
+        );
+        assert_eq!(
+            orders.allowed_values("priority").unwrap(),
+            ["1".to_string(), "2".to_string(), "3".to_string()]
+        );
+        assert!(orders
+            .get_check("orders_kind_check")
+            .unwrap()
+            .allowed_values
+            .is_some());
 
-        let avatar_col = table.get_column("avatar_url").unwrap();
-        assert!(avatar_col.nullable);
+        // The unnamed table-level check is named after its first column
+        let shipped = orders.get_check("orders_shipped_at_check").unwrap();
+        assert_eq!(shipped.columns, ["shipped_at", "priority"]);
+        assert_eq!(shipped.allowed_values, None);
+
+        // IS NOT NULL tightens nullability, unless the constraint is NOT VALID
+        assert!(!orders.get_column("shipped_at").unwrap().nullable);
+        assert!(orders.get_column("notes").unwrap().nullable);
+        assert!(orders.get_column("status").unwrap().nullable);
+        assert!(orders.allowed_values("notes").is_none());
+    }
+
+    #[test]
+    fn test_check_constraints_follow_alterations() {
+        let sql = r#"
+            CREATE TABLE orders (
+                id uuid,
+                state text CHECK (state IN ('a', 'b')),
+                total integer CHECK (total >= 0)
+            );
+            ALTER TABLE orders RENAME COLUMN state TO status;
+            ALTER TABLE orders DROP COLUMN total;
+        "#;
+
+        let mut schema = Schema::from_sql(sql).unwrap();
+        let orders = schema.get_table("orders").unwrap();
+        assert_eq!(orders.checks.len(), 1);
+        assert!(orders.allowed_values("status").is_some());
+
+        schema
+            .apply_sql("ALTER TABLE orders DROP CONSTRAINT orders_state_check;")
+            .unwrap();
+        assert!(schema.get_table("orders").unwrap().checks.is_empty());
+    }
+
+    #[test]
+    fn test_parse_generated_columns() {
+        let sql = r#"
+            CREATE TABLE people (
+                id bigint GENERATED ALWAYS AS IDENTITY,
+                legacy_id integer GENERATED BY DEFAULT AS IDENTITY,
+                height_cm numeric,
+                height_in numeric GENERATED ALWAYS AS (height_cm / 2.54) STORED,
+                name text
+            );
+
+            CREATE TABLE events (id integer NOT NULL, name text);
+            ALTER TABLE public.events ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+                SEQUENCE NAME public.events_id_seq
+                START WITH 1
+                INCREMENT BY 1
+                NO MINVALUE
+                NO MAXVALUE
+                CACHE 1
+            );
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let people = schema.get_table("people").unwrap();
+
+        let id = people.get_column("id").unwrap();
+        assert_eq!(id.generated, Some(Generated::Identity { always: true }));
+        assert!(id.has_default);
+        assert!(!id.nullable);
+        assert!(id.write_restriction(false).is_some());
+        assert!(id.write_restriction(true).is_none());
+
+        let legacy_id = people.get_column("legacy_id").unwrap();
+        assert_eq!(
+            legacy_id.generated,
+            Some(Generated::Identity { always: false })
+        );
+        assert!(legacy_id.write_restriction(false).is_none());
+
+        let height_in = people.get_column("height_in").unwrap();
+        assert_eq!(
+            height_in.generated,
+            Some(Generated::Expression("height_cm / 2.54".to_string()))
+        );
+        assert!(height_in.has_default);
+        assert!(height_in.nullable);
+        assert!(height_in.write_restriction(true).is_some());
+
+        assert_eq!(people.get_column("name").unwrap().generated, None);
+
+        let events = schema.get_table("events").unwrap();
+        let id = events.get_column("id").unwrap();
+        assert_eq!(id.generated, Some(Generated::Identity { always: true }));
+        assert!(id.has_default);
+    }
+
+    #[test]
+    fn test_parse_tables_named_identity() {
+        // Only the options after GENERATED ... AS IDENTITY are left out
+        let sql = r#"
+            CREATE TABLE identity (
+                id int GENERATED BY DEFAULT AS IDENTITY (START WITH 10 INCREMENT BY 5),
+                name text
+            );
+            CREATE TABLE badges (
+                identity_id int NOT NULL REFERENCES identity (id),
+                identity varchar(20) CHECK (identity IN ('a', 'b'))
+            );
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let identity = schema.get_table("identity").unwrap();
+        let names: Vec<_> = identity.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name"]);
+        assert_eq!(
+            identity.get_column("id").unwrap().generated,
+            Some(Generated::Identity { always: false })
+        );
+
+        let badges = schema.get_table("badges").unwrap();
+        assert_eq!(badges.columns.len(), 2);
+        assert_eq!(badges.foreign_keys.len(), 1);
+        assert!(badges.allowed_values("identity").is_some());
+    }
+
+    #[test]
+    fn test_parse_serial_columns() {
+        let sql = r#"
+            CREATE SCHEMA app;
+            CREATE TABLE app.posts (
+                id SERIAL PRIMARY KEY,
+                views bigserial,
+                rank smallserial,
+                legacy serial4
+            );
+            ALTER TABLE app.posts ADD COLUMN revision serial8;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let posts = schema.get_table("app.posts").unwrap();
+
+        for (name, data_type) in [
+            ("id", PostgresType::Integer),
+            ("views", PostgresType::BigInt),
+            ("rank", PostgresType::SmallInt),
+            ("legacy", PostgresType::Integer),
+            ("revision", PostgresType::BigInt),
+        ] {
+            let column = posts.get_column(name).unwrap();
+            assert_eq!(column.data_type, data_type, "{name}");
+            assert!(!column.nullable, "{name}");
+            assert!(column.has_default, "{name}");
+            assert_eq!(
+                column.sequence.as_deref(),
+                Some(format!("app.posts_{name}_seq").as_str())
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_functions() {
+        let sql = r#"
+            CREATE TABLE users (id uuid NOT NULL, name text NOT NULL);
+
+            CREATE FUNCTION public.full_name(first text, last text DEFAULT ''::text) RETURNS text
+                LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+                AS $$ SELECT first || ' ' || last $$;
+
+            CREATE OR REPLACE FUNCTION active_users(since timestamp with time zone) RETURNS SETOF public.users
+                LANGUAGE sql STABLE SECURITY DEFINER
+                SET search_path TO 'public', 'pg_temp'
+                COST 10 ROWS 100
+                AS 'SELECT * FROM users';
+
+            CREATE FUNCTION user_stats(IN user_id uuid, OUT orders bigint, OUT total numeric)
+                RETURNS record
+                LANGUAGE plpgsql
+                AS $_$ BEGIN orders := 0; total := 0; END $_$;
+
+            CREATE FUNCTION recent_names(lim integer = 10)
+                RETURNS TABLE(name text, seen_at timestamp without time zone)
+                LANGUAGE sql
+                BEGIN ATOMIC
+                    SELECT name, CASE WHEN true THEN now() END FROM users LIMIT lim;
+                END;
+
+            CREATE FUNCTION pick(VARIADIC choices integer[]) RETURNS integer
+                RETURN choices[1];
+
+            ALTER FUNCTION public.full_name(text, text) OWNER TO postgres;
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+
+        let full_name = &schema.get_functions("full_name")[0];
+        assert_eq!(full_name.qualified_name(), "public.full_name");
+        assert_eq!(
+            full_name.returns,
+            FunctionReturn::Scalar(PostgresType::Text)
+        );
+        assert!(full_name.strict);
+        assert_eq!(full_name.args.len(), 2);
+        assert_eq!(full_name.args[0].name.as_deref(), Some("first"));
+        assert!(full_name.args[1].has_default);
+        assert!(full_name.accepts(1) && full_name.accepts(2));
+        assert!(!full_name.accepts(0) && !full_name.accepts(3));
+
+        let active = &schema.get_functions("public.active_users")[0];
+        assert_eq!(
+            active.returns,
+            FunctionReturn::SetOf(PostgresType::Custom("public.users".to_string()))
+        );
+        assert_eq!(active.args[0].data_type, PostgresType::TimestampTz);
+        assert!(!active.strict);
+
+        // OUT arguments describe the result and aren't passed by callers
+        let stats = &schema.get_functions("user_stats")[0];
+        assert_eq!(stats.args.len(), 1);
+        let FunctionReturn::Table(columns) = &stats.returns else {
+            panic!("expected a row result, got {:?}", stats.returns);
+        };
+        let names: Vec<_> = columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["orders", "total"]);
+
+        let recent = &schema.get_functions("recent_names")[0];
+        assert!(recent.accepts(0));
+        assert_eq!(
+            recent.returns,
+            FunctionReturn::Table(vec![
+                CompositeField {
+                    name: "name".to_string(),
+                    data_type: PostgresType::Text,
+                },
+                CompositeField {
+                    name: "seen_at".to_string(),
+                    data_type: PostgresType::Timestamp,
+                },
+            ])
+        );
+
+        let pick = &schema.get_functions("pick")[0];
+        assert!(pick.args[0].variadic);
+        assert!(pick.accepts(1) && pick.accepts(5));
+        assert!(!pick.accepts(0));
+    }
+
+    #[test]
+    fn test_function_overloads_and_alterations() {
+        let sql = r#"
+            CREATE FUNCTION area(r double precision) RETURNS double precision
+                AS 'SELECT pi() * r * r' LANGUAGE sql;
+            CREATE FUNCTION area(w integer, h integer) RETURNS integer
+                AS 'SELECT w * h' LANGUAGE sql;
+            CREATE OR REPLACE FUNCTION area(w integer, h integer) RETURNS bigint
+                AS 'SELECT w::bigint * h' LANGUAGE sql;
+        "#;
+
+        let mut schema = Schema::from_sql(sql).unwrap();
+        let overloads = schema.get_functions("area");
+        assert_eq!(overloads.len(), 2);
+        assert_eq!(
+            overloads[1].returns,
+            FunctionReturn::Scalar(PostgresType::BigInt)
+        );
+
+        schema
+            .apply_sql("ALTER FUNCTION area(integer, integer) RENAME TO rect_area;")
+            .unwrap();
+        assert_eq!(schema.get_functions("area").len(), 1);
+        assert_eq!(schema.get_functions("rect_area").len(), 1);
+
+        schema
+            .apply_sql("DROP FUNCTION area(double precision); DROP FUNCTION IF EXISTS area;")
+            .unwrap();
+        assert!(schema.get_functions("area").is_empty());
+
+        let result = schema.apply_sql("DROP FUNCTION area;");
+        assert!(matches!(result, Err(Error::UnknownFunction(_))));
+
+        schema
+            .apply_sql("CREATE SCHEMA util; CREATE FUNCTION util.one() RETURNS integer RETURN 1;")
+            .unwrap();
+        assert_eq!(schema.get_functions("util.one").len(), 1);
+        schema.apply_sql("DROP SCHEMA util CASCADE;").unwrap();
+        assert!(schema.get_functions("util.one").is_empty());
+    }
+
+    #[test]
+    fn test_parse_sequences() {
+        let sql = r#"
+            CREATE TABLE public.invoices (
+                id integer NOT NULL,
+                number bigint NOT NULL
+            );
+
+            CREATE SEQUENCE public.invoices_id_seq
+                AS integer
+                START WITH 1
+                INCREMENT BY 1
+                NO MINVALUE
+                NO MAXVALUE
+                CACHE 1;
+
+            ALTER SEQUENCE public.invoices_id_seq OWNED BY public.invoices.id;
+            ALTER TABLE ONLY public.invoices ALTER COLUMN id SET DEFAULT nextval('public.invoices_id_seq'::regclass);
+
+            CREATE SEQUENCE invoice_numbers START WITH 1000 MINVALUE -1 CYCLE;
+            CREATE SEQUENCE IF NOT EXISTS invoice_numbers AS smallint;
+
+            CREATE TABLE events (
+                id bigserial PRIMARY KEY,
+                seq integer GENERATED BY DEFAULT AS IDENTITY
+            );
+        "#;
+
+        let mut schema = Schema::from_sql(sql).unwrap();
+
+        let id_seq = schema.get_sequence("invoices_id_seq").unwrap();
+        assert_eq!(id_seq.qualified_name(), "public.invoices_id_seq");
+        assert_eq!(id_seq.data_type, PostgresType::Integer);
+        let invoices = schema.get_table("invoices").unwrap();
+        assert_eq!(
+            invoices.get_column("id").unwrap().sequence.as_deref(),
+            Some("public.invoices_id_seq")
+        );
+
+        let numbers = schema.get_sequence("public.invoice_numbers").unwrap();
+        assert_eq!(numbers.data_type, PostgresType::BigInt);
+
+        // Serial and identity columns bring their own sequences
+        assert_eq!(
+            schema.get_sequence("events_id_seq").unwrap().data_type,
+            PostgresType::BigInt
+        );
+        assert_eq!(
+            schema.get_sequence("events_seq_seq").unwrap().data_type,
+            PostgresType::Integer
+        );
+
+        schema
+            .apply_sql(
+                "ALTER SEQUENCE invoices_id_seq RENAME TO invoice_ids;
+                 ALTER SEQUENCE invoice_numbers OWNED BY invoices.number;
+                 ALTER SEQUENCE IF EXISTS missing RESTART;",
+            )
+            .unwrap();
+        assert!(schema.get_sequence("invoices_id_seq").is_none());
+        let invoices = schema.get_table("invoices").unwrap();
+        assert_eq!(
+            invoices.get_column("id").unwrap().sequence.as_deref(),
+            Some("public.invoice_ids")
+        );
+        assert_eq!(
+            invoices.get_column("number").unwrap().sequence.as_deref(),
+            Some("public.invoice_numbers")
+        );
+
+        // Owned sequences are dropped with their column or table
+        schema
+            .apply_sql("ALTER TABLE invoices DROP COLUMN number; DROP TABLE events;")
+            .unwrap();
+        let mut names: Vec<_> = schema.sequence_names().collect();
+        names.sort();
+        assert_eq!(names, ["public.invoice_ids"]);
+
+        schema
+            .apply_sql("ALTER SEQUENCE invoice_ids OWNED BY NONE; DROP TABLE invoices;")
+            .unwrap();
+        assert!(schema.get_sequence("invoice_ids").is_some());
+        schema.apply_sql("DROP SEQUENCE invoice_ids;").unwrap();
+        assert_eq!(schema.sequence_names().count(), 0);
+
+        let result = schema.apply_sql("DROP SEQUENCE invoice_ids;");
+        assert!(matches!(result, Err(Error::UnknownSequence(_))));
+        let result = schema.apply_sql("ALTER SEQUENCE invoice_ids RESTART WITH 1;");
+        assert!(matches!(result, Err(Error::UnknownSequence(_))));
+        let result = Schema::from_sql("CREATE SEQUENCE s OWNED BY missing.id;");
+        assert!(matches!(result, Err(Error::UnknownTable(_))));
+    }
+
+    #[test]
+    fn test_parse_comments() {
+        let sql = r#"
+            CREATE TABLE public.users (
+                id integer NOT NULL,
+                email text NOT NULL
+            );
+            CREATE VIEW public.user_emails AS SELECT id, email FROM public.users;
+            CREATE TYPE public.pair AS (a integer, b integer);
+            CREATE FUNCTION public.answer() RETURNS integer LANGUAGE sql AS 'SELECT 42';
+
+            COMMENT ON TABLE public.users IS 'Registered users';
+            COMMENT ON COLUMN public.users.id IS 'Primary key';
+            COMMENT ON COLUMN users.email IS 'Login address, unique per user';
+            COMMENT ON COLUMN public.user_emails.email IS 'From users.email';
+            COMMENT ON COLUMN public.pair.a IS 'First';
+            COMMENT ON FUNCTION public.answer() IS 'Always 42';
+            COMMENT ON VIEW public.user_emails IS 'Emails by user';
+            COMMENT ON SCHEMA public IS 'standard public schema';
+        "#;
+
+        let mut schema = Schema::from_sql(sql).unwrap();
+        let users = schema.get_table("users").unwrap();
+        assert_eq!(users.comment.as_deref(), Some("Registered users"));
+        assert_eq!(
+            users.get_column("id").unwrap().comment.as_deref(),
+            Some("Primary key")
+        );
+        assert_eq!(
+            users.get_column("email").unwrap().comment.as_deref(),
+            Some("Login address, unique per user")
+        );
+        let view = schema.get_view("user_emails").unwrap();
+        assert_eq!(
+            view.get_column("email").unwrap().comment.as_deref(),
+            Some("From users.email")
+        );
+
+        // IS NULL and empty strings remove the comment
+        schema
+            .apply_sql("COMMENT ON TABLE users IS NULL; COMMENT ON COLUMN users.id IS '';")
+            .unwrap();
+        let users = schema.get_table("users").unwrap();
+        assert_eq!(users.comment, None);
+        assert_eq!(users.get_column("id").unwrap().comment, None);
+
+        let result = schema.apply_sql("COMMENT ON COLUMN users.missing IS 'x';");
+        assert!(matches!(result, Err(Error::UnknownColumn { .. })));
+        let result = schema.apply_sql("COMMENT ON TABLE missing IS 'x';");
+        assert!(matches!(result, Err(Error::UnknownTable(_))));
+    }
+
+    #[test]
+    fn test_json_snapshot_round_trip() {
+        let sql = r#"
+            CREATE SCHEMA billing;
+            CREATE TYPE mood AS ENUM ('happy', 'sad');
+            CREATE TYPE pair AS (a integer, b text);
+            CREATE DOMAIN email AS text NOT NULL;
+            CREATE TABLE users (
+                id serial PRIMARY KEY,
+                email email,
+                mood mood,
+                status text CHECK (status IN ('active', 'banned'))
+            );
+            CREATE TABLE billing.invoices (
+                id bigint GENERATED ALWAYS AS IDENTITY,
+                user_id integer REFERENCES users (id) ON DELETE CASCADE
+            );
+            CREATE UNIQUE INDEX users_lower_email ON users (lower(email));
+            CREATE VIEW user_moods AS SELECT id, mood FROM users;
+            CREATE FUNCTION add(a integer, b integer DEFAULT 1) RETURNS integer
+                LANGUAGE sql STRICT AS 'SELECT a + b';
+            CREATE SEQUENCE billing.numbers;
+            COMMENT ON COLUMN users.email IS 'Login address';
+        "#;
+
+        let schema = Schema::from_sql(sql).unwrap();
+        let json = schema.to_json().unwrap();
+        // Deterministic, regardless of hash order
+        assert_eq!(Schema::from_sql(sql).unwrap().to_json().unwrap(), json);
+
+        let loaded = Schema::from_json(&json).unwrap();
+        assert_eq!(loaded.to_json().unwrap(), json);
+
+        let users = loaded.get_table("users").unwrap();
+        let email = users.get_column("EMAIL").unwrap();
+        assert_eq!(email.data_type, PostgresType::Text);
+        assert!(!email.nullable);
+        assert_eq!(email.comment.as_deref(), Some("Login address"));
+        assert_eq!(
+            users.allowed_values("status").unwrap(),
+            ["active", "banned"]
+        );
+        assert!(loaded.get_table("billing.invoices").is_some());
+        assert!(loaded.get_enum("mood").unwrap().has_label("sad"));
+        assert!(loaded.get_composite("pair").is_some());
+        assert!(loaded.get_view("user_moods").is_some());
+        assert!(loaded.get_functions("add")[0].strict);
+        assert!(loaded.get_sequence("billing.numbers").is_some());
+
+        // Queries validate the same against the loaded schema
+        let result = crate::validate_query(&loaded, "SELECT u.id, m.mood FROM users u JOIN user_moods m ON m.id = u.id WHERE status = 'active'").unwrap();
+        assert_eq!(result.columns.len(), 2);
+    }
+
+    #[test]
+    fn test_json_snapshot_version_1() {
+        // Written before unique keys, partitions, extensions and domains were tracked
+        let json = r#"{"version":1,"schema":{
+            "tables":{"public.users":{"name":"users","namespace":"public","columns":[
+                {"name":"id","data_type":"Integer","nullable":false,"has_default":true,
+                 "is_primary_key":true,"is_unique":false,"generated":null,
+                 "sequence":"public.users_id_seq","comment":null},
+                {"name":"email","data_type":"Text","nullable":false,"has_default":false,
+                 "is_primary_key":false,"is_unique":true,"generated":null,"sequence":null,
+                 "comment":"Login address"}],
+                "foreign_keys":[],"indexes":[],"checks":[],"comment":null}},
+            "enums":{},"composites":{},"domains":{},
+            "views":{"public.user_ids":{"name":"user_ids","namespace":"public",
+                "columns":[{"name":"id","rust_type":"I32","comment":null}],"materialized":false}},
+            "functions":{},
+            "sequences":{"public.users_id_seq":{"name":"users_id_seq","namespace":"public",
+                "data_type":"Integer"}},
+            "namespaces":["public"],"search_path":["public"]}}"#;
+
+        let mut schema = Schema::from_json(json).unwrap();
+        let users = schema.get_table("users").unwrap();
+        assert!(users.parents.is_empty());
+        assert_eq!(users.get_column("email").unwrap().domain, None);
+        assert_eq!(users.get_unique_key("users_pkey").unwrap().columns, ["id"]);
+        assert_eq!(
+            users.get_unique_key("users_email_key").unwrap().columns,
+            ["email"]
+        );
+        assert!(schema.get_view("user_ids").unwrap().error.is_none());
+        assert!(json.contains("\"version\":1"));
+        assert!(schema
+            .to_json()
+            .unwrap()
+            .starts_with(&format!("{{\"version\":{SNAPSHOT_VERSION},")));
+
+        // The rebuilt keys still make the columns unique after other changes
+        schema
+            .apply_sql("CREATE INDEX users_email_idx ON users (email); DROP INDEX users_email_idx;")
+            .unwrap();
+        let users = schema.get_table("users").unwrap();
+        assert!(users.get_column("id").unwrap().is_primary_key);
+        assert!(users.get_column("email").unwrap().is_unique);
+    }
+
+    #[test]
+    fn test_json_snapshot_errors() {
+        let json = Schema::new().to_json().unwrap();
+        let other_version = json.replace(
+            &format!("\"version\":{}", SNAPSHOT_VERSION),
+            "\"version\":999",
+        );
+        assert_ne!(json, other_version);
+        let result = Schema::from_json(&other_version);
+        assert!(
+            matches!(&result, Err(Error::Snapshot(message)) if message.contains("version 999"))
+        );
+
+        assert!(matches!(
+            Schema::from_json("{\"tables\": {}}"),
+            Err(Error::Snapshot(_))
+        ));
+        assert!(matches!(
+            Schema::from_json("not json"),
+            Err(Error::Snapshot(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_sql_tolerant() {
+        let sql = r#"\restrict abc123
+SET statement_timeout = 0;
+SELECT pg_catalog.set_config('search_path', '', false);
+
+CREATE TABLE public.users (
+    id integer NOT NULL,
+    email text NOT NULL
+);
+
+ALTER TABLE public.users OWNER TO app;
+CREATE POLICY users_self ON public.users USING (id = current_setting('app.user')::integer);
+CREATE TRIGGER users_audit AFTER UPDATE ON public.users
+    FOR EACH ROW EXECUTE FUNCTION audit();
+CREATE VIEW public.broken AS SELECT missing FROM public.users;
+CREATE VIEW public.emails AS SELECT email FROM public.users;
+\unrestrict abc123
+"#;
+        let (schema, warnings) = Schema::from_sql_tolerant(sql).unwrap();
+
+        assert!(schema.get_table("users").is_some());
+        assert!(schema.get_view("emails").is_some());
+        assert!(schema.get_view("broken").unwrap().error.is_some());
+        let lines: Vec<_> = warnings.iter().map(|w| w.line).collect();
+        assert_eq!(lines, vec![1, 14, 16]);
+        assert_eq!(
+            warnings[0].to_string(),
+            "line 1: Skipped psql meta-command \\restrict"
+        );
+
+        // The strict parser rejects the same script
+        assert!(Schema::from_sql(sql).is_err());
+
+        // Statements defining tables or types still fail
+        let err = Schema::from_sql_tolerant(
+            "CREATE POLICY p ON t USING (true);\nCREATE TABLE t (id integer NOT NULL,);",
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::SchemaParse(_)), "{err}");
+        let err = Schema::from_sql_tolerant(
+            "CREATE TYPE mood AS ENUM ('ok');\nCREATE TYPE mood AS ENUM ('ok');",
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::DuplicateDefinition { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_tolerant_storage_alters() {
+        let sql = r#"CREATE TYPE mood AS ENUM ('ok');
+CREATE TABLE users (id integer PRIMARY KEY, email text NOT NULL);
+CREATE INDEX users_email_idx ON users (email);
+ALTER TYPE mood OWNER TO app;
+ALTER TABLE users CLUSTER ON users_email_idx;
+ALTER TABLE ONLY users ALTER COLUMN email SET STATISTICS 500, ALTER COLUMN email SET STORAGE EXTERNAL;
+ALTER TABLE users REPLICA IDENTITY FULL;
+\i other.sql
+"#;
+        let (schema, warnings) = Schema::from_sql_tolerant(sql).unwrap();
+        assert!(schema.get_table("users").is_some());
+        let lines: Vec<_> = warnings.iter().map(|w| w.line).collect();
+        assert_eq!(lines, vec![4, 5, 6, 8]);
+        assert!(warnings[3].message.contains("from_file_tolerant"));
+
+        // Unparsable ALTERs that change a column's shape still fail
+        let err = Schema::from_sql_tolerant(
+            "CREATE TABLE t (id integer);\nALTER TABLE t OWNER TO app, ADD COLUMN x integer CHECK (;",
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::SchemaParse(_)), "{err}");
+    }
+
+    #[test]
+    fn test_from_file_tolerant() {
+        let dir = std::env::temp_dir().join(format!(
+            "sql-check-schema-files-{}-{}",
+            std::process::id(),
+            "test_from_file_tolerant"
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        std::fs::write(
+            dir.join("dump.sql"),
+            "\\restrict abc123\n\\ir types.sql\nCREATE TABLE users (id integer, mood mood);\nCREATE VIEW broken AS SELECT missing FROM users;",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("types.sql"),
+            "CREATE TYPE mood AS ENUM ('ok');\nALTER TYPE mood OWNER TO app;",
+        )
+        .unwrap();
+
+        let (schema, warnings) = Schema::from_file_tolerant(&dir.join("dump.sql")).unwrap();
+        assert!(schema.get_table("users").is_some());
+        let warnings: Vec<_> = warnings
+            .iter()
+            .map(|w| (w.file.as_ref().unwrap().file_name().unwrap(), w.line))
+            .collect();
+        assert_eq!(
+            warnings,
+            vec![
+                ("dump.sql".as_ref(), 1),
+                ("types.sql".as_ref(), 2),
+                ("dump.sql".as_ref(), 4)
+            ]
+        );
+
+        // The strict loader rejects the same files
+        assert!(Schema::from_file(&dir.join("dump.sql")).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_partitioned_tables() {
+        let sql = r#"
+CREATE TABLE events (
+    id bigint NOT NULL,
+    kind text NOT NULL CHECK (kind <> ''),
+    occurred_at timestamptz NOT NULL,
+    PRIMARY KEY (id, occurred_at)
+) PARTITION BY RANGE (occurred_at);
+
+CREATE TABLE events_2024 PARTITION OF events (
+    kind WITH OPTIONS DEFAULT 'misc'
+) FOR VALUES FROM ('2024-01-01') TO ('2025-01-01');
+
+CREATE TABLE events_old (
+    id bigint NOT NULL,
+    kind text NOT NULL,
+    occurred_at timestamptz NOT NULL
+);
+ALTER TABLE ONLY events ATTACH PARTITION events_old FOR VALUES FROM (MINVALUE) TO ('2024-01-01');
+CREATE TABLE events_default PARTITION OF events DEFAULT;
+
+CREATE INDEX events_kind_idx ON ONLY events (kind);
+CREATE INDEX events_2024_kind_idx ON events_2024 (kind);
+ALTER INDEX events_kind_idx ATTACH PARTITION events_2024_kind_idx;
+
+ALTER TABLE events ADD COLUMN payload jsonb;
+ALTER TABLE ONLY events ADD COLUMN source text;
+ALTER TABLE events DETACH PARTITION events_default;
+"#;
+        let schema = Schema::from_sql(sql).unwrap();
+
+        let events = schema.get_table("events").unwrap();
+        assert_eq!(events.partition_key.as_deref(), Some("RANGE(occurred_at)"));
+        assert!(events.has_column("source"));
+
+        let partition = schema.get_table("events_2024").unwrap();
+        assert_eq!(partition.parents, vec!["public.events"]);
+        assert_eq!(
+            partition.partition_bound.as_deref(),
+            Some("FOR VALUES FROM ('2024-01-01') TO ('2025-01-01')")
+        );
+        let names: Vec<_> = partition.column_names().collect();
+        assert_eq!(names, vec!["id", "kind", "occurred_at", "payload"]);
+        assert!(partition.get_column("id").unwrap().is_primary_key);
+        assert!(partition.get_column("kind").unwrap().has_default);
+        assert!(partition.get_check("events_kind_check").is_some());
+        assert!(partition.get_index("events_2024_kind_idx").is_some());
+
+        let attached = schema.get_table("events_old").unwrap();
+        assert_eq!(attached.parents, vec!["public.events"]);
+        assert!(attached.has_column("payload"));
+        assert!(!attached.has_column("source"));
+
+        let detached = schema.get_table("events_default").unwrap();
+        assert!(detached.parents.is_empty());
+        assert_eq!(detached.partition_bound, None);
+        // Detached partitions keep the columns they got while attached
+        assert!(detached.has_column("payload"));
+
+        // Partitions go away with their parent
+        let schema = Schema::from_sql(&format!("{sql}\nDROP TABLE events;")).unwrap();
+        assert!(!schema.has_table("events_2024"));
+        assert!(!schema.has_table("events_old"));
+        assert!(schema.has_table("events_default"));
+
+        // Attached tables must have the parent's columns
+        let err = Schema::from_sql(
+            "CREATE TABLE t (a int, b int) PARTITION BY LIST (a);
+             CREATE TABLE t1 (a int);
+             ALTER TABLE t ATTACH PARTITION t1 FOR VALUES IN (1);",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("t1"), "{err}");
+
+        // Options only apply to inherited columns
+        let err = Schema::from_sql(
+            "CREATE TABLE t (a int) PARTITION BY LIST (a);
+             CREATE TABLE t1 PARTITION OF t (b NOT NULL) FOR VALUES IN (1);",
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::UnknownColumn { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_table_inheritance() {
+        let sql = r#"
+CREATE TABLE audit_log (
+    id bigint PRIMARY KEY,
+    message text CHECK (message <> ''),
+    logged_at timestamptz DEFAULT now()
+);
+CREATE TABLE audit_log_archive (
+    message text NOT NULL,
+    archived_at timestamptz NOT NULL
+) INHERITS (audit_log);
+ALTER TABLE audit_log ADD COLUMN level text;
+ALTER TABLE audit_log RENAME TO log;
+"#;
+        let schema = Schema::from_sql(sql).unwrap();
+
+        let archive = schema.get_table("audit_log_archive").unwrap();
+        assert_eq!(archive.parents, vec!["public.log"]);
+        assert_eq!(archive.partition_bound, None);
+        let names: Vec<_> = archive.column_names().collect();
+        assert_eq!(
+            names,
+            vec!["id", "message", "logged_at", "archived_at", "level"]
+        );
+        // Redeclared columns merge their constraints
+        assert!(!archive.get_column("message").unwrap().nullable);
+        assert!(archive.get_column("logged_at").unwrap().has_default);
+        assert!(archive.get_check("audit_log_message_check").is_some());
+        // Primary keys aren't inherited
+        let id = archive.get_column("id").unwrap();
+        assert!(!id.is_primary_key);
+        assert!(!id.nullable);
+
+        // Inherited columns must keep their type
+        let err = Schema::from_sql(
+            "CREATE TABLE p (a int);
+             CREATE TABLE c (a text) INHERITS (p);",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("inherits type integer"), "{err}");
+
+        let schema = Schema::from_sql(&format!("{sql}\nDROP TABLE log CASCADE;")).unwrap();
+        assert!(!schema.has_table("audit_log_archive"));
+
+        // Children use the sequences of their parent's serial columns, but
+        // don't own them
+        let sql = r#"
+CREATE TABLE jobs (id serial, kind text) PARTITION BY LIST (kind);
+CREATE TABLE jobs_email PARTITION OF jobs FOR VALUES IN ('email');
+CREATE TABLE jobs_sms PARTITION OF jobs FOR VALUES IN ('sms');
+CREATE TABLE jobs_log (note text) INHERITS (jobs);
+ALTER TABLE jobs ADD COLUMN attempt bigserial;
+DROP TABLE jobs_email;
+ALTER TABLE jobs DETACH PARTITION jobs_sms;
+DROP TABLE jobs_sms;
+DROP TABLE jobs_log;
+"#;
+        let schema = Schema::from_sql(sql).unwrap();
+        let mut sequences: Vec<_> = schema.sequence_names().collect();
+        sequences.sort();
+        assert_eq!(
+            sequences,
+            vec!["public.jobs_attempt_seq", "public.jobs_id_seq"]
+        );
+    }
+
+    #[test]
+    fn test_extension_types() {
+        let sql = r#"
+CREATE TABLE before (email citext);
+CREATE SCHEMA extensions;
+CREATE EXTENSION IF NOT EXISTS citext WITH SCHEMA extensions;
+CREATE EXTENSION hstore;
+CREATE TABLE accounts (
+    email extensions.citext NOT NULL,
+    settings hstore,
+    shape geometry
+);
+"#;
+        let schema = Schema::from_sql(sql).unwrap();
+        let mut names: Vec<_> = schema.extension_names().collect();
+        names.sort();
+        assert_eq!(names, vec!["citext", "hstore"]);
+        assert_eq!(
+            schema.get_extension("CITEXT").unwrap().namespace,
+            "extensions"
+        );
+
+        let accounts = schema.get_table("accounts").unwrap();
+        let column_type = |name| accounts.get_column(name).unwrap().data_type.clone();
+        assert_eq!(column_type("email"), PostgresType::Citext);
+        assert_eq!(column_type("settings"), PostgresType::Hstore);
+        // PostGIS isn't installed
+        assert_eq!(
+            column_type("shape"),
+            PostgresType::Custom("geometry".to_string())
+        );
+        // Columns created before the extension keep the unknown type
+        assert_eq!(
+            schema.get_table("before").unwrap().columns[0].data_type,
+            PostgresType::Custom("citext".to_string())
+        );
+
+        // The extension's schema must be named or on the search path
+        let other =
+            Schema::from_sql(&format!("{sql}\nCREATE TABLE t (email public.citext);")).unwrap();
+        assert_eq!(
+            other.get_table("t").unwrap().columns[0].data_type,
+            PostgresType::Custom("public.citext".to_string())
+        );
+        assert_eq!(
+            schema.resolve_type(PostgresType::Custom("citext".to_string())),
+            PostgresType::Custom("citext".to_string())
+        );
+        assert_eq!(
+            schema.extension_function("extensions.nlevel", None),
+            None,
+            "ltree isn't installed"
+        );
+        assert_eq!(
+            schema.extension_function("hstore_to_jsonb", None),
+            Some(PostgresType::Jsonb)
+        );
+
+        let restored = Schema::from_json(&schema.to_json().unwrap()).unwrap();
+        assert_eq!(
+            restored.get_extension("hstore"),
+            schema.get_extension("hstore")
+        );
+
+        let schema = Schema::from_sql(&format!("{sql}\nDROP EXTENSION hstore, citext;")).unwrap();
+        assert_eq!(schema.extension_names().count(), 0);
+        assert!(Schema::from_sql("DROP EXTENSION hstore;").is_err());
+        assert!(Schema::from_sql("DROP EXTENSION IF EXISTS hstore;").is_ok());
+        assert!(matches!(
+            Schema::from_sql("CREATE EXTENSION hstore; CREATE EXTENSION hstore;"),
+            Err(Error::DuplicateDefinition { .. })
+        ));
+    }
+
+    #[test]
+    fn test_type_overrides() {
+        let mut schema = Schema::from_sql(
+            r#"
+CREATE DOMAIN money_cents AS bigint;
+CREATE SCHEMA billing;
+CREATE TABLE users (id uuid PRIMARY KEY, name varchar(100), tags uuid[]);
+CREATE TABLE billing.invoices (
+    id uuid PRIMARY KEY,
+    total money_cents NOT NULL,
+    tax money_cents NOT NULL,
+    position bigint NOT NULL
+);
+"#,
+        )
+        .unwrap();
+        schema
+            .add_type_override("users.ID", "crate::ids::UserId")
+            .unwrap();
+        schema.add_type_override("uuid", "crate::ids::Id").unwrap();
+        schema.add_type_override("money_cents", "Cents").unwrap();
+        schema
+            .add_type_override("billing.invoices.tax", "Tax")
+            .unwrap();
+        schema.add_type_override("varchar", "Name").unwrap();
+
+        let path = |path: &str| RustType::Path(path.to_string());
+        let users = schema.get_table("users").unwrap();
+        let invoices = schema.get_table("billing.invoices").unwrap();
+        let column_type =
+            |table: &Table, name| schema.column_rust_type(table, table.get_column(name).unwrap());
+        // Column overrides win over type overrides
+        assert_eq!(column_type(users, "id"), path("crate::ids::UserId"));
+        assert_eq!(column_type(invoices, "id"), path("crate::ids::Id"));
+        // Lengths don't matter and arrays follow their element type
+        assert_eq!(column_type(users, "name"), path("Name"));
+        assert_eq!(
+            column_type(users, "tags"),
+            RustType::Vec(Box::new(path("crate::ids::Id")))
+        );
+        // Domain overrides only apply to columns declared with the domain
+        assert_eq!(column_type(invoices, "total"), path("Cents"));
+        assert_eq!(column_type(invoices, "tax"), path("Tax"));
+        assert_eq!(column_type(invoices, "position"), RustType::I64);
+        assert_eq!(schema.rust_type(&PostgresType::BigInt), RustType::I64);
+
+        assert!(matches!(
+            schema.add_type_override("users.email", "Email"),
+            Err(Error::UnknownColumn { .. })
+        ));
+        assert!(matches!(
+            schema.add_type_override("not_a_type", "Thing"),
+            Err(Error::UnknownType(_))
+        ));
     }
 }
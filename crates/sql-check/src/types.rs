@@ -1,9 +1,10 @@
 //! Type mappings between PostgreSQL and Rust.
 
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// PostgreSQL data types we support.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PostgresType {
     // Numeric types
     SmallInt,        // int2
@@ -44,9 +45,22 @@ pub enum PostgresType {
     Cidr,
     MacAddr,
 
+    // Extension types, once the schema creates their extension
+    Citext,    // case-insensitive text (citext)
+    Hstore,    // text key/value pairs (hstore)
+    Ltree,     // label path (ltree)
+    Geometry,  // postgis
+    Geography, // postgis
+
     // Arrays (element type)
     Array(Box<PostgresType>),
 
+    // User-defined enum (CREATE TYPE ... AS ENUM), by schema type name
+    Enum(String),
+
+    // User-defined composite (CREATE TYPE ... AS (...)), by schema type name
+    Composite(String),
+
     // Custom/unknown types
     Custom(String),
 }
@@ -140,7 +154,9 @@ impl PostgresType {
 
             PostgresType::Boolean => RustType::Bool,
 
-            PostgresType::Timestamp | PostgresType::TimestampTz => RustType::DateTime,
+            // Postgres only decodes timestamptz into a type with a time zone
+            PostgresType::Timestamp => RustType::LocalDateTime,
+            PostgresType::TimestampTz => RustType::DateTime,
             PostgresType::Date => RustType::Date,
             PostgresType::Time | PostgresType::TimeTz => RustType::Time,
             PostgresType::Interval => RustType::Duration,
@@ -152,11 +168,33 @@ impl PostgresType {
             PostgresType::Inet | PostgresType::Cidr => RustType::IpAddr,
             PostgresType::MacAddr => RustType::String,
 
+            PostgresType::Citext | PostgresType::Ltree => RustType::String,
+            PostgresType::Hstore => RustType::Hstore,
+            // Queries selecting these fail without the `postgis` feature
+            PostgresType::Geometry | PostgresType::Geography => RustType::Geometry,
+
             PostgresType::Array(elem) => RustType::Vec(Box::new(elem.to_rust_type())),
 
+            PostgresType::Enum(name) => RustType::Enum(name.clone()),
+            PostgresType::Composite(name) => RustType::Composite(name.clone()),
+
             PostgresType::Custom(name) => RustType::Custom(name.clone()),
         }
     }
+
+    /// Point references to user-defined type `from` at `to` (used when a type is renamed).
+    pub(crate) fn renamed_type(&self, from: &str, to: &str) -> Self {
+        match self {
+            PostgresType::Enum(name) if name == from => PostgresType::Enum(to.to_string()),
+            PostgresType::Composite(name) if name == from => {
+                PostgresType::Composite(to.to_string())
+            }
+            PostgresType::Array(inner) => {
+                PostgresType::Array(Box::new(inner.renamed_type(from, to)))
+            }
+            other => other.clone(),
+        }
+    }
 }
 
 /// Parse length from types like "varchar(255)" or "char(10)"
@@ -170,7 +208,7 @@ fn parse_length(s: &str) -> Option<u32> {
 }
 
 /// Rust types that we generate for query results.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RustType {
     // Numeric
     I16,
@@ -189,6 +227,8 @@ pub enum RustType {
 
     // Date/Time
     DateTime,
+    // A timestamp without time zone
+    LocalDateTime,
     Date,
     Time,
     Duration,
@@ -202,12 +242,27 @@ pub enum RustType {
     // Network
     IpAddr,
 
+    // hstore
+    Hstore,
+
+    // PostGIS geometry or geography (EWKB)
+    Geometry,
+
     // Collections
     Vec(Box<RustType>),
 
     // Optional wrapper (for nullable columns)
     Option(Box<RustType>),
 
+    // Postgres enum, by schema type name
+    Enum(String),
+
+    // Postgres composite type, by schema type name
+    Composite(String),
+
+    // A type path from a type override (`crate::ids::UserId`)
+    Path(String),
+
     // Custom/unknown
     Custom(String),
 }
@@ -230,16 +285,138 @@ impl RustType {
             RustType::String => "String".to_string(),
             RustType::VecU8 => "Vec<u8>".to_string(),
             RustType::Bool => "bool".to_string(),
+            // The `time` feature switches date/time types from chrono to time
+            RustType::DateTime if cfg!(feature = "time") => "time::OffsetDateTime".to_string(),
+            RustType::LocalDateTime if cfg!(feature = "time") => {
+                "time::PrimitiveDateTime".to_string()
+            }
+            RustType::Date if cfg!(feature = "time") => "time::Date".to_string(),
+            RustType::Time if cfg!(feature = "time") => "time::Time".to_string(),
+            RustType::Duration if cfg!(feature = "time") => "time::Duration".to_string(),
             RustType::DateTime => "chrono::DateTime<chrono::Utc>".to_string(),
+            RustType::LocalDateTime => "chrono::NaiveDateTime".to_string(),
             RustType::Date => "chrono::NaiveDate".to_string(),
             RustType::Time => "chrono::NaiveTime".to_string(),
             RustType::Duration => "chrono::Duration".to_string(),
             RustType::Uuid => "uuid::Uuid".to_string(),
             RustType::JsonValue => "serde_json::Value".to_string(),
             RustType::IpAddr => "std::net::IpAddr".to_string(),
+            RustType::Hstore => "std::collections::HashMap<String, Option<String>>".to_string(),
+            RustType::Geometry => "postgis::ewkb::Geometry".to_string(),
             RustType::Vec(inner) => format!("Vec<{}>", inner.type_path()),
             RustType::Option(inner) => format!("Option<{}>", inner.type_path()),
-            RustType::Custom(name) => name.clone(),
+            RustType::Enum(name) | RustType::Composite(name) => {
+                to_pascal_case(unqualified_name(name))
+            }
+            RustType::Path(path) | RustType::Custom(path) => path.clone(),
+        }
+    }
+}
+
+/// Strip a schema qualifier from a type name (`public.mood` -> `mood`).
+pub fn unqualified_name(name: &str) -> &str {
+    split_qualified_name(name).1
+}
+
+/// Split a name at its last dot outside double quotes, so that
+/// `"my.schema".mood` is `"my.schema"` and `mood`. The qualifier is kept as
+/// written (it may be qualified itself); quotes around the name are removed.
+pub fn split_qualified_name(name: &str) -> (Option<&str>, &str) {
+    let mut quoted = false;
+    let mut split = None;
+    for (idx, c) in name.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '.' if !quoted => split = Some(idx),
+            _ => {}
+        }
+    }
+    match split {
+        Some(idx) => (Some(&name[..idx]), unquote(&name[idx + 1..])),
+        None => (None, unquote(name)),
+    }
+}
+
+/// Quote a name part that contains a dot (`my.schema` -> `"my.schema"`), so
+/// it can be joined with other parts and split again.
+pub fn quote_name_part(part: &str) -> std::borrow::Cow<'_, str> {
+    if part.contains('.') {
+        format!("\"{}\"", part).into()
+    } else {
+        part.into()
+    }
+}
+
+/// Remove the double quotes around a name part.
+pub(crate) fn unquote(part: &str) -> &str {
+    part.strip_prefix('"')
+        .and_then(|part| part.strip_suffix('"'))
+        .unwrap_or(part)
+}
+
+/// Convert a Postgres identifier or enum label to a Rust type/variant name
+/// (`order_status` -> `OrderStatus`, `in-progress` -> `InProgress`).
+///
+/// `self` becomes `Self_`, since `Self` is reserved and can't be a raw identifier.
+pub fn to_pascal_case(name: &str) -> String {
+    let mut out = String::new();
+    for word in name.split(|c: char| !c.is_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            out.extend(first.to_uppercase());
+            out.push_str(chars.as_str());
+        }
+    }
+
+    // Identifiers can't be empty or start with a digit
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, 'V');
+    }
+    if out == "Self" {
+        out.push('_');
+    }
+
+    out
+}
+
+/// Formats the type the way Postgres spells it (`character varying(50)`, `integer[]`).
+impl fmt::Display for PostgresType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PostgresType::SmallInt => f.write_str("smallint"),
+            PostgresType::Integer => f.write_str("integer"),
+            PostgresType::BigInt => f.write_str("bigint"),
+            PostgresType::Real => f.write_str("real"),
+            PostgresType::DoublePrecision => f.write_str("double precision"),
+            PostgresType::Numeric => f.write_str("numeric"),
+            PostgresType::Text => f.write_str("text"),
+            PostgresType::Varchar(None) => f.write_str("character varying"),
+            PostgresType::Varchar(Some(len)) => write!(f, "character varying({len})"),
+            PostgresType::Char(None) => f.write_str("character"),
+            PostgresType::Char(Some(len)) => write!(f, "character({len})"),
+            PostgresType::Bytea => f.write_str("bytea"),
+            PostgresType::Boolean => f.write_str("boolean"),
+            PostgresType::Timestamp => f.write_str("timestamp without time zone"),
+            PostgresType::TimestampTz => f.write_str("timestamp with time zone"),
+            PostgresType::Date => f.write_str("date"),
+            PostgresType::Time => f.write_str("time without time zone"),
+            PostgresType::TimeTz => f.write_str("time with time zone"),
+            PostgresType::Interval => f.write_str("interval"),
+            PostgresType::Uuid => f.write_str("uuid"),
+            PostgresType::Json => f.write_str("json"),
+            PostgresType::Jsonb => f.write_str("jsonb"),
+            PostgresType::Inet => f.write_str("inet"),
+            PostgresType::Cidr => f.write_str("cidr"),
+            PostgresType::MacAddr => f.write_str("macaddr"),
+            PostgresType::Citext => f.write_str("citext"),
+            PostgresType::Hstore => f.write_str("hstore"),
+            PostgresType::Ltree => f.write_str("ltree"),
+            PostgresType::Geometry => f.write_str("geometry"),
+            PostgresType::Geography => f.write_str("geography"),
+            PostgresType::Array(element) => write!(f, "{element}[]"),
+            PostgresType::Enum(name)
+            | PostgresType::Composite(name)
+            | PostgresType::Custom(name) => f.write_str(name),
         }
     }
 }
@@ -300,5 +477,94 @@ mod tests {
             RustType::Option(Box::new(RustType::String)).type_path(),
             "Option<String>"
         );
+        assert_eq!(
+            RustType::Option(Box::new(RustType::Path("crate::ids::UserId".into()))).type_path(),
+            "Option<crate::ids::UserId>"
+        );
+    }
+
+    #[test]
+    fn test_enum_type_path() {
+        assert_eq!(
+            RustType::Enum("public.order_status".to_string()).type_path(),
+            "OrderStatus"
+        );
+        assert_eq!(to_pascal_case("mood"), "Mood");
+        assert_eq!(to_pascal_case("in-progress"), "InProgress");
+        assert_eq!(to_pascal_case("waiting on customer"), "WaitingOnCustomer");
+        assert_eq!(to_pascal_case("1st"), "V1st");
+        assert_eq!(to_pascal_case("self"), "Self_");
+        assert_eq!(unqualified_name("\"my.schema\".mood"), "mood");
+        assert_eq!(unqualified_name("public.\"a.b\""), "a.b");
+        assert_eq!(unqualified_name("\"my.schema\""), "my.schema");
+        assert_eq!(
+            split_qualified_name("\"my.schema\".mood"),
+            (Some("\"my.schema\""), "mood")
+        );
+        assert_eq!(split_qualified_name("mood"), (None, "mood"));
+        assert_eq!(to_pascal_case("SELF"), "SELF");
+    }
+
+    #[test]
+    fn test_composite_type_path() {
+        let ty = PostgresType::Composite("public.postal_address".to_string());
+        assert_eq!(ty.to_rust_type().type_path(), "PostalAddress");
+        assert_eq!(
+            ty.to_rust_type().nullable().type_path(),
+            "Option<PostalAddress>"
+        );
+    }
+
+    #[test]
+    fn test_date_time_backend() {
+        let path = |ty: PostgresType| ty.to_rust_type().type_path();
+        if cfg!(feature = "time") {
+            assert_eq!(path(PostgresType::TimestampTz), "time::OffsetDateTime");
+            assert_eq!(path(PostgresType::Timestamp), "time::PrimitiveDateTime");
+            assert_eq!(path(PostgresType::Date), "time::Date");
+            assert_eq!(path(PostgresType::Time), "time::Time");
+            assert_eq!(path(PostgresType::Interval), "time::Duration");
+        } else {
+            assert_eq!(
+                path(PostgresType::TimestampTz),
+                "chrono::DateTime<chrono::Utc>"
+            );
+            assert_eq!(path(PostgresType::Timestamp), "chrono::NaiveDateTime");
+            assert_eq!(path(PostgresType::Date), "chrono::NaiveDate");
+            assert_eq!(path(PostgresType::Time), "chrono::NaiveTime");
+            assert_eq!(path(PostgresType::Interval), "chrono::Duration");
+        }
+    }
+
+    #[test]
+    fn test_extension_type_mapping() {
+        assert_eq!(PostgresType::Citext.to_rust_type(), RustType::String);
+        assert_eq!(PostgresType::Ltree.to_rust_type(), RustType::String);
+        assert_eq!(
+            PostgresType::Hstore.to_rust_type().type_path(),
+            "std::collections::HashMap<String, Option<String>>"
+        );
+        assert_eq!(
+            PostgresType::Geography.to_rust_type().type_path(),
+            "postgis::ewkb::Geometry"
+        );
+    }
+
+    #[test]
+    fn test_postgres_type_display() {
+        for name in [
+            "integer",
+            "double precision",
+            "character varying(50)",
+            "character",
+            "timestamp with time zone",
+            "uuid[]",
+        ] {
+            assert_eq!(PostgresType::from_sql_name(name).to_string(), name);
+        }
+        assert_eq!(
+            PostgresType::Enum("public.mood".to_string()).to_string(),
+            "public.mood"
+        );
     }
 }
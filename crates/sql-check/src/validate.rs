@@ -1,15 +1,21 @@
 //! Query validation against a schema.
 
 use crate::error::{Error, Result};
-use crate::schema::Schema;
-use crate::types::RustType;
+use crate::schema::{
+    literal_value, object_name_to_string, strip_casts, Column, Function, FunctionReturn, Schema,
+    Table,
+};
+use crate::types::{unqualified_name, PostgresType, RustType};
+use serde::{Deserialize, Serialize};
 use sqlparser::ast::{
     AssignmentTarget, BinaryOperator, Delete, Expr, FromTable, FunctionArg, FunctionArgExpr,
-    FunctionArguments, JoinOperator, Query, Select, SelectItem, SetExpr, SetOperator, Statement,
-    TableFactor, TableWithJoins, Update, Value,
+    FunctionArguments, JoinOperator, ObjectName, Query, Select, SelectItem, SetExpr, SetOperator,
+    Statement, TableAlias, TableFactor, TableWithJoins, Update, Value,
 };
 use sqlparser::dialect::PostgreSqlDialect;
 use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, TokenWithSpan, Tokenizer};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 
 /// Result of validating a query - contains the inferred column types.
@@ -19,17 +25,26 @@ pub struct QueryResult {
 }
 
 /// A column in the query result.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryColumn {
     pub name: String,
     pub rust_type: RustType,
+    /// The `COMMENT ON COLUMN` text of the table column this selects, if any.
+    pub comment: Option<String>,
+    /// The table column (`public.users.id`) this selects unchanged, if any,
+    /// so the overrides of the column and its type also apply through views.
+    #[serde(default)]
+    pub source: Option<String>,
 }
 
 /// Validate a query against a schema and return the inferred types.
 pub fn validate_query(schema: &Schema, sql: &str) -> Result<QueryResult> {
     let dialect = PostgreSqlDialect {};
-    let statements =
-        Parser::parse_sql(&dialect, sql).map_err(|e| Error::QueryParse(e.to_string()))?;
+    let (tokens, overriding) = tokenize_query(&dialect, sql)?;
+    let statements = Parser::new(&dialect)
+        .with_tokens_with_locations(tokens)
+        .parse_statements()
+        .map_err(|e| Error::QueryParse(e.to_string()))?;
 
     if statements.len() != 1 {
         return Err(Error::InvalidQuery(
@@ -37,15 +52,83 @@ pub fn validate_query(schema: &Schema, sql: &str) -> Result<QueryResult> {
         ));
     }
 
-    match &statements[0] {
-        Statement::Query(query) => validate_select(schema, query),
-        Statement::Insert(insert) => validate_insert(schema, insert),
-        Statement::Update(update) => validate_update(schema, update),
-        Statement::Delete(delete) => validate_delete(schema, delete),
+    let result = match &statements[0] {
+        Statement::Query(query) if overriding.is_none() => validate_select(schema, query),
+        Statement::Insert(insert) => validate_insert(schema, insert, overriding),
+        Statement::Update(update) if overriding.is_none() => validate_update(schema, update),
+        Statement::Delete(delete) if overriding.is_none() => validate_delete(schema, delete),
+        _ if overriding.is_some() => Err(Error::InvalidQuery(
+            "OVERRIDING ... VALUE is only valid in INSERT".to_string(),
+        )),
         _ => Err(Error::InvalidQuery(
             "Only SELECT, INSERT, UPDATE, and DELETE are supported".to_string(),
         )),
+    }?;
+
+    // PostGIS values are only mapped to Rust with the `postgis` feature
+    if !cfg!(feature = "postgis") {
+        if let Some(column) = result.columns.iter().find(|c| is_geometry(&c.rust_type)) {
+            return Err(Error::InvalidQuery(format!(
+                "Column '{}' has a PostGIS geometry or geography type: enable the postgis feature to map it to postgis::ewkb::Geometry",
+                column.name
+            )));
+        }
+    }
+    Ok(result)
+}
+
+/// Whether a type is (an optional or array of) a PostGIS geometry.
+fn is_geometry(rust_type: &RustType) -> bool {
+    match rust_type {
+        RustType::Geometry => true,
+        RustType::Option(inner) | RustType::Vec(inner) => is_geometry(inner),
+        _ => false,
+    }
+}
+
+/// The `OVERRIDING { SYSTEM | USER } VALUE` clause of an INSERT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Overriding {
+    System,
+    User,
+}
+
+/// Tokenize a query, taking out an `OVERRIDING { SYSTEM | USER } VALUE` clause.
+///
+/// sqlparser can't parse the clause, and all it changes for us is whether an
+/// INSERT may write GENERATED ALWAYS identity columns.
+fn tokenize_query(
+    dialect: &PostgreSqlDialect,
+    sql: &str,
+) -> Result<(Vec<TokenWithSpan>, Option<Overriding>)> {
+    let mut tokens = Tokenizer::new(dialect, sql)
+        .tokenize_with_location()
+        .map_err(|e| Error::QueryParse(e.to_string()))?;
+
+    // Positions and (unquoted) words of all non-whitespace tokens
+    let words: Vec<(usize, Option<String>)> = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| !matches!(t.token, Token::Whitespace(_)))
+        .map(|(idx, t)| match &t.token {
+            Token::Word(word) if word.quote_style.is_none() => {
+                (idx, Some(word.value.to_uppercase()))
+            }
+            _ => (idx, None),
+        })
+        .collect();
+
+    for window in words.windows(3) {
+        let overriding = match [&window[0].1, &window[1].1, &window[2].1].map(Option::as_deref) {
+            [Some("OVERRIDING"), Some("SYSTEM"), Some("VALUE")] => Overriding::System,
+            [Some("OVERRIDING"), Some("USER"), Some("VALUE")] => Overriding::User,
+            _ => continue,
+        };
+        tokens.drain(window[0].0..=window[2].0);
+        return Ok((tokens, Some(overriding)));
     }
+
+    Ok((tokens, None))
 }
 
 /// A CTE (Common Table Expression) definition with its column types.
@@ -81,6 +164,17 @@ impl ResolveContext {
         }
     }
 
+    /// The function whose columns unqualified names of no other table
+    /// refer to: the only function in FROM the schema doesn't define.
+    fn unknown_function(&self) -> Option<&str> {
+        let mut functions = self
+            .table_aliases
+            .values()
+            .filter_map(|table_ref| table_ref.strip_prefix("_function:"));
+        let function = functions.next()?;
+        functions.next().is_none().then_some(function)
+    }
+
     fn get_cte(&self, name: &str) -> Option<&CteDefinition> {
         self.cte_definitions.get(&name.to_lowercase())
     }
@@ -89,10 +183,24 @@ impl ResolveContext {
         self.cte_definitions
             .insert(name.to_lowercase(), CteDefinition { columns });
     }
+
+    /// Register a FROM item's alias; like Postgres, an alias can only be used once.
+    fn add_alias(&mut self, alias: &str, target: String) -> Result<()> {
+        match self.table_aliases.entry(alias.to_lowercase()) {
+            Entry::Occupied(_) => Err(Error::InvalidQuery(format!(
+                "Table name \"{}\" specified more than once",
+                alias
+            ))),
+            Entry::Vacant(entry) => {
+                entry.insert(target);
+                Ok(())
+            }
+        }
+    }
 }
 
 /// Validate a SELECT query.
-fn validate_select(schema: &Schema, query: &Query) -> Result<QueryResult> {
+pub(crate) fn validate_select(schema: &Schema, query: &Query) -> Result<QueryResult> {
     // First, process CTEs if present
     let mut ctx = ResolveContext::default();
 
@@ -187,6 +295,10 @@ fn validate_select_body_with_ctx(
         resolve_table_refs(schema, table_with_joins, &mut ctx)?;
     }
 
+    if let Some(selection) = &select.selection {
+        check_literal_values(schema, &ctx, selection)?;
+    }
+
     // Then validate and infer types for each selected item
     let mut columns = Vec::new();
 
@@ -194,13 +306,24 @@ fn validate_select_body_with_ctx(
         match item {
             SelectItem::UnnamedExpr(expr) => {
                 let (name, rust_type) = infer_expr_type(schema, &ctx, expr)?;
-                columns.push(QueryColumn { name, rust_type });
+                let comment = column_comment(schema, &ctx, expr);
+                let source = column_source(schema, &ctx, expr);
+                columns.push(QueryColumn {
+                    name,
+                    rust_type,
+                    comment,
+                    source,
+                });
             }
             SelectItem::ExprWithAlias { expr, alias } => {
                 let (_, rust_type) = infer_expr_type(schema, &ctx, expr)?;
+                let comment = column_comment(schema, &ctx, expr);
+                let source = column_source(schema, &ctx, expr);
                 columns.push(QueryColumn {
                     name: alias.value.clone(),
                     rust_type,
+                    comment,
+                    source,
                 });
             }
             SelectItem::Wildcard(_) => {
@@ -217,18 +340,24 @@ fn validate_select_body_with_ctx(
                                 columns.push(QueryColumn {
                                     name: cte_col.name.clone(),
                                     rust_type,
+                                    comment: cte_col.comment.clone(),
+                                    source: cte_col.source.clone(),
                                 });
                             }
                         }
+                    } else if let Some(function) = table_ref.strip_prefix("_function:") {
+                        columns.push(unknown_function_column(alias, function));
                     } else if let Some(table) = schema.get_table(table_ref) {
                         for col in &table.columns {
-                            let mut rust_type = col.data_type.to_rust_type();
+                            let mut rust_type = schema.column_rust_type(table, col);
                             if col.nullable || ctx.is_nullable_table(alias) {
                                 rust_type = rust_type.nullable();
                             }
                             columns.push(QueryColumn {
                                 name: col.name.clone(),
                                 rust_type,
+                                comment: col.comment.clone(),
+                                source: Some(table.column_key(&col.name)),
                             });
                         }
                     }
@@ -240,7 +369,7 @@ fn validate_select_body_with_ctx(
                 let table_alias = match kind {
                     SelectItemQualifiedWildcardKind::ObjectName(obj_name) => obj_name
                         .0
-                        .first()
+                        .last()
                         .and_then(|part| part.as_ident())
                         .map(|i| i.value.clone())
                         .ok_or_else(|| {
@@ -259,7 +388,9 @@ fn validate_select_body_with_ctx(
                     .ok_or_else(|| Error::UnknownTable(table_alias.clone()))?;
 
                 // Check if this is a CTE reference
-                if let Some(cte_name) = table_ref.strip_prefix("_cte:") {
+                if let Some(function) = table_ref.strip_prefix("_function:") {
+                    columns.push(unknown_function_column(&table_alias, function));
+                } else if let Some(cte_name) = table_ref.strip_prefix("_cte:") {
                     let cte = ctx
                         .get_cte(cte_name)
                         .ok_or_else(|| Error::UnknownTable(cte_name.to_string()))?;
@@ -272,6 +403,8 @@ fn validate_select_body_with_ctx(
                         columns.push(QueryColumn {
                             name: cte_col.name.clone(),
                             rust_type,
+                            comment: cte_col.comment.clone(),
+                            source: cte_col.source.clone(),
                         });
                     }
                 } else {
@@ -280,13 +413,15 @@ fn validate_select_body_with_ctx(
                         .ok_or_else(|| Error::UnknownTable(table_ref.clone()))?;
 
                     for col in &table.columns {
-                        let mut rust_type = col.data_type.to_rust_type();
+                        let mut rust_type = schema.column_rust_type(table, col);
                         if col.nullable || ctx.is_nullable_table(&table_alias) {
                             rust_type = rust_type.nullable();
                         }
                         columns.push(QueryColumn {
                             name: col.name.clone(),
                             rust_type,
+                            comment: col.comment.clone(),
+                            source: Some(table.column_key(&col.name)),
                         });
                     }
                 }
@@ -363,7 +498,16 @@ fn get_table_alias(factor: &TableFactor) -> Option<String> {
                     .map(|i| i.value.clone())
             })
         }
+        TableFactor::Function { name, alias, .. } => alias
+            .as_ref()
+            .map(|a| a.name.value.clone())
+            .or_else(|| Some(unqualified_name(&object_name_to_string(name)).to_string())),
         TableFactor::Derived { alias: Some(a), .. } => Some(a.name.value.clone()),
+        TableFactor::UNNEST { alias, .. } => Some(
+            alias
+                .as_ref()
+                .map_or_else(|| "unnest".to_string(), |a| a.name.value.clone()),
+        ),
         _ => None,
     }
 }
@@ -375,54 +519,632 @@ fn resolve_table_factor(
     ctx: &mut ResolveContext,
 ) -> Result<()> {
     match factor {
+        TableFactor::Table {
+            name,
+            alias,
+            args: Some(args),
+            ..
+        } => {
+            // `FROM f(...)`
+            resolve_function_factor(schema, ctx, name, &args.args, alias.as_ref())?;
+        }
+        TableFactor::Function {
+            name, args, alias, ..
+        } => {
+            // `FROM LATERAL f(...)`
+            resolve_function_factor(schema, ctx, name, args, alias.as_ref())?;
+        }
         TableFactor::Table { name, alias, .. } => {
-            let table_name = name
-                .0
-                .last()
-                .and_then(|part| part.as_ident())
-                .map(|i| i.value.clone())
-                .ok_or_else(|| Error::InvalidQuery("Empty table name".to_string()))?;
+            // Possibly schema-qualified (`billing.invoices`)
+            let table_name = object_name_to_string(name);
+            if table_name.is_empty() {
+                return Err(Error::InvalidQuery("Empty table name".to_string()));
+            }
 
-            // Use alias if provided, otherwise use table name
+            // Use alias if provided, otherwise the unqualified table name
             let alias_name = alias
                 .as_ref()
                 .map(|a| a.name.value.clone())
-                .unwrap_or_else(|| table_name.clone());
+                .unwrap_or_else(|| unqualified_name(&table_name).to_string());
+
+            // Views behave like CTEs defined by the schema; a CTE of the same
+            // name shadows them (CTE names are never qualified)
+            let is_cte = name.0.len() == 1 && ctx.get_cte(&table_name).is_some();
+            if !is_cte {
+                if let Some(view) = schema.get_view(&table_name) {
+                    if let Some(reason) = &view.error {
+                        return Err(Error::View {
+                            name: view.qualified_name(),
+                            reason: reason.clone(),
+                        });
+                    }
+                    // Overrides set after the view was typed still apply to
+                    // the table columns it selects
+                    let columns = view
+                        .columns
+                        .iter()
+                        .map(|col| {
+                            let overridden = col
+                                .source
+                                .as_deref()
+                                .and_then(|key| schema.source_rust_type(key));
+                            match overridden {
+                                Some(rust_type) => QueryColumn {
+                                    rust_type: match col.rust_type {
+                                        RustType::Option(_) => rust_type.nullable(),
+                                        _ => rust_type,
+                                    },
+                                    ..col.clone()
+                                },
+                                None => col.clone(),
+                            }
+                        })
+                        .collect();
+                    ctx.add_cte(table_name.clone(), columns);
+                }
+            }
 
             // Check if this is a CTE reference first
             if ctx.get_cte(&table_name).is_some() {
                 // It's a CTE - use the special marker "_cte:<name>"
-                ctx.table_aliases.insert(
-                    alias_name.to_lowercase(),
-                    format!("_cte:{}", table_name.to_lowercase()),
-                );
+                ctx.add_alias(&alias_name, format!("_cte:{}", table_name.to_lowercase()))?;
             } else {
-                // Not a CTE - verify table exists in schema
-                if !schema.has_table(&table_name) {
-                    return Err(Error::UnknownTable(table_name));
-                }
+                // Not a CTE - resolve the table through the search path
+                let table = schema
+                    .get_table(&table_name)
+                    .ok_or_else(|| Error::UnknownTable(table_name.clone()))?;
 
-                ctx.table_aliases
-                    .insert(alias_name.to_lowercase(), table_name.clone());
+                ctx.add_alias(&alias_name, table.qualified_name())?;
             }
         }
         TableFactor::Derived { alias: Some(a), .. } => {
             // Subquery - for now, just track the alias
             // We can't easily resolve subquery columns, so mark as custom
-            ctx.table_aliases
-                .insert(a.name.value.to_lowercase(), "_subquery".to_string());
+            ctx.add_alias(&a.name.value, "_subquery".to_string())?;
         }
         TableFactor::Derived { alias: None, .. } => {
             // Subquery without alias - nothing to track
         }
+        TableFactor::UNNEST {
+            alias,
+            array_exprs,
+            with_ordinality,
+            ..
+        } => {
+            // `FROM unnest(a, b) WITH ORDINALITY`
+            let alias_name = alias
+                .as_ref()
+                .map_or_else(|| "unnest".to_string(), |a| a.name.value.clone());
+            let args: Vec<_> = array_exprs.iter().collect();
+            let Some(mut columns) = builtin_function_columns(schema, ctx, "unnest", &args)? else {
+                return ctx.add_alias(&alias_name, "_function:unnest".to_string());
+            };
+            if *with_ordinality {
+                columns.push(QueryColumn {
+                    name: "ordinality".to_string(),
+                    rust_type: RustType::I64,
+                    comment: None,
+                    source: None,
+                });
+            }
+            add_function_columns(ctx, alias_name, alias.as_ref(), columns)?;
+        }
+        TableFactor::NestedJoin {
+            table_with_joins, ..
+        } => {
+            // Parenthesized join, as pg_dump writes every join in a view body
+            resolve_table_refs(schema, table_with_joins, ctx)?;
+        }
         _ => {
-            // Other table factors (UNNEST, etc.) - skip for now
+            // Other table factors (JSON_TABLE, etc.) - skip for now
         }
     }
 
     Ok(())
 }
 
+/// Resolve a function call in FROM, registering its result columns like a CTE.
+///
+/// Built-in set-returning functions (`generate_series`, `unnest`,
+/// `jsonb_each`, ...) get their real columns. Other functions the schema
+/// doesn't define are registered by name, and any column of theirs a query
+/// refers to is of an unknown type.
+fn resolve_function_factor(
+    schema: &Schema,
+    ctx: &mut ResolveContext,
+    name: &ObjectName,
+    args: &[FunctionArg],
+    alias: Option<&TableAlias>,
+) -> Result<()> {
+    let alias_name = alias
+        .map(|a| a.name.value.clone())
+        .unwrap_or_else(|| unqualified_name(&object_name_to_string(name)).to_string());
+    let args = call_args(args);
+    let Some(function) = resolve_function(schema, ctx, name, &args)? else {
+        let function_name = unqualified_name(&object_name_to_string(name)).to_lowercase();
+        let Some(columns) = builtin_function_columns(schema, ctx, &function_name, &args)? else {
+            return ctx.add_alias(&alias_name, format!("_function:{function_name}"));
+        };
+        return add_function_columns(ctx, alias_name, alias, columns);
+    };
+
+    let columns = match &function.returns {
+        // Result columns can't be declared NOT NULL
+        FunctionReturn::Table(fields) => fields
+            .iter()
+            .map(|field| QueryColumn {
+                name: field.name.clone(),
+                rust_type: schema.rust_type(&field.data_type).nullable(),
+                comment: None,
+                source: None,
+            })
+            .collect(),
+        // A row type expands into its columns. A single row comes back all
+        // NULL when the function finds nothing, so none of them is NOT NULL.
+        FunctionReturn::Scalar(data_type) => match row_columns(schema, data_type) {
+            Some(columns) => columns
+                .into_iter()
+                .map(|column| QueryColumn {
+                    rust_type: match column.rust_type {
+                        RustType::Option(_) => column.rust_type,
+                        rust_type => rust_type.nullable(),
+                    },
+                    ..column
+                })
+                .collect(),
+            None => vec![function_column(schema, ctx, function, &args, &alias_name)?],
+        },
+        FunctionReturn::SetOf(data_type) => match row_columns(schema, data_type) {
+            Some(columns) => columns,
+            None => vec![function_column(schema, ctx, function, &args, &alias_name)?],
+        },
+    };
+
+    add_function_columns(ctx, alias_name, alias, columns)
+}
+
+/// The single column of a function in FROM returning a scalar or a set of
+/// them, named after the alias.
+fn function_column(
+    schema: &Schema,
+    ctx: &ResolveContext,
+    function: &Function,
+    args: &[&Expr],
+    alias_name: &str,
+) -> Result<QueryColumn> {
+    Ok(QueryColumn {
+        name: alias_name.to_string(),
+        rust_type: function_call_type(schema, ctx, function, args)?,
+        comment: None,
+        source: None,
+    })
+}
+
+/// Register the result columns of a function in FROM under its alias.
+///
+/// Columns with an empty name are named after the alias.
+fn add_function_columns(
+    ctx: &mut ResolveContext,
+    alias_name: String,
+    alias: Option<&TableAlias>,
+    mut columns: Vec<QueryColumn>,
+) -> Result<()> {
+    for column in columns.iter_mut().filter(|column| column.name.is_empty()) {
+        column.name = alias_name.clone();
+    }
+    // `f(...) AS t (a, b)` renames the leading columns
+    if let Some(alias) = alias {
+        for (column, name) in columns.iter_mut().zip(&alias.columns) {
+            column.name = name.name.value.clone();
+        }
+    }
+
+    ctx.add_cte(alias_name.clone(), columns);
+    ctx.add_alias(&alias_name, format!("_cte:{}", alias_name.to_lowercase()))
+}
+
+/// The result columns of a built-in set-returning function.
+///
+/// Columns with an empty name are named after the alias (or the function
+/// without one), as Postgres does for functions returning a single value.
+fn builtin_function_columns(
+    schema: &Schema,
+    ctx: &ResolveContext,
+    name: &str,
+    args: &[&Expr],
+) -> Result<Option<Vec<QueryColumn>>> {
+    let column = |name: &str, rust_type: RustType| QueryColumn {
+        name: name.to_string(),
+        rust_type,
+        comment: None,
+        source: None,
+    };
+    let arg_type = |idx: usize| -> Result<Option<RustType>> {
+        args.get(idx)
+            .map(|arg| infer_expr_type(schema, ctx, arg).map(|(_, ty)| ty))
+            .transpose()
+    };
+
+    let columns = match name {
+        "generate_series" => {
+            // Each value has the type of the bounds, the wider of the two for numbers
+            let mut bounds = Vec::new();
+            for arg in args.iter().take(2) {
+                bounds.push(match arg {
+                    Expr::Value(value) => match &value.value {
+                        Value::Number(n, _) if n.parse::<i32>().is_ok() => RustType::I32,
+                        Value::Number(n, _) if n.parse::<i64>().is_ok() => RustType::I64,
+                        Value::Number(_, _) => RustType::Decimal,
+                        _ => return Ok(None),
+                    },
+                    arg => match infer_expr_type(schema, ctx, arg)?.1 {
+                        RustType::Option(inner) => *inner,
+                        rust_type => rust_type,
+                    },
+                });
+            }
+            let widest = [RustType::Decimal, RustType::I64]
+                .into_iter()
+                .find(|wide| bounds.contains(wide));
+            match widest.or_else(|| bounds.into_iter().next()) {
+                Some(rust_type) => vec![column("", rust_type)],
+                None => return Ok(None),
+            }
+        }
+        "unnest" => {
+            // A column per array, named after the function if there are
+            // several; array elements can be NULL
+            let name = if args.len() == 1 { "" } else { "unnest" };
+            let mut columns = Vec::new();
+            for idx in 0..args.len() {
+                let element = match arg_type(idx)? {
+                    Some(RustType::Vec(element)) => *element,
+                    Some(RustType::Option(inner)) => match *inner {
+                        RustType::Vec(element) => *element,
+                        _ => return Ok(None),
+                    },
+                    _ => return Ok(None),
+                };
+                let element = match element {
+                    RustType::Option(_) => element,
+                    element => element.nullable(),
+                };
+                columns.push(column(name, element));
+            }
+            columns
+        }
+        "jsonb_each" | "json_each" => vec![
+            column("key", RustType::String),
+            column("value", RustType::JsonValue),
+        ],
+        "jsonb_each_text" | "json_each_text" => vec![
+            column("key", RustType::String),
+            column("value", RustType::String.nullable()),
+        ],
+        "jsonb_array_elements" | "json_array_elements" => {
+            vec![column("value", RustType::JsonValue)]
+        }
+        "jsonb_array_elements_text" | "json_array_elements_text" => {
+            vec![column("value", RustType::String.nullable())]
+        }
+        "jsonb_object_keys" | "json_object_keys" => vec![column("", RustType::String)],
+        _ => return Ok(None),
+    };
+    Ok(Some(columns))
+}
+
+/// The type of a column of a function in FROM the schema doesn't define.
+fn unknown_function_type(function: &str) -> RustType {
+    RustType::Custom(function.to_string()).nullable()
+}
+
+/// The column `*` expands to for a function in FROM the schema doesn't
+/// define: a single value named after the alias, as most such functions return.
+fn unknown_function_column(alias: &str, function: &str) -> QueryColumn {
+    QueryColumn {
+        name: alias.to_string(),
+        rust_type: unknown_function_type(function),
+        comment: None,
+        source: None,
+    }
+}
+
+/// The columns of a row type: a table's rows or a composite type.
+fn row_columns(schema: &Schema, data_type: &PostgresType) -> Option<Vec<QueryColumn>> {
+    match data_type {
+        PostgresType::Custom(name) => schema.get_table(name).map(|table| {
+            table
+                .columns
+                .iter()
+                .map(|col| {
+                    let rust_type = schema.column_rust_type(table, col);
+                    QueryColumn {
+                        name: col.name.clone(),
+                        rust_type: if col.nullable {
+                            rust_type.nullable()
+                        } else {
+                            rust_type
+                        },
+                        comment: col.comment.clone(),
+                        source: Some(table.column_key(&col.name)),
+                    }
+                })
+                .collect()
+        }),
+        PostgresType::Composite(name) => schema.get_composite(name).map(|composite| {
+            composite
+                .fields
+                .iter()
+                .map(|field| QueryColumn {
+                    name: field.name.clone(),
+                    rust_type: schema.rust_type(&field.data_type).nullable(),
+                    comment: None,
+                    source: None,
+                })
+                .collect()
+        }),
+        _ => None,
+    }
+}
+
+/// Resolve a call to a function defined in the schema.
+///
+/// Returns `None` for functions the schema doesn't define (built-ins and
+/// extensions), and errors if no overload takes that many arguments.
+/// Overloads taking the same number of arguments are told apart by the
+/// argument types, roughly as Postgres does: exact matches win, then an
+/// untyped literal or parameter prefers a text argument. A call that still
+/// matches overloads returning different types is ambiguous.
+fn resolve_function<'a>(
+    schema: &'a Schema,
+    ctx: &ResolveContext,
+    name: &ObjectName,
+    args: &[&Expr],
+) -> Result<Option<&'a Function>> {
+    let name = object_name_to_string(name);
+    let overloads = schema.get_functions(&name);
+    if overloads.is_empty() {
+        return Ok(None);
+    }
+
+    let candidates: Vec<_> = overloads
+        .iter()
+        .filter(|function| function.accepts(args.len()))
+        .collect();
+    match candidates.as_slice() {
+        [] => {
+            return Err(Error::WrongArgumentCount {
+                function: name,
+                count: args.len(),
+            })
+        }
+        [function] => return Ok(Some(function)),
+        _ => {}
+    }
+
+    let arg_types = args
+        .iter()
+        .map(|arg| call_arg_type(schema, ctx, arg))
+        .collect::<Result<Vec<_>>>()?;
+    let scored: Vec<_> = candidates
+        .into_iter()
+        .filter_map(|function| overload_score(schema, function, &arg_types).map(|s| (s, function)))
+        .collect();
+    let Some(best) = scored.iter().map(|(score, _)| *score).max() else {
+        return Err(Error::NoMatchingFunction(name));
+    };
+    let mut matches = scored
+        .into_iter()
+        .filter(|(score, _)| *score == best)
+        .map(|(_, function)| function);
+    let function = matches
+        .next()
+        .expect("the best score belongs to an overload");
+    if matches.any(|other| other.returns != function.returns) {
+        return Err(Error::AmbiguousFunction(name));
+    }
+    Ok(Some(function))
+}
+
+/// What overload resolution knows about the type of a call argument.
+enum CallArgType {
+    /// An expression of a known type.
+    Known(RustType),
+    /// A string literal, NULL or parameter, whose type Postgres infers from
+    /// the function.
+    Unknown,
+    /// An expression whose type can't be inferred precisely enough.
+    Uninferred,
+}
+
+/// The type of a call argument, for overload resolution.
+fn call_arg_type(schema: &Schema, ctx: &ResolveContext, expr: &Expr) -> Result<CallArgType> {
+    let rust_type = match expr {
+        Expr::Value(value) => match &value.value {
+            Value::Number(n, _) if n.parse::<i32>().is_ok() => RustType::I32,
+            Value::Number(n, _) if n.parse::<i64>().is_ok() => RustType::I64,
+            Value::Number(_, _) => RustType::Decimal,
+            Value::Boolean(_) => RustType::Bool,
+            Value::SingleQuotedString(_) | Value::Null | Value::Placeholder(_) => {
+                return Ok(CallArgType::Unknown)
+            }
+            _ => return Ok(CallArgType::Uninferred),
+        },
+        Expr::Nested(inner) => return call_arg_type(schema, ctx, inner),
+        Expr::Identifier(_) | Expr::CompoundIdentifier(_) | Expr::Cast { .. } => {
+            match infer_expr_type(schema, ctx, expr)?.1 {
+                RustType::Option(inner) => *inner,
+                rust_type => rust_type,
+            }
+        }
+        _ => return Ok(CallArgType::Uninferred),
+    };
+    Ok(CallArgType::Known(rust_type))
+}
+
+/// How well a call's arguments match an overload: the number of exact
+/// matches, then the number of untyped arguments going to a text argument.
+/// `None` if an argument can't be converted implicitly.
+fn overload_score(
+    schema: &Schema,
+    function: &Function,
+    arg_types: &[CallArgType],
+) -> Option<(usize, usize)> {
+    let mut score = (0, 0);
+    for (idx, arg_type) in arg_types.iter().enumerate() {
+        let param = function.args.get(idx).or(function.args.last())?;
+        let param_type = match &param.data_type {
+            PostgresType::Array(element) if param.variadic => schema.rust_type(element),
+            data_type => schema.rust_type(data_type),
+        };
+        match arg_type {
+            CallArgType::Known(rust_type) if *rust_type == param_type => score.0 += 1,
+            CallArgType::Known(rust_type) if !implicitly_casts(rust_type, &param_type) => {
+                return None
+            }
+            CallArgType::Unknown if param_type == RustType::String => score.1 += 1,
+            _ => {}
+        }
+    }
+    Some(score)
+}
+
+/// Whether Postgres may convert a value of one type to another when calling
+/// a function: numbers widen, and date/time types convert among themselves.
+///
+/// Types outside these built-in categories (enums, domains, arrays, ...) are
+/// assumed to convert, so only calls that are certainly wrong are rejected.
+fn implicitly_casts(from: &RustType, to: &RustType) -> bool {
+    // Numbers in the order they implicitly widen
+    const NUMBERS: [RustType; 6] = [
+        RustType::I16,
+        RustType::I32,
+        RustType::I64,
+        RustType::Decimal,
+        RustType::F32,
+        RustType::F64,
+    ];
+    let category = |rust_type: &RustType| match rust_type {
+        RustType::I16
+        | RustType::I32
+        | RustType::I64
+        | RustType::Decimal
+        | RustType::F32
+        | RustType::F64 => Some(0),
+        RustType::DateTime | RustType::LocalDateTime | RustType::Date | RustType::Time => Some(1),
+        RustType::String => Some(2),
+        RustType::Bool => Some(3),
+        RustType::Uuid => Some(4),
+        RustType::JsonValue => Some(5),
+        RustType::VecU8 => Some(6),
+        RustType::IpAddr => Some(7),
+        RustType::Duration => Some(8),
+        _ => None,
+    };
+    match (category(from), category(to)) {
+        (Some(0), Some(0)) => {
+            let rank = |rust_type| NUMBERS.iter().position(|n| n == rust_type);
+            rank(from) <= rank(to)
+        }
+        (Some(a), Some(b)) => a == b,
+        _ => true,
+    }
+}
+
+/// Infer the type of a call to a function defined in the schema.
+///
+/// Any function can return NULL, unless it's STRICT and none of its arguments
+/// are nullable. Set-returning functions yield their element type.
+///
+/// Functions returning rows (`RETURNS TABLE`, OUT arguments or a table's row
+/// type) produce a record no Rust type maps to, so they must be called in FROM.
+fn function_call_type(
+    schema: &Schema,
+    ctx: &ResolveContext,
+    function: &Function,
+    args: &[&Expr],
+) -> Result<RustType> {
+    let data_type = match &function.returns {
+        FunctionReturn::Scalar(data_type) | FunctionReturn::SetOf(data_type)
+            if !is_table_row(schema, data_type) =>
+        {
+            data_type
+        }
+        _ => {
+            return Err(Error::InvalidQuery(format!(
+                "Function '{}' returns rows; select its columns with SELECT ... FROM {}(...)",
+                function.name, function.name
+            )))
+        }
+    };
+    let nullable = !function.strict || any_nullable(schema, ctx, args)?;
+    let rust_type = schema.rust_type(data_type);
+    Ok(if nullable {
+        rust_type.nullable()
+    } else {
+        rust_type
+    })
+}
+
+/// Whether a type is the row type of a table.
+fn is_table_row(schema: &Schema, data_type: &PostgresType) -> bool {
+    matches!(data_type, PostgresType::Custom(name) if schema.get_table(name).is_some())
+}
+
+/// Infer the type of a call to a function of an installed extension.
+///
+/// Extension functions are STRICT, returning NULL only for NULL arguments.
+fn extension_call_type(
+    schema: &Schema,
+    ctx: &ResolveContext,
+    name: &ObjectName,
+    args: &[&Expr],
+) -> Result<Option<RustType>> {
+    let first_arg = args
+        .first()
+        .and_then(|arg| expr_postgres_type(schema, ctx, arg));
+    let Some(data_type) =
+        schema.extension_function(&object_name_to_string(name), first_arg.as_ref())
+    else {
+        return Ok(None);
+    };
+    let rust_type = schema.rust_type(&data_type);
+    Ok(Some(if any_nullable(schema, ctx, args)? {
+        rust_type.nullable()
+    } else {
+        rust_type
+    }))
+}
+
+/// Whether any of the expressions can be NULL.
+fn any_nullable(schema: &Schema, ctx: &ResolveContext, exprs: &[&Expr]) -> Result<bool> {
+    for expr in exprs {
+        let (_, rust_type) = infer_expr_type(schema, ctx, expr)?;
+        if matches!(rust_type, RustType::Option(_)) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// The argument expressions of a function call, positional or named.
+fn call_args(args: &[FunctionArg]) -> Vec<&Expr> {
+    args.iter()
+        .filter_map(|arg| match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(expr))
+            | FunctionArg::Named {
+                arg: FunctionArgExpr::Expr(expr),
+                ..
+            }
+            | FunctionArg::ExprNamed {
+                arg: FunctionArgExpr::Expr(expr),
+                ..
+            } => Some(expr),
+            _ => None,
+        })
+        .collect()
+}
+
 /// Infer the type of an expression.
 fn infer_expr_type(
     schema: &Schema,
@@ -435,18 +1157,24 @@ fn infer_expr_type(
             let col_name = &ident.value;
 
             // First, try to find in CTEs
-            if let Some((table_alias, rust_type)) = find_column_in_ctes(ctx, col_name) {
-                let mut rust_type = rust_type;
+            if let Some((table_alias, col)) = find_column_in_ctes(ctx, col_name) {
+                let mut rust_type = col.rust_type.clone();
                 if ctx.is_nullable_table(&table_alias) {
                     rust_type = rust_type.nullable();
                 }
                 return Ok((col_name.clone(), rust_type));
             }
 
-            // Then try schema tables
-            let (table_alias, col) = find_column_in_tables(schema, ctx, col_name)?;
+            // Then try schema tables, then the columns of an unknown function
+            let found = find_column_in_tables(schema, ctx, col_name);
+            if let (Err(Error::UnknownColumn { .. }), Some(function)) =
+                (&found, ctx.unknown_function())
+            {
+                return Ok((col_name.clone(), unknown_function_type(function)));
+            }
+            let (table_alias, table, col) = found?;
 
-            let mut rust_type = col.data_type.to_rust_type();
+            let mut rust_type = schema.column_rust_type(table, col);
             if col.nullable || ctx.is_nullable_table(&table_alias) {
                 rust_type = rust_type.nullable();
             }
@@ -454,22 +1182,26 @@ fn infer_expr_type(
             Ok((col_name.clone(), rust_type))
         }
         Expr::CompoundIdentifier(idents) => {
-            // Qualified column reference: table.column
-            if idents.len() != 2 {
-                return Err(Error::InvalidQuery(format!(
-                    "Expected table.column, got {} parts",
-                    idents.len()
-                )));
-            }
-
-            let table_alias = &idents[0].value;
-            let col_name = &idents[1].value;
+            // Qualified column reference: table.column or schema.table.column
+            let (table_alias, col_name) = match idents.as_slice() {
+                [table, column] | [_, table, column] => (&table.value, &column.value),
+                _ => {
+                    return Err(Error::InvalidQuery(format!(
+                        "Expected table.column, got {} parts",
+                        idents.len()
+                    )))
+                }
+            };
 
             let table_ref = ctx
                 .table_aliases
                 .get(&table_alias.to_lowercase())
                 .ok_or_else(|| Error::UnknownTable(table_alias.clone()))?;
 
+            if let Some(function) = table_ref.strip_prefix("_function:") {
+                return Ok((col_name.clone(), unknown_function_type(function)));
+            }
+
             // Check if this is a CTE reference
             if let Some(cte_name) = table_ref.strip_prefix("_cte:") {
                 // Look up the column in the CTE definition
@@ -506,7 +1238,7 @@ fn infer_expr_type(
                     column: col_name.clone(),
                 })?;
 
-            let mut rust_type = col.data_type.to_rust_type();
+            let mut rust_type = schema.column_rust_type(table, col);
             if col.nullable || ctx.is_nullable_table(table_alias) {
                 rust_type = rust_type.nullable();
             }
@@ -590,12 +1322,22 @@ fn infer_expr_type(
                     "to_timestamp" => RustType::DateTime,
                     "current_date" => RustType::Date,
                     "current_time" => RustType::Time,
-                    "current_timestamp" | "localtimestamp" | "localtime" => RustType::DateTime,
+                    "current_timestamp" => RustType::DateTime,
+                    "localtimestamp" => PostgresType::Timestamp.to_rust_type(),
+                    "localtime" => RustType::Time,
                     "make_date" => RustType::Date,
                     "make_time" => RustType::Time,
-                    "make_timestamp" | "make_timestamptz" => RustType::DateTime,
+                    "make_timestamp" => PostgresType::Timestamp.to_rust_type(),
+                    "make_timestamptz" => RustType::DateTime,
                     "make_interval" => RustType::Duration,
 
+                    // Sequence functions
+                    "nextval" | "currval" | "setval" => {
+                        check_sequence_call(schema, func)?;
+                        RustType::I64
+                    }
+                    "lastval" => RustType::I64,
+
                     // Window functions that return integers (ranking functions)
                     "row_number" | "rank" | "dense_rank" | "ntile" | "cume_dist"
                     | "percent_rank" => RustType::I64,
@@ -627,7 +1369,17 @@ fn infer_expr_type(
                         }
                     }
 
-                    _ => RustType::Custom(func_name.clone()),
+                    _ => {
+                        let args = match &func.args {
+                            FunctionArguments::List(list) => call_args(&list.args),
+                            _ => Vec::new(),
+                        };
+                        match resolve_function(schema, ctx, &func.name, &args)? {
+                            Some(function) => function_call_type(schema, ctx, function, &args)?,
+                            None => extension_call_type(schema, ctx, &func.name, &args)?
+                                .unwrap_or_else(|| RustType::Custom(func_name.clone())),
+                        }
+                    }
                 };
 
             Ok((func_name, rust_type))
@@ -647,8 +1399,10 @@ fn infer_expr_type(
             expr, data_type, ..
         } => {
             // CAST changes the type
-            let rust_type =
-                crate::types::PostgresType::from_sql_name(&format!("{}", data_type)).to_rust_type();
+            let target =
+                schema.resolve_type(PostgresType::from_sql_name(&format!("{}", data_type)));
+            check_cast_literal(schema, &target, expr)?;
+            let rust_type = schema.rust_type(&target);
             let (name, _) = infer_expr_type(schema, ctx, expr)?;
             Ok((name, rust_type))
         }
@@ -662,6 +1416,29 @@ fn infer_expr_type(
                     // These operators return boolean
                     Ok(("?column?".to_string(), RustType::Bool))
                 }
+                BinaryOperator::Question        // ? (jsonb/hstore has key)
+                | BinaryOperator::QuestionAnd   // ?& (has all keys)
+                | BinaryOperator::QuestionPipe  // ?| (has any key)
+                | BinaryOperator::PGRegexMatch  // ~ (regex match, ltree ~ lquery)
+                | BinaryOperator::PGRegexIMatch
+                | BinaryOperator::PGRegexNotMatch
+                | BinaryOperator::PGRegexNotIMatch
+                => Ok(("?column?".to_string(), RustType::Bool)),
+                BinaryOperator::LtDashGt => {
+                    // <-> (PostGIS distance)
+                    Ok(("?column?".to_string(), RustType::F64))
+                }
+                BinaryOperator::Arrow => {
+                    let (name, left_type) = infer_expr_type(schema, ctx, left)?;
+                    match left_type {
+                        // hstore -> key is NULL when the key is missing
+                        RustType::Hstore => Ok((name, RustType::String.nullable())),
+                        RustType::Option(inner) if *inner == RustType::Hstore => {
+                            Ok((name, RustType::String.nullable()))
+                        }
+                        other => Ok((name, other)),
+                    }
+                }
                 _ => {
                     // For other binary ops, infer from left side (simplification)
                     infer_expr_type(schema, ctx, left)
@@ -746,9 +1523,56 @@ fn get_first_arg_type(
     }
 }
 
+/// Check that the sequence named in `nextval('seq')`, `currval` or `setval` exists.
+///
+/// Only literal names can be checked, not `nextval($1)`.
+fn check_sequence_call(schema: &Schema, func: &sqlparser::ast::Function) -> Result<()> {
+    let FunctionArguments::List(list) = &func.args else {
+        return Ok(());
+    };
+    let Some(FunctionArg::Unnamed(FunctionArgExpr::Expr(arg))) = list.args.first() else {
+        return Ok(());
+    };
+    // `'public.seq'`, or `'public.seq'::regclass` as pg_dump writes it
+    let Expr::Value(value) = strip_casts(arg) else {
+        return Ok(());
+    };
+    let Value::SingleQuotedString(name) = &value.value else {
+        return Ok(());
+    };
+
+    let name = name.replace('"', "");
+    if schema.get_sequence(&name).is_none() {
+        return Err(Error::UnknownSequence(name));
+    }
+    Ok(())
+}
+
+/// Check the sequence of a value written by INSERT or UPDATE, like
+/// `VALUES (nextval('invoice_numbers'))`.
+fn check_sequence_value(schema: &Schema, expr: &Expr) -> Result<()> {
+    match expr {
+        Expr::Function(func)
+            if matches!(
+                unqualified_name(&object_name_to_string(&func.name))
+                    .to_lowercase()
+                    .as_str(),
+                "nextval" | "currval" | "setval"
+            ) =>
+        {
+            check_sequence_call(schema, func)
+        }
+        Expr::Nested(inner) => check_sequence_value(schema, inner),
+        _ => Ok(()),
+    }
+}
+
 /// Find a column in CTE definitions.
-fn find_column_in_ctes(ctx: &ResolveContext, col_name: &str) -> Option<(String, RustType)> {
-    let mut found: Option<(String, RustType)> = None;
+fn find_column_in_ctes<'a>(
+    ctx: &'a ResolveContext,
+    col_name: &str,
+) -> Option<(String, &'a QueryColumn)> {
+    let mut found: Option<(String, &QueryColumn)> = None;
 
     for (alias, table_ref) in &ctx.table_aliases {
         if let Some(cte_name) = table_ref.strip_prefix("_cte:") {
@@ -763,7 +1587,7 @@ fn find_column_in_ctes(ctx: &ResolveContext, col_name: &str) -> Option<(String,
                         // (though it won't find anything, leading to proper error)
                         return None;
                     }
-                    found = Some((alias.clone(), col.rust_type.clone()));
+                    found = Some((alias.clone(), col));
                 }
             }
         }
@@ -777,8 +1601,8 @@ fn find_column_in_tables<'a>(
     schema: &'a Schema,
     ctx: &ResolveContext,
     col_name: &str,
-) -> Result<(String, &'a crate::schema::Column)> {
-    let mut found: Option<(String, &crate::schema::Column)> = None;
+) -> Result<(String, &'a Table, &'a crate::schema::Column)> {
+    let mut found = None;
 
     for (alias, table_name) in &ctx.table_aliases {
         // Skip CTE references
@@ -791,7 +1615,7 @@ fn find_column_in_tables<'a>(
                 if found.is_some() {
                     return Err(Error::AmbiguousColumn(col_name.to_string()));
                 }
-                found = Some((alias.clone(), col));
+                found = Some((alias.clone(), table, col));
             }
         }
     }
@@ -802,75 +1626,421 @@ fn find_column_in_tables<'a>(
     })
 }
 
-/// Validate an INSERT statement.
-fn validate_insert(schema: &Schema, insert: &sqlparser::ast::Insert) -> Result<QueryResult> {
-    let table_name = insert.table.to_string();
-
-    // Verify table exists
-    let table = schema
-        .get_table(&table_name)
-        .ok_or_else(|| Error::UnknownTable(table_name.clone()))?;
+/// Check literals compared with columns (`status = 'x'`, `status IN (...)`)
+/// against the columns' CHECK constraint value lists.
+///
+/// Only columns that resolve unambiguously to a table are checked. Negated
+/// comparisons (`<>`, `NOT IN`, `NOT (...)`) are valid SQL that is always
+/// true for other values, so they aren't checked against the CHECK list.
+/// Literals compared with an enum column by any operator must still be
+/// labels of the enum.
+fn check_literal_values(schema: &Schema, ctx: &ResolveContext, expr: &Expr) -> Result<()> {
+    match expr {
+        Expr::BinaryOp { left, op, right } => match op {
+            BinaryOperator::Eq => {
+                check_column_literal(schema, ctx, left, right)?;
+                check_column_literal(schema, ctx, right, left)
+            }
+            // Any literal compared with an enum must be one of its labels
+            BinaryOperator::NotEq
+            | BinaryOperator::Lt
+            | BinaryOperator::LtEq
+            | BinaryOperator::Gt
+            | BinaryOperator::GtEq => {
+                check_enum_literal(schema, ctx, left, right)?;
+                check_enum_literal(schema, ctx, right, left)
+            }
+            BinaryOperator::And | BinaryOperator::Or => {
+                check_literal_values(schema, ctx, left)?;
+                check_literal_values(schema, ctx, right)
+            }
+            _ => Ok(()),
+        },
+        Expr::InList {
+            expr,
+            list,
+            negated: false,
+        } => list
+            .iter()
+            .try_for_each(|item| check_column_literal(schema, ctx, expr, item)),
+        Expr::InList {
+            expr,
+            list,
+            negated: true,
+        } => list
+            .iter()
+            .try_for_each(|item| check_enum_literal(schema, ctx, expr, item)),
+        Expr::Nested(inner) => check_literal_values(schema, ctx, inner),
+        _ => Ok(()),
+    }
+}
 
-    // Verify columns exist
-    for col_ident in &insert.columns {
-        let col_name = &col_ident.value;
-        if !table.has_column(col_name) {
-            return Err(Error::UnknownColumn {
-                table: table_name.clone(),
-                column: col_name.clone(),
-            });
-        }
+/// Check a literal compared with a column reference.
+fn check_column_literal(
+    schema: &Schema,
+    ctx: &ResolveContext,
+    column: &Expr,
+    value: &Expr,
+) -> Result<()> {
+    let Some(value) = literal_value(value) else {
+        return Ok(());
+    };
+    match resolve_column(schema, ctx, column) {
+        Some((table, column)) => check_allowed_value(schema, table, &column, &value),
+        None => Ok(()),
     }
+}
 
-    // If there's a RETURNING clause, infer those types
-    if let Some(returning) = &insert.returning {
-        let mut ctx = ResolveContext::default();
-        ctx.table_aliases
-            .insert(table_name.to_lowercase(), table_name.clone());
+/// Check a literal compared with an enum column against the enum's labels.
+///
+/// Unlike [`check_column_literal`], a `CHECK` list is ignored: `status <> 'x'`
+/// is fine for a value the constraint forbids, but Postgres rejects any
+/// literal that isn't a label when converting it to the enum.
+fn check_enum_literal(
+    schema: &Schema,
+    ctx: &ResolveContext,
+    column: &Expr,
+    value: &Expr,
+) -> Result<()> {
+    let Some(value) = literal_value(value) else {
+        return Ok(());
+    };
+    let Some((table, column)) = resolve_column(schema, ctx, column) else {
+        return Ok(());
+    };
+    match table.get_column(&column).map(|col| &col.data_type) {
+        Some(PostgresType::Enum(name)) => check_enum_label(schema, name, &value),
+        _ => Ok(()),
+    }
+}
 
-        let mut columns = Vec::new();
-        for item in returning {
-            match item {
-                SelectItem::UnnamedExpr(expr) => {
-                    let (name, rust_type) = infer_expr_type(schema, &ctx, expr)?;
-                    columns.push(QueryColumn { name, rust_type });
-                }
-                SelectItem::ExprWithAlias { expr, alias } => {
-                    let (_, rust_type) = infer_expr_type(schema, &ctx, expr)?;
-                    columns.push(QueryColumn {
-                        name: alias.value.clone(),
-                        rust_type,
-                    });
-                }
-                SelectItem::Wildcard(_) => {
-                    for col in &table.columns {
-                        let mut rust_type = col.data_type.to_rust_type();
-                        if col.nullable {
-                            rust_type = rust_type.nullable();
-                        }
-                        columns.push(QueryColumn {
-                            name: col.name.clone(),
-                            rust_type,
-                        });
-                    }
-                }
-                _ => {}
-            }
-        }
+/// Check whether an expression is the DEFAULT keyword (`VALUES (DEFAULT)`, `SET col = DEFAULT`).
+fn is_default(expr: &Expr) -> bool {
+    matches!(expr, Expr::Identifier(ident)
+        if ident.quote_style.is_none() && ident.value.eq_ignore_ascii_case("DEFAULT"))
+}
 
-        return Ok(QueryResult { columns });
+/// Build the error for a write to a column that can't be written.
+fn read_only_column(table: &Table, column: &Column, reason: &str) -> Error {
+    Error::ReadOnlyColumn {
+        table: table.name.clone(),
+        column: column.name.clone(),
+        reason: reason.to_string(),
     }
+}
 
-    // No RETURNING - return empty result
-    Ok(QueryResult { columns: vec![] })
+/// Check a value against a column's `CHECK (col IN (...))` list, if it has
+/// one, and against the labels of an enum column.
+///
+/// Values of numeric columns are compared as numbers (`1.0` and `01` are `1`).
+fn check_allowed_value(schema: &Schema, table: &Table, column: &str, value: &str) -> Result<()> {
+    let data_type = table.get_column(column).map(|col| &col.data_type);
+    if let Some(PostgresType::Enum(name)) = data_type {
+        check_enum_label(schema, name, value)?;
+    }
+    let numeric = data_type.is_some_and(is_numeric);
+    let same = |allowed: &String| match (allowed.parse::<f64>(), value.parse::<f64>()) {
+        (Ok(a), Ok(b)) if numeric => a == b,
+        _ => allowed == value,
+    };
+    match table.allowed_values(column) {
+        Some(allowed) if !allowed.iter().any(same) => Err(Error::InvalidValue {
+            table: table.name.clone(),
+            column: column.to_string(),
+            value: value.to_string(),
+            allowed: allowed.join(", "),
+        }),
+        _ => Ok(()),
+    }
 }
 
-/// Validate an UPDATE statement.
-fn validate_update(schema: &Schema, update: &Update) -> Result<QueryResult> {
-    // Get table name from the UPDATE target
-    let table_name = extract_table_name_from_table_with_joins(&update.table)?;
+/// Check a literal cast to an enum (`'happy'::mood`) against the enum's labels.
+fn check_cast_literal(schema: &Schema, target: &PostgresType, expr: &Expr) -> Result<()> {
+    match (target, literal_value(expr)) {
+        (PostgresType::Enum(name), Some(value)) => check_enum_label(schema, name, &value),
+        _ => Ok(()),
+    }
+}
 
-    // Verify table exists
+/// Check that a value is one of an enum's labels.
+fn check_enum_label(schema: &Schema, type_name: &str, value: &str) -> Result<()> {
+    match schema.get_enum(type_name) {
+        Some(enum_type) if !enum_type.labels.iter().any(|label| label == value) => {
+            Err(Error::InvalidEnumValue {
+                type_name: enum_type.name.clone(),
+                value: value.to_string(),
+                allowed: enum_type.labels.join(", "),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Whether values of a type are numbers.
+fn is_numeric(data_type: &PostgresType) -> bool {
+    matches!(
+        data_type,
+        PostgresType::SmallInt
+            | PostgresType::Integer
+            | PostgresType::BigInt
+            | PostgresType::Real
+            | PostgresType::DoublePrecision
+            | PostgresType::Numeric
+    )
+}
+
+/// The table and column a column reference resolves to, if it is unambiguous.
+fn resolve_column<'a>(
+    schema: &'a Schema,
+    ctx: &ResolveContext,
+    expr: &Expr,
+) -> Option<(&'a Table, String)> {
+    match expr {
+        Expr::Identifier(ident) => {
+            if find_column_in_ctes(ctx, &ident.value).is_some() {
+                return None;
+            }
+            let (alias, _, _) = find_column_in_tables(schema, ctx, &ident.value).ok()?;
+            let table = schema.get_table(ctx.table_aliases.get(&alias)?)?;
+            Some((table, ident.value.clone()))
+        }
+        Expr::CompoundIdentifier(idents) => match idents.as_slice() {
+            [table, column] | [_, table, column] => {
+                let table =
+                    schema.get_table(ctx.table_aliases.get(&table.value.to_lowercase())?)?;
+                Some((table, column.value.clone()))
+            }
+            _ => None,
+        },
+        Expr::Nested(inner) | Expr::Cast { expr: inner, .. } => resolve_column(schema, ctx, inner),
+        _ => None,
+    }
+}
+
+/// The Postgres type of an expression, where it is known: a table column, a
+/// cast, or a call to an extension function.
+fn expr_postgres_type(schema: &Schema, ctx: &ResolveContext, expr: &Expr) -> Option<PostgresType> {
+    match expr {
+        Expr::Nested(inner) => expr_postgres_type(schema, ctx, inner),
+        Expr::Cast { data_type, .. } => {
+            Some(schema.resolve_type(PostgresType::from_sql_name(&format!("{}", data_type))))
+        }
+        Expr::Function(func) => {
+            let args = match &func.args {
+                FunctionArguments::List(list) => call_args(&list.args),
+                _ => Vec::new(),
+            };
+            let first_arg = args
+                .first()
+                .and_then(|arg| expr_postgres_type(schema, ctx, arg));
+            schema.extension_function(&object_name_to_string(&func.name), first_arg.as_ref())
+        }
+        _ => {
+            let (table, column) = resolve_column(schema, ctx, expr)?;
+            Some(table.get_column(&column)?.data_type.clone())
+        }
+    }
+}
+
+/// The table column (by [`Table::column_key`]) an expression selects
+/// unchanged, directly or through a CTE.
+fn column_source(schema: &Schema, ctx: &ResolveContext, expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Nested(inner) => column_source(schema, ctx, inner),
+        Expr::Identifier(ident) => match find_column_in_ctes(ctx, &ident.value) {
+            Some((_, col)) => col.source.clone(),
+            None => {
+                let (table, column) = resolve_column(schema, ctx, expr)?;
+                Some(table.column_key(&table.get_column(&column)?.name))
+            }
+        },
+        Expr::CompoundIdentifier(idents) => match idents.as_slice() {
+            [table, column] | [_, table, column] => {
+                let table_ref = ctx.table_aliases.get(&table.value.to_lowercase())?;
+                match table_ref.strip_prefix("_cte:") {
+                    Some(cte_name) => ctx
+                        .get_cte(cte_name)?
+                        .columns
+                        .iter()
+                        .find(|c| c.name.eq_ignore_ascii_case(&column.value))?
+                        .source
+                        .clone(),
+                    None => {
+                        let table = schema.get_table(table_ref)?;
+                        Some(table.column_key(&table.get_column(&column.value)?.name))
+                    }
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The comment of the table (or CTE) column an expression refers to.
+fn column_comment(schema: &Schema, ctx: &ResolveContext, expr: &Expr) -> Option<String> {
+    let cte_column = match expr {
+        Expr::Identifier(ident) => find_column_in_ctes(ctx, &ident.value).map(|(_, col)| col),
+        Expr::CompoundIdentifier(idents) => match idents.as_slice() {
+            [table, column] | [_, table, column] => ctx
+                .table_aliases
+                .get(&table.value.to_lowercase())
+                .and_then(|table_ref| table_ref.strip_prefix("_cte:"))
+                .and_then(|cte_name| ctx.get_cte(cte_name))
+                .and_then(|cte| {
+                    cte.columns
+                        .iter()
+                        .find(|c| c.name.eq_ignore_ascii_case(&column.value))
+                }),
+            _ => None,
+        },
+        _ => None,
+    };
+    match cte_column {
+        Some(col) => col.comment.clone(),
+        None => {
+            let (table, column) = resolve_column(schema, ctx, expr)?;
+            table.get_column(&column)?.comment.clone()
+        }
+    }
+}
+
+/// Validate an INSERT statement.
+fn validate_insert(
+    schema: &Schema,
+    insert: &sqlparser::ast::Insert,
+    overriding: Option<Overriding>,
+) -> Result<QueryResult> {
+    let table_name = insert.table.to_string();
+
+    // Verify table exists
+    let table = schema
+        .get_table(&table_name)
+        .ok_or_else(|| Error::UnknownTable(table_name.clone()))?;
+    let table_key = table.qualified_name();
+
+    // Verify columns exist
+    for col_ident in &insert.columns {
+        let col_name = &col_ident.value;
+        if !table.has_column(col_name) {
+            return Err(Error::UnknownColumn {
+                table: table_name.clone(),
+                column: col_name.clone(),
+            });
+        }
+    }
+
+    // Generated and GENERATED ALWAYS identity columns may only be set to DEFAULT.
+    // Without a column list, VALUES fills the table's columns in order.
+    let rows = match insert.source.as_ref().map(|q| q.body.as_ref()) {
+        Some(SetExpr::Values(values)) => Some(&values.rows),
+        _ => None,
+    };
+    let targets: Vec<&Column> = if insert.columns.is_empty() {
+        if rows.is_some() {
+            table.columns.iter().collect()
+        } else {
+            Vec::new()
+        }
+    } else {
+        insert
+            .columns
+            .iter()
+            .filter_map(|c| table.get_column(&c.value))
+            .collect()
+    };
+    for (idx, column) in targets.iter().enumerate() {
+        let Some(reason) = column.write_restriction(overriding.is_some()) else {
+            continue;
+        };
+        let only_defaults =
+            rows.is_some_and(|rows| rows.iter().all(|row| row.get(idx).is_none_or(is_default)));
+        if !only_defaults {
+            return Err(read_only_column(table, column, reason));
+        }
+    }
+
+    // Literal values must satisfy the columns' CHECK (col IN (...)) lists
+    if let Some(rows) = rows {
+        let columns: Vec<&str> = if insert.columns.is_empty() {
+            table.column_names().collect()
+        } else {
+            insert.columns.iter().map(|c| c.value.as_str()).collect()
+        };
+        for row in rows {
+            for value in row {
+                check_sequence_value(schema, value)?;
+            }
+            for (column, value) in columns.iter().zip(row) {
+                if let Some(value) = literal_value(value) {
+                    check_allowed_value(schema, table, column, &value)?;
+                }
+            }
+        }
+    }
+
+    // If there's a RETURNING clause, infer those types
+    if let Some(returning) = &insert.returning {
+        let mut ctx = ResolveContext::default();
+        ctx.table_aliases
+            .insert(table.name.to_lowercase(), table_key);
+
+        let mut columns = Vec::new();
+        for item in returning {
+            match item {
+                SelectItem::UnnamedExpr(expr) => {
+                    let (name, rust_type) = infer_expr_type(schema, &ctx, expr)?;
+                    let comment = column_comment(schema, &ctx, expr);
+                    let source = column_source(schema, &ctx, expr);
+                    columns.push(QueryColumn {
+                        name,
+                        rust_type,
+                        comment,
+                        source,
+                    });
+                }
+                SelectItem::ExprWithAlias { expr, alias } => {
+                    let (_, rust_type) = infer_expr_type(schema, &ctx, expr)?;
+                    let comment = column_comment(schema, &ctx, expr);
+                    let source = column_source(schema, &ctx, expr);
+                    columns.push(QueryColumn {
+                        name: alias.value.clone(),
+                        rust_type,
+                        comment,
+                        source,
+                    });
+                }
+                SelectItem::Wildcard(_) => {
+                    for col in &table.columns {
+                        let mut rust_type = schema.column_rust_type(table, col);
+                        if col.nullable {
+                            rust_type = rust_type.nullable();
+                        }
+                        columns.push(QueryColumn {
+                            name: col.name.clone(),
+                            rust_type,
+                            comment: col.comment.clone(),
+                            source: Some(table.column_key(&col.name)),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        return Ok(QueryResult { columns });
+    }
+
+    // No RETURNING - return empty result
+    Ok(QueryResult { columns: vec![] })
+}
+
+/// Validate an UPDATE statement.
+fn validate_update(schema: &Schema, update: &Update) -> Result<QueryResult> {
+    // Get table name from the UPDATE target
+    let table_name = extract_table_name_from_table_with_joins(&update.table)?;
+
+    // Verify table exists
     let table = schema
         .get_table(&table_name)
         .ok_or_else(|| Error::UnknownTable(table_name.clone()))?;
@@ -878,22 +2048,40 @@ fn validate_update(schema: &Schema, update: &Update) -> Result<QueryResult> {
     // Verify columns in SET clause exist
     for assignment in &update.assignments {
         let col_names = extract_assignment_target_columns(&assignment.target)?;
-        for col_name in col_names {
-            if !table.has_column(&col_name) {
+        for col_name in &col_names {
+            if !table.has_column(col_name) {
                 return Err(Error::UnknownColumn {
                     table: table_name.clone(),
-                    column: col_name,
+                    column: col_name.clone(),
                 });
             }
         }
+        for col_name in &col_names {
+            if let Some(column) = table.get_column(col_name) {
+                if let Some(reason) = column.write_restriction(false) {
+                    if !is_default(&assignment.value) {
+                        return Err(read_only_column(table, column, reason));
+                    }
+                }
+            }
+        }
+        check_sequence_value(schema, &assignment.value)?;
+        if let ([col_name], Some(value)) = (col_names.as_slice(), literal_value(&assignment.value))
+        {
+            check_allowed_value(schema, table, col_name, &value)?;
+        }
+    }
+
+    let mut ctx = ResolveContext::default();
+    ctx.table_aliases
+        .insert(table.name.to_lowercase(), table.qualified_name());
+
+    if let Some(selection) = &update.selection {
+        check_literal_values(schema, &ctx, selection)?;
     }
 
     // If there's a RETURNING clause, infer those types
     if let Some(returning) = &update.returning {
-        let mut ctx = ResolveContext::default();
-        ctx.table_aliases
-            .insert(table_name.to_lowercase(), table_name.clone());
-
         return infer_returning_types(schema, &ctx, table, returning);
     }
 
@@ -911,12 +2099,16 @@ fn validate_delete(schema: &Schema, delete: &Delete) -> Result<QueryResult> {
         .get_table(&table_name)
         .ok_or_else(|| Error::UnknownTable(table_name.clone()))?;
 
+    let mut ctx = ResolveContext::default();
+    ctx.table_aliases
+        .insert(table.name.to_lowercase(), table.qualified_name());
+
+    if let Some(selection) = &delete.selection {
+        check_literal_values(schema, &ctx, selection)?;
+    }
+
     // If there's a RETURNING clause, infer those types
     if let Some(returning) = &delete.returning {
-        let mut ctx = ResolveContext::default();
-        ctx.table_aliases
-            .insert(table_name.to_lowercase(), table_name.clone());
-
         return infer_returning_types(schema, &ctx, table, returning);
     }
 
@@ -927,12 +2119,7 @@ fn validate_delete(schema: &Schema, delete: &Delete) -> Result<QueryResult> {
 /// Extract table name from TableWithJoins.
 fn extract_table_name_from_table_with_joins(twj: &TableWithJoins) -> Result<String> {
     match &twj.relation {
-        TableFactor::Table { name, .. } => name
-            .0
-            .last()
-            .and_then(|part| part.as_ident())
-            .map(|i| i.value.clone())
-            .ok_or_else(|| Error::InvalidQuery("Empty table name".to_string())),
+        TableFactor::Table { name, .. } => Ok(object_name_to_string(name)),
         _ => Err(Error::InvalidQuery(
             "Complex table expressions not supported in UPDATE".to_string(),
         )),
@@ -1000,24 +2187,37 @@ fn infer_returning_types(
         match item {
             SelectItem::UnnamedExpr(expr) => {
                 let (name, rust_type) = infer_expr_type(schema, ctx, expr)?;
-                columns.push(QueryColumn { name, rust_type });
+                let comment = column_comment(schema, ctx, expr);
+                let source = column_source(schema, ctx, expr);
+                columns.push(QueryColumn {
+                    name,
+                    rust_type,
+                    comment,
+                    source,
+                });
             }
             SelectItem::ExprWithAlias { expr, alias } => {
                 let (_, rust_type) = infer_expr_type(schema, ctx, expr)?;
+                let comment = column_comment(schema, ctx, expr);
+                let source = column_source(schema, ctx, expr);
                 columns.push(QueryColumn {
                     name: alias.value.clone(),
                     rust_type,
+                    comment,
+                    source,
                 });
             }
             SelectItem::Wildcard(_) => {
                 for col in &table.columns {
-                    let mut rust_type = col.data_type.to_rust_type();
+                    let mut rust_type = schema.column_rust_type(table, col);
                     if col.nullable {
                         rust_type = rust_type.nullable();
                     }
                     columns.push(QueryColumn {
                         name: col.name.clone(),
                         rust_type,
+                        comment: col.comment.clone(),
+                        source: Some(table.column_key(&col.name)),
                     });
                 }
             }
@@ -2256,4 +3456,913 @@ mod tests {
         assert_eq!(result.columns[2].name, "row_num");
         assert_eq!(result.columns[2].rust_type, RustType::I64);
     }
+
+    // Enum type tests
+
+    #[test]
+    fn test_validate_enum_column() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy');
+
+            CREATE TABLE people (
+                id uuid NOT NULL,
+                current_mood mood NOT NULL,
+                previous_mood mood
+            );
+            "#,
+        )
+        .unwrap();
+
+        let result = validate_query(
+            &schema,
+            "SELECT current_mood, previous_mood, 'happy'::mood AS target FROM people",
+        )
+        .unwrap();
+
+        assert_eq!(result.columns.len(), 3);
+        assert_eq!(
+            result.columns[0].rust_type,
+            RustType::Enum("public.mood".to_string())
+        );
+        assert_eq!(
+            result.columns[1].rust_type,
+            RustType::Option(Box::new(RustType::Enum("public.mood".to_string())))
+        );
+        assert_eq!(
+            result.columns[2].rust_type,
+            RustType::Enum("public.mood".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate_enum_labels() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE TYPE mood AS ENUM ('sad', 'ok', 'happy');
+            CREATE TABLE people (id uuid NOT NULL, current_mood mood NOT NULL);
+            "#,
+        )
+        .unwrap();
+
+        for sql in [
+            "SELECT id FROM people WHERE current_mood = 'happy' OR current_mood IN ('ok', 'sad')",
+            "SELECT id FROM people WHERE current_mood <> 'sad' AND current_mood > 'ok'",
+            "SELECT id FROM people WHERE current_mood NOT IN ('ok', 'happy')",
+            "INSERT INTO people (id, current_mood) VALUES ($1, 'sad')",
+            "UPDATE people SET current_mood = 'ok'::mood",
+        ] {
+            assert!(validate_query(&schema, sql).is_ok(), "{sql}");
+        }
+
+        for sql in [
+            "SELECT id FROM people WHERE current_mood = 'bogus'",
+            "SELECT id FROM people p WHERE p.current_mood IN ('ok', 'bogus')",
+            "SELECT id FROM people WHERE current_mood <> 'bogus'",
+            "SELECT id FROM people WHERE current_mood < 'bogus'",
+            "SELECT id FROM people WHERE 'bogus' >= current_mood",
+            "SELECT id FROM people WHERE current_mood NOT IN ('bogus')",
+            "INSERT INTO people (id, current_mood) VALUES ($1, 'bogus')",
+            "UPDATE people SET current_mood = 'bogus'::mood",
+            "SELECT 'bogus'::mood AS m FROM people",
+            "SELECT CAST('bogus' AS mood) AS m FROM people",
+        ] {
+            let result = validate_query(&schema, sql);
+            assert!(
+                matches!(&result, Err(Error::InvalidEnumValue { value, type_name, .. })
+                    if value == "bogus" && type_name == "mood"),
+                "{sql}: {result:?}"
+            );
+        }
+    }
+
+    // View tests
+
+    #[test]
+    fn test_validate_select_from_view() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE TABLE users (
+                id uuid NOT NULL,
+                name text NOT NULL,
+                deleted_at timestamp with time zone
+            );
+
+            CREATE TABLE profiles (
+                user_id uuid NOT NULL,
+                bio text NOT NULL
+            );
+
+            CREATE VIEW public.user_bios AS
+             SELECT u.id, u.name, p.bio
+               FROM (public.users u
+                 LEFT JOIN public.profiles p ON ((p.user_id = u.id)))
+              WHERE (u.deleted_at IS NULL);
+            "#,
+        )
+        .unwrap();
+
+        let result = validate_query(
+            &schema,
+            "SELECT b.id, b.bio, p.user_id FROM user_bios b LEFT JOIN profiles p ON p.user_id = b.id",
+        )
+        .unwrap();
+
+        assert_eq!(result.columns.len(), 3);
+        assert_eq!(result.columns[0].rust_type, RustType::Uuid);
+        // Nullable inside the view because of its LEFT JOIN
+        assert_eq!(
+            result.columns[1].rust_type,
+            RustType::Option(Box::new(RustType::String))
+        );
+        assert_eq!(
+            result.columns[2].rust_type,
+            RustType::Option(Box::new(RustType::Uuid))
+        );
+
+        let result = validate_query(&schema, "SELECT * FROM user_bios").unwrap();
+        let names: Vec<_> = result.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "bio"]);
+
+        let result = validate_query(&schema, "SELECT missing FROM user_bios");
+        assert!(matches!(result, Err(Error::UnknownColumn { .. })));
+    }
+
+    // Schema-qualified name tests
+
+    #[test]
+    fn test_validate_schema_qualified_tables() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE SCHEMA billing;
+            CREATE TABLE users (id uuid NOT NULL, email text NOT NULL);
+            CREATE TABLE billing.users (id uuid NOT NULL, account_code text);
+            "#,
+        )
+        .unwrap();
+
+        let result = validate_query(
+            &schema,
+            "SELECT users.email, b.account_code, billing.users.id FROM public.users JOIN billing.users b ON b.id = users.id LEFT JOIN billing.users ON billing.users.id = b.id",
+        );
+        // billing.users appears twice without an alias
+        assert!(
+            matches!(&result, Err(Error::InvalidQuery(msg))
+                if msg == "Table name \"users\" specified more than once"),
+            "{result:?}"
+        );
+
+        let result = validate_query(
+            &schema,
+            "SELECT u.email, b.account_code FROM users u JOIN billing.users b ON b.id = u.id",
+        )
+        .unwrap();
+        assert_eq!(result.columns[0].rust_type, RustType::String);
+        assert_eq!(
+            result.columns[1].rust_type,
+            RustType::Option(Box::new(RustType::String))
+        );
+
+        let result = validate_query(
+            &schema,
+            "SELECT billing.users.account_code FROM billing.users",
+        )
+        .unwrap();
+        assert_eq!(result.columns[0].name, "account_code");
+
+        let result = validate_query(&schema, "SELECT account_code FROM users");
+        assert!(matches!(result, Err(Error::UnknownColumn { .. })));
+
+        let result = validate_query(
+            &schema,
+            "UPDATE billing.users SET account_code = 'x' RETURNING users.account_code",
+        )
+        .unwrap();
+        assert_eq!(result.columns.len(), 1);
+    }
+
+    // CHECK constraint tests
+
+    fn test_schema_with_checks() -> Schema {
+        Schema::from_sql(
+            r#"
+            CREATE TABLE orders (
+                id uuid NOT NULL,
+                status text NOT NULL CHECK (status IN ('pending', 'shipped')),
+                priority integer CHECK (priority IN (1, 2, 3)),
+                note text
+            );
+            CREATE TABLE shipments (id uuid NOT NULL, status text NOT NULL);
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_check_constraint_literals() {
+        let schema = test_schema_with_checks();
+
+        for sql in [
+            "SELECT id FROM orders WHERE status = 'pending'",
+            "SELECT id FROM orders WHERE 'shipped' <> status AND note = 'anything'",
+            "SELECT o.id FROM orders o WHERE o.status IN ('pending', 'shipped')",
+            "INSERT INTO orders (id, status) VALUES ($1, 'shipped')",
+            "UPDATE orders SET status = 'pending', note = 'x' WHERE status = 'shipped'",
+            "DELETE FROM orders WHERE status = 'pending'",
+            // Numbers are compared by value
+            "SELECT id FROM orders WHERE priority = 1.0 OR priority IN (02, 3)",
+            "UPDATE orders SET priority = '3'",
+            // Negated comparisons are always true for other values
+            "SELECT id FROM orders WHERE status <> 'zzz' AND status NOT IN ('zzz')",
+            "SELECT o.id FROM orders o WHERE NOT (o.status IN ('pending', 'lost'))",
+            // Ambiguous and unconstrained columns aren't checked
+            "SELECT o.id FROM orders o JOIN shipments s ON s.id = o.id WHERE s.status = 'lost'",
+        ] {
+            assert!(validate_query(&schema, sql).is_ok(), "{sql}");
+        }
+
+        for sql in [
+            "SELECT id FROM orders WHERE status = 'lost'",
+            "SELECT o.id FROM orders o WHERE o.status IN ('pending', 'lost')",
+            "INSERT INTO orders (id, status) VALUES ($1, 'lost')",
+            "UPDATE orders SET status = 'lost'",
+            "DELETE FROM orders WHERE status = 'lost'",
+        ] {
+            let result = validate_query(&schema, sql);
+            assert!(
+                matches!(&result, Err(Error::InvalidValue { value, .. }) if value == "lost"),
+                "{sql}: {result:?}"
+            );
+        }
+        assert!(matches!(
+            validate_query(&schema, "SELECT id FROM orders WHERE priority = 4.0"),
+            Err(Error::InvalidValue { .. })
+        ));
+    }
+
+    // Generated column tests
+
+    #[test]
+    fn test_validate_writes_to_generated_columns() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE TABLE people (
+                id bigint GENERATED ALWAYS AS IDENTITY,
+                legacy_id integer GENERATED BY DEFAULT AS IDENTITY,
+                height_cm numeric,
+                height_in numeric GENERATED ALWAYS AS (height_cm / 2.54) STORED
+            );
+            "#,
+        )
+        .unwrap();
+
+        for sql in [
+            "INSERT INTO people (height_cm) VALUES (180) RETURNING id, height_in",
+            "INSERT INTO people (legacy_id, height_cm) VALUES (7, 180)",
+            "INSERT INTO people (id, height_cm) OVERRIDING SYSTEM VALUE VALUES (1, 180)",
+            "INSERT INTO people (id, height_cm) overriding user value VALUES (1, 180)",
+            "INSERT INTO people (id, height_cm, height_in) VALUES (DEFAULT, 180, DEFAULT)",
+            "INSERT INTO people VALUES (DEFAULT, 7, 180)",
+            "INSERT INTO people DEFAULT VALUES",
+            "UPDATE people SET height_cm = 190, legacy_id = 8, height_in = DEFAULT",
+        ] {
+            assert!(validate_query(&schema, sql).is_ok(), "{sql}");
+        }
+
+        for (sql, column) in [
+            ("INSERT INTO people (id, height_cm) VALUES (1, 180)", "id"),
+            ("INSERT INTO people VALUES (1, 7, 180)", "id"),
+            ("INSERT INTO people (height_in) VALUES (70)", "height_in"),
+            (
+                "INSERT INTO people (height_in) OVERRIDING SYSTEM VALUE VALUES (70)",
+                "height_in",
+            ),
+            ("INSERT INTO people (id) SELECT legacy_id FROM people", "id"),
+            ("UPDATE people SET id = 2", "id"),
+            (
+                "UPDATE people SET height_in = 70 WHERE height_cm > 0",
+                "height_in",
+            ),
+        ] {
+            let result = validate_query(&schema, sql);
+            assert!(
+                matches!(&result, Err(Error::ReadOnlyColumn { column: c, .. }) if c == column),
+                "{sql}: {result:?}"
+            );
+        }
+
+        let result = validate_query(&schema, "SELECT id FROM people OVERRIDING SYSTEM VALUE");
+        assert!(matches!(result, Err(Error::InvalidQuery(_))));
+    }
+
+    #[test]
+    fn test_validate_serial_columns() {
+        let schema = Schema::from_sql(
+            "CREATE TABLE posts (id serial PRIMARY KEY, views bigserial, title text);",
+        )
+        .unwrap();
+
+        let result = validate_query(
+            &schema,
+            "INSERT INTO posts (title) VALUES ($1) RETURNING id, views",
+        )
+        .unwrap();
+        assert_eq!(result.columns[0].rust_type, RustType::I32);
+        assert_eq!(result.columns[1].rust_type, RustType::I64);
+    }
+
+    // User-defined function tests
+
+    fn test_schema_with_functions() -> Schema {
+        Schema::from_sql(
+            r#"
+            CREATE TABLE users (
+                id uuid NOT NULL,
+                name text NOT NULL,
+                nickname text
+            );
+
+            CREATE FUNCTION display_name(name text, nickname text) RETURNS text
+                LANGUAGE sql AS $$ SELECT coalesce(nickname, name) $$;
+            CREATE FUNCTION slugify(input text) RETURNS text
+                LANGUAGE sql IMMUTABLE STRICT AS $$ SELECT lower(input) $$;
+            CREATE FUNCTION search_users(query text) RETURNS SETOF users
+                LANGUAGE sql STABLE AS $$ SELECT * FROM users WHERE name ILIKE query $$;
+            CREATE FUNCTION user_counts() RETURNS TABLE(day date, signups bigint)
+                LANGUAGE sql AS $$ SELECT current_date, 0::bigint $$;
+            CREATE FUNCTION lucky_numbers(n integer) RETURNS SETOF integer STRICT
+                LANGUAGE sql AS $$ SELECT generate_series(1, n) $$;
+            CREATE FUNCTION find_user(user_id uuid) RETURNS users STRICT
+                LANGUAGE sql AS $$ SELECT * FROM users WHERE id = user_id $$;
+            CREATE FUNCTION pair(n integer, OUT x integer, OUT y text)
+                LANGUAGE sql AS $$ SELECT n, n::text $$;
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_user_function_calls() {
+        let schema = test_schema_with_functions();
+
+        let result = validate_query(
+            &schema,
+            "SELECT display_name(name, nickname), slugify(name) AS slug, slugify(nickname), public.slugify($1) FROM users",
+        )
+        .unwrap();
+        assert_eq!(result.columns[0].name, "display_name");
+        // Not STRICT, so it may return NULL for any input
+        assert_eq!(
+            result.columns[0].rust_type,
+            RustType::Option(Box::new(RustType::String))
+        );
+        // STRICT returns NULL only for NULL arguments
+        assert_eq!(result.columns[1].name, "slug");
+        assert_eq!(result.columns[1].rust_type, RustType::String);
+        assert_eq!(
+            result.columns[2].rust_type,
+            RustType::Option(Box::new(RustType::String))
+        );
+        assert_eq!(result.columns[3].rust_type, RustType::String);
+
+        let result = validate_query(&schema, "SELECT slugify(name, name) FROM users");
+        assert!(matches!(
+            result,
+            Err(Error::WrongArgumentCount { count: 2, .. })
+        ));
+
+        let result = validate_query(&schema, "SELECT slugify(missing) FROM users");
+        assert!(matches!(result, Err(Error::UnknownColumn { .. })));
+
+        // Functions returning rows can only be called in FROM
+        for sql in [
+            "SELECT user_counts()",
+            "SELECT pair(1)",
+            "SELECT search_users('a')",
+            "SELECT find_user(id) FROM users",
+        ] {
+            let result = validate_query(&schema, sql);
+            assert!(
+                matches!(result, Err(Error::InvalidQuery(_))),
+                "{sql}: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_function_overloads() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE TABLE items (n integer NOT NULL, label text NOT NULL);
+
+            CREATE FUNCTION add(a integer, b integer DEFAULT 1) RETURNS integer
+                LANGUAGE sql AS $$ SELECT a + b $$;
+            CREATE FUNCTION add(a text, b text) RETURNS text
+                LANGUAGE sql AS $$ SELECT a || b $$;
+            CREATE FUNCTION scale(a bigint) RETURNS bigint
+                LANGUAGE sql AS $$ SELECT a * 2 $$;
+            CREATE FUNCTION scale(a numeric) RETURNS numeric
+                LANGUAGE sql AS $$ SELECT a * 2 $$;
+            "#,
+        )
+        .unwrap();
+
+        let text = RustType::Option(Box::new(RustType::String));
+        let integer = RustType::Option(Box::new(RustType::I32));
+        for (sql, rust_type) in [
+            ("SELECT add('a', 'b')", &text),
+            ("SELECT add(label, 'b') FROM items", &text),
+            // Untyped parameters prefer text, as in Postgres
+            ("SELECT add($1, $2)", &text),
+            ("SELECT add(1, 2)", &integer),
+            ("SELECT add(n, 2) FROM items", &integer),
+            ("SELECT add(1)", &integer),
+            (
+                "SELECT scale(n::bigint) FROM items",
+                &RustType::I64.nullable(),
+            ),
+        ] {
+            let result = validate_query(&schema, sql).unwrap();
+            assert_eq!(&result.columns[0].rust_type, rust_type, "{sql}");
+        }
+
+        let result = validate_query(&schema, "SELECT add(true, false)");
+        assert!(
+            matches!(result, Err(Error::NoMatchingFunction(_))),
+            "{result:?}"
+        );
+
+        // integer converts to both bigint and numeric
+        let result = validate_query(&schema, "SELECT scale(n) FROM items");
+        assert!(
+            matches!(result, Err(Error::AmbiguousFunction(_))),
+            "{result:?}"
+        );
+    }
+
+    #[test]
+    fn test_validate_set_returning_functions() {
+        let schema = test_schema_with_functions();
+
+        // SETOF a table's row type has the table's columns
+        let result = validate_query(&schema, "SELECT * FROM search_users($1)").unwrap();
+        let names: Vec<_> = result.columns.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["id", "name", "nickname"]);
+        assert_eq!(result.columns[0].rust_type, RustType::Uuid);
+        assert_eq!(
+            result.columns[2].rust_type,
+            RustType::Option(Box::new(RustType::String))
+        );
+
+        let result = validate_query(
+            &schema,
+            "SELECT u.name, c.signups FROM search_users('a') u CROSS JOIN user_counts() c",
+        )
+        .unwrap();
+        assert_eq!(result.columns[0].rust_type, RustType::String);
+        assert_eq!(
+            result.columns[1].rust_type,
+            RustType::Option(Box::new(RustType::I64))
+        );
+
+        // A scalar set is one column named after the alias
+        let result = validate_query(&schema, "SELECT n FROM lucky_numbers(3) AS n").unwrap();
+        assert_eq!(result.columns[0].rust_type, RustType::I32);
+        let result =
+            validate_query(&schema, "SELECT lucky FROM lucky_numbers(3) AS t (lucky)").unwrap();
+        assert_eq!(result.columns[0].rust_type, RustType::I32);
+
+        // A single row comes back all NULL when the function finds nothing
+        let result = validate_query(&schema, "SELECT * FROM find_user($1)").unwrap();
+        assert_eq!(
+            result.columns[0].rust_type,
+            RustType::Option(Box::new(RustType::Uuid))
+        );
+        assert_eq!(
+            result.columns[2].rust_type,
+            RustType::Option(Box::new(RustType::String))
+        );
+        let result = validate_query(&schema, "SELECT p.x, p.y FROM pair(1) p").unwrap();
+        assert_eq!(
+            result.columns[0].rust_type,
+            RustType::Option(Box::new(RustType::I32))
+        );
+
+        let result = validate_query(&schema, "SELECT missing FROM user_counts()");
+        assert!(matches!(result, Err(Error::UnknownColumn { .. })));
+
+        let result = validate_query(&schema, "SELECT * FROM user_counts(1)");
+        assert!(matches!(result, Err(Error::WrongArgumentCount { .. })));
+    }
+
+    #[test]
+    fn test_validate_builtin_set_returning_functions() {
+        let schema = Schema::from_sql(
+            "CREATE TABLE docs (id bigint NOT NULL, data jsonb NOT NULL, tags text[] NOT NULL);",
+        )
+        .unwrap();
+        let columns = |sql: &str| -> Vec<(String, RustType)> {
+            validate_query(&schema, sql)
+                .unwrap()
+                .columns
+                .into_iter()
+                .map(|c| (c.name, c.rust_type))
+                .collect()
+        };
+        let nullable = |ty: RustType| RustType::Option(Box::new(ty));
+
+        // Single-value functions name their column after the alias or themselves
+        assert_eq!(
+            columns("SELECT * FROM generate_series(1, 10)"),
+            [("generate_series".to_string(), RustType::I32)]
+        );
+        assert_eq!(
+            columns("SELECT n FROM docs, generate_series(1, docs.id) AS n"),
+            [("n".to_string(), RustType::I64)]
+        );
+        assert_eq!(
+            columns("SELECT d.id, t FROM docs d, unnest(d.tags) AS t"),
+            [
+                ("id".to_string(), RustType::I64),
+                ("t".to_string(), nullable(RustType::String))
+            ]
+        );
+        assert_eq!(
+            columns("SELECT * FROM unnest(ARRAY[1, 2]) WITH ORDINALITY"),
+            [
+                ("unnest".to_string(), nullable(RustType::I64)),
+                ("ordinality".to_string(), RustType::I64)
+            ]
+        );
+        assert_eq!(
+            columns("SELECT e.key, e.value FROM docs, jsonb_each(docs.data) e"),
+            [
+                ("key".to_string(), RustType::String),
+                ("value".to_string(), RustType::JsonValue)
+            ]
+        );
+        assert_eq!(
+            columns("SELECT k, v FROM docs, jsonb_each_text(data) AS e (k, v)"),
+            [
+                ("k".to_string(), RustType::String),
+                ("v".to_string(), nullable(RustType::String))
+            ]
+        );
+
+        let result = validate_query(&schema, "SELECT e.missing FROM jsonb_each('{}') e");
+        assert!(matches!(result, Err(Error::UnknownColumn { .. })));
+
+        // Columns of other functions are of unknown type
+        let unknown = nullable(RustType::Custom("json_populate_record".to_string()));
+        assert_eq!(
+            columns("SELECT r.a, b FROM json_populate_record(null, '{}') r"),
+            [
+                ("a".to_string(), unknown.clone()),
+                ("b".to_string(), unknown)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_sequence_functions() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE SCHEMA billing;
+            CREATE TABLE billing.invoices (id serial PRIMARY KEY, number bigint NOT NULL);
+            CREATE SEQUENCE billing.invoice_numbers START WITH 1000;
+            "#,
+        )
+        .unwrap();
+
+        let result = validate_query(
+            &schema,
+            "SELECT nextval('billing.invoice_numbers'), currval('billing.invoice_numbers'::regclass), setval('billing.invoices_id_seq', 10, false), lastval()",
+        )
+        .unwrap();
+        for column in &result.columns {
+            assert_eq!(column.rust_type, RustType::I64);
+        }
+        assert_eq!(result.columns[0].name, "nextval");
+
+        validate_query(
+            &schema,
+            "INSERT INTO billing.invoices (number) VALUES (nextval('billing.invoice_numbers'))",
+        )
+        .unwrap();
+        // Names that aren't literals can't be checked
+        validate_query(&schema, "SELECT nextval($1)").unwrap();
+
+        let result = validate_query(&schema, "SELECT nextval('invoice_numbers')");
+        assert!(matches!(result, Err(Error::UnknownSequence(_))));
+        let result = validate_query(
+            &schema,
+            "INSERT INTO billing.invoices (number) VALUES (nextval('billing.missing_seq'))",
+        );
+        assert!(matches!(result, Err(Error::UnknownSequence(_))));
+        let result = validate_query(
+            &schema,
+            "UPDATE billing.invoices SET number = nextval('missing_seq')",
+        );
+        assert!(matches!(result, Err(Error::UnknownSequence(_))));
+    }
+
+    #[test]
+    fn test_validate_column_comments() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE TABLE users (id integer PRIMARY KEY, email text NOT NULL, name text);
+            COMMENT ON COLUMN users.id IS 'Primary key';
+            COMMENT ON COLUMN users.email IS 'Login address';
+            CREATE VIEW user_emails AS SELECT id, email FROM users;
+            "#,
+        )
+        .unwrap();
+
+        let comments = |sql: &str| -> Vec<Option<String>> {
+            validate_query(&schema, sql)
+                .unwrap()
+                .columns
+                .into_iter()
+                .map(|c| c.comment)
+                .collect()
+        };
+        let id = Some("Primary key".to_string());
+        let email = Some("Login address".to_string());
+
+        assert_eq!(
+            comments("SELECT * FROM users"),
+            vec![id.clone(), email.clone(), None]
+        );
+        assert_eq!(
+            comments("SELECT u.id AS user_id, upper(u.email), name FROM users u"),
+            vec![id.clone(), None, None]
+        );
+        assert_eq!(
+            comments("WITH c AS (SELECT email FROM users) SELECT c.email, email FROM c"),
+            vec![email.clone(), email.clone()]
+        );
+        assert_eq!(
+            comments("SELECT * FROM user_emails"),
+            vec![id.clone(), email.clone()]
+        );
+        assert_eq!(
+            comments("UPDATE users SET name = 'x' RETURNING id, email"),
+            vec![id, email]
+        );
+    }
+
+    #[test]
+    fn test_validate_extension_types() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE EXTENSION hstore;
+            CREATE EXTENSION ltree;
+            CREATE TABLE pages (
+                id bigint NOT NULL,
+                path ltree NOT NULL,
+                attributes hstore,
+                tags hstore NOT NULL
+            );
+            "#,
+        )
+        .unwrap();
+        let string = || RustType::String;
+        let nullable = |t: RustType| t.nullable();
+
+        let result = validate_query(
+            &schema,
+            "SELECT path, attributes, tags -> 'color' AS color, nlevel(path) AS depth,
+                    akeys(tags) AS keys, akeys(attributes) AS maybe_keys,
+                    tags ? 'color' AS has_color, path ~ 'top.*' AS matches
+             FROM pages",
+        )
+        .unwrap();
+        let types: Vec<_> = result.columns.iter().map(|c| c.rust_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                string(),
+                nullable(RustType::Hstore),
+                nullable(string()),
+                RustType::I32,
+                RustType::Vec(Box::new(string())),
+                nullable(RustType::Vec(Box::new(string()))),
+                RustType::Bool,
+                RustType::Bool,
+            ]
+        );
+
+        // Functions of extensions that aren't installed stay unknown
+        let result = validate_query(&schema, "SELECT st_astext(path) AS shape FROM pages").unwrap();
+        assert_eq!(
+            result.columns[0].rust_type,
+            RustType::Custom("st_astext".to_string())
+        );
+
+        // Common names are only the extension's for arguments of its types
+        let result = validate_query(
+            &schema,
+            "SELECT index(path, 'a'::ltree) AS position, delete(tags, 'color') AS rest,
+                    index(id) AS other
+             FROM pages",
+        )
+        .unwrap();
+        let types: Vec<_> = result.columns.iter().map(|c| c.rust_type.clone()).collect();
+        assert_eq!(
+            types,
+            vec![
+                RustType::I32,
+                RustType::Hstore,
+                RustType::Custom("index".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_postgis_types() {
+        let schema = Schema::from_sql(
+            r#"
+            CREATE EXTENSION postgis;
+            CREATE TABLE places (
+                id bigint NOT NULL,
+                location geography NOT NULL,
+                outline geometry
+            );
+            "#,
+        )
+        .unwrap();
+
+        let result = validate_query(
+            &schema,
+            "SELECT st_area(location) AS area, st_srid(outline) AS srid FROM places",
+        )
+        .unwrap();
+        let types: Vec<_> = result.columns.iter().map(|c| c.rust_type.clone()).collect();
+        assert_eq!(types, vec![RustType::F64, RustType::I32.nullable()]);
+
+        // Geography functions stay geography
+        let ctx = ResolveContext::default();
+        let buffer = |sql: &str| {
+            let expr = Parser::new(&PostgreSqlDialect {})
+                .try_with_sql(sql)
+                .unwrap()
+                .parse_expr()
+                .unwrap();
+            expr_postgres_type(&schema, &ctx, &expr)
+        };
+        assert_eq!(
+            buffer("st_buffer('POINT(0 0)'::geography, 10)"),
+            Some(PostgresType::Geography)
+        );
+        assert_eq!(
+            buffer("st_centroid(st_buffer('POINT(0 0)'::geometry, 10))"),
+            Some(PostgresType::Geometry)
+        );
+
+        let result = validate_query(&schema, "SELECT id, location FROM places");
+        if cfg!(feature = "postgis") {
+            assert_eq!(result.unwrap().columns[1].rust_type, RustType::Geometry);
+        } else {
+            let err = result.unwrap_err().to_string();
+            assert!(err.contains("enable the postgis feature"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_validate_type_overrides() {
+        let mut schema = Schema::from_sql(
+            r#"
+            CREATE DOMAIN money_cents AS bigint;
+            CREATE TABLE users (id uuid PRIMARY KEY, name text NOT NULL);
+            CREATE TABLE orders (
+                id bigint PRIMARY KEY,
+                user_id uuid NOT NULL REFERENCES users(id),
+                total money_cents NOT NULL
+            );
+            "#,
+        )
+        .unwrap();
+        schema.add_type_override("users.id", "UserId").unwrap();
+        schema.add_type_override("money_cents", "Cents").unwrap();
+        let path = |path: &str| RustType::Path(path.to_string());
+        let types = |sql: &str| -> Vec<RustType> {
+            let result = validate_query(&schema, sql).unwrap();
+            result.columns.into_iter().map(|c| c.rust_type).collect()
+        };
+
+        // Joins, including the nullable side of an outer join
+        assert_eq!(
+            types(
+                "SELECT u.id, o.user_id, o.total, o2.total AS other
+                 FROM users u
+                 JOIN orders o ON o.user_id = u.id
+                 LEFT JOIN orders o2 ON o2.id = o.id"
+            ),
+            vec![
+                path("UserId"),
+                RustType::Uuid,
+                path("Cents"),
+                path("Cents").nullable(),
+            ]
+        );
+
+        // CTEs
+        assert_eq!(
+            types(
+                "WITH big AS (SELECT user_id, total FROM orders WHERE total > 100)
+                 SELECT u.id, big.total FROM big JOIN users u ON u.id = big.user_id"
+            ),
+            vec![path("UserId"), path("Cents")]
+        );
+
+        // RETURNING
+        assert_eq!(
+            types("INSERT INTO users (id, name) VALUES ($1, $2) RETURNING id, name"),
+            vec![path("UserId"), RustType::String]
+        );
+        assert_eq!(
+            types("UPDATE orders SET total = $1 WHERE id = $2 RETURNING total"),
+            vec![path("Cents")]
+        );
+    }
+
+    #[test]
+    fn test_validate_view_column_overrides() {
+        let mut schema = Schema::from_sql(
+            r#"
+            CREATE TABLE users (id uuid PRIMARY KEY, name text NOT NULL);
+            CREATE TABLE profiles (user_id uuid NOT NULL, bio text);
+            CREATE VIEW user_bios AS
+                SELECT u.id, p.user_id, u.id::text AS label, p.bio
+                FROM users u LEFT JOIN profiles p ON p.user_id = u.id;
+            CREATE VIEW bio_ids AS
+                WITH b AS (SELECT id FROM user_bios) SELECT id AS user_id FROM b;
+            "#,
+        )
+        .unwrap();
+        // Views are typed before the overrides are added
+        schema.add_type_override("users.id", "UserId").unwrap();
+        schema
+            .add_type_override("profiles.user_id", "UserId")
+            .unwrap();
+        let path = |path: &str| RustType::Path(path.to_string());
+
+        let result = validate_query(&schema, "SELECT * FROM user_bios").unwrap();
+        let types: Vec<_> = result.columns.into_iter().map(|c| c.rust_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                path("UserId"),
+                path("UserId").nullable(),
+                RustType::String,
+                RustType::String.nullable(),
+            ]
+        );
+
+        // Through views of views and their CTEs
+        let result = validate_query(&schema, "SELECT user_id FROM bio_ids").unwrap();
+        assert_eq!(result.columns[0].rust_type, path("UserId"));
+    }
+
+    #[test]
+    fn test_validate_view_type_overrides() {
+        let mut schema = Schema::from_sql(
+            r#"
+            CREATE DOMAIN money_cents AS bigint;
+            CREATE TABLE orders (
+                id bigint PRIMARY KEY,
+                total money_cents NOT NULL,
+                tip bigint
+            );
+            CREATE VIEW order_totals AS
+                SELECT id, total, tip, id::bigint AS number, count(*) OVER () AS orders
+                FROM orders;
+            "#,
+        )
+        .unwrap();
+        schema.add_type_override("bigint", "Cents").unwrap();
+        schema.add_type_override("money_cents", "Money").unwrap();
+        let path = |path: &str| RustType::Path(path.to_string());
+        let types = |sql: &str| -> Vec<RustType> {
+            let result = validate_query(&schema, sql).unwrap();
+            result.columns.into_iter().map(|c| c.rust_type).collect()
+        };
+
+        // Columns selected unchanged get the same types as from the table;
+        // computed columns keep the default types
+        assert_eq!(
+            types("SELECT * FROM order_totals"),
+            vec![
+                path("Cents"),
+                path("Money"),
+                path("Cents").nullable(),
+                RustType::I64,
+                RustType::I64,
+            ]
+        );
+        assert_eq!(
+            types("SELECT id, total, tip FROM orders"),
+            vec![path("Cents"), path("Money"), path("Cents").nullable()]
+        );
+    }
 }
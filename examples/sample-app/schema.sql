@@ -18,6 +18,16 @@ CREATE TABLE users (
     CONSTRAINT users_email_key UNIQUE (email)
 );
 
+--
+-- Name: TABLE users; Type: COMMENT
+--
+
+COMMENT ON TABLE users IS 'Registered accounts';
+
+COMMENT ON COLUMN users.email IS 'Login address, unique across users';
+
+COMMENT ON COLUMN users.metadata IS 'Free-form settings stored by the client';
+
 --
 -- Name: profiles; Type: TABLE
 --
@@ -115,6 +125,22 @@ CREATE TABLE order_items (
         REFERENCES products(id) ON DELETE RESTRICT
 );
 
+--
+-- Name: deliveries; Type: TABLE
+--
+
+CREATE TABLE deliveries (
+    id uuid NOT NULL,
+    order_id uuid NOT NULL,
+    status text NOT NULL DEFAULT 'pending',
+    priority integer NOT NULL DEFAULT 2,
+    CONSTRAINT deliveries_pkey PRIMARY KEY (id),
+    CONSTRAINT deliveries_priority_check CHECK ((priority = ANY (ARRAY[1, 2, 3]))),
+    CONSTRAINT deliveries_status_check CHECK ((status = ANY (ARRAY['pending'::text, 'in_transit'::text, 'delivered'::text, 'returned'::text]))),
+    CONSTRAINT deliveries_order_fkey FOREIGN KEY (order_id)
+        REFERENCES orders(id) ON DELETE CASCADE
+);
+
 --
 -- Name: idx_products_category; Type: INDEX
 --
@@ -132,3 +158,296 @@ CREATE INDEX idx_orders_user ON orders USING btree (user_id);
 --
 
 CREATE INDEX idx_order_items_order ON order_items USING btree (order_id);
+
+--
+-- Name: ticket_status; Type: TYPE
+--
+
+CREATE TYPE ticket_status AS ENUM ('open', 'in_progress', 'closed');
+
+--
+-- Name: visibility; Type: TYPE
+--
+
+CREATE TYPE visibility AS ENUM ('self', 'team', 'public');
+
+--
+-- Name: tickets; Type: TABLE
+--
+
+CREATE TABLE tickets (
+    id uuid NOT NULL,
+    title text NOT NULL,
+    status ticket_status NOT NULL DEFAULT 'open',
+    previous_status ticket_status,
+    created_at timestamp with time zone NOT NULL DEFAULT now(),
+    CONSTRAINT tickets_pkey PRIMARY KEY (id)
+);
+
+--
+-- Name: postal_address; Type: TYPE
+--
+
+CREATE TYPE postal_address AS (
+    street text,
+    city text,
+    postal_code text
+);
+
+--
+-- Name: warehouses; Type: TABLE
+--
+
+CREATE TABLE warehouses (
+    id uuid NOT NULL,
+    name text NOT NULL,
+    address postal_address NOT NULL,
+    CONSTRAINT warehouses_pkey PRIMARY KEY (id)
+);
+
+--
+-- Name: email_address; Type: DOMAIN
+--
+
+CREATE DOMAIN email_address AS text NOT NULL
+	CONSTRAINT email_address_check CHECK ((VALUE ~~ '%@%'::text));
+
+--
+-- Name: contacts; Type: TABLE
+--
+
+CREATE TABLE contacts (
+    id uuid NOT NULL,
+    email email_address,
+    CONSTRAINT contacts_pkey PRIMARY KEY (id)
+);
+
+--
+-- Name: user_profiles; Type: VIEW
+--
+
+CREATE VIEW user_profiles AS
+ SELECT users.id,
+    users.name,
+    profiles.bio
+   FROM (public.users
+     LEFT JOIN public.profiles ON ((profiles.user_id = users.id)));
+
+--
+-- Name: user_counts; Type: MATERIALIZED VIEW
+--
+
+CREATE MATERIALIZED VIEW user_counts AS
+ SELECT count(*) AS total
+   FROM public.users
+  WITH NO DATA;
+
+--
+-- Name: billing; Type: SCHEMA
+--
+
+CREATE SCHEMA billing;
+
+--
+-- Name: users; Type: TABLE; Schema: billing
+--
+
+CREATE TABLE billing.users (
+    id uuid NOT NULL,
+    account_code text NOT NULL,
+    CONSTRAINT users_pkey PRIMARY KEY (id)
+);
+
+--
+-- Name: invoices; Type: TABLE; Schema: billing
+--
+
+CREATE TABLE billing.invoices (
+    id uuid NOT NULL,
+    billing_user_id uuid NOT NULL,
+    amount_cents bigint NOT NULL,
+    paid_at timestamp with time zone,
+    CONSTRAINT invoices_pkey PRIMARY KEY (id)
+);
+
+--
+-- Name: invoice_numbers; Type: SEQUENCE; Schema: billing
+--
+
+CREATE SEQUENCE billing.invoice_numbers
+    START WITH 1000
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1;
+
+--
+-- Name: measurements; Type: TABLE
+--
+
+CREATE TABLE measurements (
+    id bigint NOT NULL,
+    label text NOT NULL,
+    celsius integer NOT NULL,
+    fahrenheit integer GENERATED ALWAYS AS ((((celsius * 9) / 5) + 32)) STORED,
+    CONSTRAINT measurements_pkey PRIMARY KEY (id)
+);
+
+ALTER TABLE measurements ALTER COLUMN id ADD GENERATED ALWAYS AS IDENTITY (
+    SEQUENCE NAME measurements_id_seq
+    START WITH 1
+    INCREMENT BY 1
+    NO MINVALUE
+    NO MAXVALUE
+    CACHE 1
+);
+
+--
+-- Name: events; Type: TABLE
+--
+
+CREATE TABLE events (
+    id bigint NOT NULL,
+    kind text NOT NULL,
+    payload jsonb,
+    occurred_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT events_kind_check CHECK ((kind <> ''::text))
+)
+PARTITION BY RANGE (occurred_at);
+
+--
+-- Name: events_2024; Type: TABLE
+--
+
+CREATE TABLE events_2024 (
+    id bigint NOT NULL,
+    kind text NOT NULL,
+    payload jsonb,
+    occurred_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT events_kind_check CHECK ((kind <> ''::text))
+);
+
+--
+-- Name: events_default; Type: TABLE
+--
+
+CREATE TABLE events_default PARTITION OF events DEFAULT;
+
+--
+-- Name: events_2024; Type: TABLE ATTACH
+--
+
+ALTER TABLE ONLY events ATTACH PARTITION events_2024 FOR VALUES FROM ('2024-01-01 00:00:00+00') TO ('2025-01-01 00:00:00+00');
+
+--
+-- Name: events events_pkey; Type: CONSTRAINT
+--
+
+ALTER TABLE ONLY events
+    ADD CONSTRAINT events_pkey PRIMARY KEY (id, occurred_at);
+
+ALTER TABLE ONLY events_2024
+    ADD CONSTRAINT events_2024_pkey PRIMARY KEY (id, occurred_at);
+
+ALTER TABLE ONLY events_default
+    ADD CONSTRAINT events_default_pkey PRIMARY KEY (id, occurred_at);
+
+--
+-- Name: events_kind_idx; Type: INDEX
+--
+
+CREATE INDEX events_kind_idx ON ONLY events USING btree (kind);
+
+CREATE INDEX events_2024_kind_idx ON events_2024 USING btree (kind);
+
+CREATE INDEX events_default_kind_idx ON events_default USING btree (kind);
+
+ALTER INDEX events_pkey ATTACH PARTITION events_2024_pkey;
+
+ALTER INDEX events_pkey ATTACH PARTITION events_default_pkey;
+
+ALTER INDEX events_kind_idx ATTACH PARTITION events_2024_kind_idx;
+
+ALTER INDEX events_kind_idx ATTACH PARTITION events_default_kind_idx;
+
+--
+-- Name: audit_log; Type: TABLE
+--
+
+CREATE TABLE audit_log (
+    id bigint NOT NULL,
+    message text NOT NULL,
+    logged_at timestamp with time zone DEFAULT now() NOT NULL,
+    CONSTRAINT audit_log_message_check CHECK ((message <> ''::text)),
+    CONSTRAINT audit_log_pkey PRIMARY KEY (id)
+);
+
+--
+-- Name: audit_log_archive; Type: TABLE
+--
+
+CREATE TABLE audit_log_archive (
+    archived_at timestamp with time zone NOT NULL
+)
+INHERITS (audit_log);
+
+--
+-- Name: citext; Type: EXTENSION
+--
+
+CREATE EXTENSION IF NOT EXISTS citext WITH SCHEMA public;
+
+--
+-- Name: hstore; Type: EXTENSION
+--
+
+CREATE EXTENSION IF NOT EXISTS hstore WITH SCHEMA public;
+
+--
+-- Name: ltree; Type: EXTENSION
+--
+
+CREATE EXTENSION IF NOT EXISTS ltree WITH SCHEMA public;
+
+--
+-- Name: pages; Type: TABLE
+--
+
+CREATE TABLE pages (
+    id bigint NOT NULL,
+    slug citext NOT NULL,
+    path ltree NOT NULL,
+    attributes hstore,
+    CONSTRAINT pages_pkey PRIMARY KEY (id),
+    CONSTRAINT pages_slug_key UNIQUE (slug)
+);
+
+--
+-- Name: display_label(text, text); Type: FUNCTION
+--
+
+CREATE FUNCTION display_label(label text, suffix text DEFAULT ''::text) RETURNS text
+    LANGUAGE sql IMMUTABLE STRICT
+    AS $$ SELECT upper(label) || suffix $$;
+
+--
+-- Name: measurements_above(integer); Type: FUNCTION
+--
+
+CREATE FUNCTION measurements_above(min_celsius integer) RETURNS SETOF measurements
+    LANGUAGE sql STABLE
+    AS $$ SELECT * FROM measurements WHERE celsius > min_celsius ORDER BY id $$;
+
+--
+-- Name: measurement_labels(); Type: FUNCTION
+--
+
+CREATE FUNCTION measurement_labels() RETURNS TABLE(label text, readings bigint)
+    LANGUAGE sql STABLE
+    AS $$ SELECT label, count(*) FROM measurements GROUP BY label $$;
+
+--
+-- Name: FUNCTION display_label(label text, suffix text); Type: COMMENT
+--
+
+COMMENT ON FUNCTION display_label(label text, suffix text) IS 'Label with an optional suffix';